@@ -0,0 +1,103 @@
+//! Compares heap memory of a catalog-sized list of FMRIs in `FMRIList` and `FMRIArena`
+//!
+//! Run with `cargo bench --bench catalog_memory`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use fmri::{fmri_list::arena::FMRIArena, FMRIList, FMRI};
+
+/// Number of packages in the catalog
+const PACKAGES: usize = 8_000;
+/// Versions of every package
+const VERSIONS: usize = 30;
+
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Returns FMRIs like in catalog of openindiana.org, with a few publishers
+fn catalog() -> Vec<FMRI> {
+    let categories = [
+        "library",
+        "system",
+        "developer",
+        "text",
+        "network",
+        "runtime",
+    ];
+    let publishers = ["openindiana.org", "openindiana.org", "hipster-encumbered"];
+    let mut fmris = Vec::with_capacity(PACKAGES * VERSIONS);
+    for package in 0..PACKAGES {
+        let category = categories[package % categories.len()];
+        let publisher = publishers[package % publishers.len()];
+        for version in 0..VERSIONS {
+            let raw_fmri = format!(
+                "pkg://{}/{}/package-{}@{}.{}.{},5.11-2023.0.0.{}:202309{:02}T{:02}{:02}00Z",
+                publisher,
+                category,
+                package,
+                package % 7,
+                version / 10,
+                version % 10,
+                version % 4,
+                1 + version % 28,
+                package % 24,
+                version % 60,
+            );
+            fmris.push(FMRI::parse_raw(&raw_fmri).unwrap());
+        }
+    }
+    fmris
+}
+
+/// Returns result and heap bytes it holds
+fn measure<T>(build: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    let value = build();
+    (value, ALLOCATED.load(Ordering::Relaxed) - before)
+}
+
+fn main() {
+    let fmris = catalog();
+    println!("catalog of {} FMRIs", fmris.len());
+
+    let start = Instant::now();
+    let (list, list_bytes) = measure(|| FMRIList::from(fmris.clone()));
+    println!(
+        "FMRIList:  {:>10} bytes, {:>6.1} bytes per FMRI, built in {:?}",
+        list_bytes,
+        list_bytes as f64 / list.len() as f64,
+        start.elapsed()
+    );
+
+    let start = Instant::now();
+    let (arena, arena_bytes) = measure(|| FMRIArena::from(&list));
+    println!(
+        "FMRIArena: {:>10} bytes, {:>6.1} bytes per FMRI, built in {:?}",
+        arena_bytes,
+        arena_bytes as f64 / arena.len() as f64,
+        start.elapsed()
+    );
+
+    let start = Instant::now();
+    let newest = arena.iter().max().unwrap();
+    println!("newest FMRI {} found in {:?}", newest, start.elapsed());
+    assert!(arena_bytes < list_bytes);
+}
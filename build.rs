@@ -0,0 +1,20 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    #[cfg(feature = "capi")]
+    generate_header();
+}
+
+/// Generates C header of the `capi` module into `$OUT_DIR/fmri.h`
+#[cfg(feature = "capi")]
+fn generate_header() {
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
+
+    // cbindgen reads the whole crate, not only src/capi.rs
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    cbindgen::generate(&crate_dir)
+        .expect("failed to generate C header")
+        .write_to_file(out_dir.join("fmri.h"));
+}
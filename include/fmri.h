@@ -0,0 +1,186 @@
+/* Generated by cbindgen from src/capi.rs, don't edit it manually */
+
+#ifndef FMRI_H
+#define FMRI_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * [`FMRI`] represents pkg fmri versioning system
+ *
+ * # Examples
+ *
+ * ```plain
+ * pkg:/audio/audacity
+ * pkg:/audio/audacity@2.3.2,5.11-2022.0.0.1
+ * pkg://solaris/system/library
+ * pkg://solaris/system/library@0.5.11-0.175.1.0.0.2.1:20120919T082311Z
+ * ```
+ */
+typedef struct fmri_t fmri_t;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Parses fmri like [`FMRI::parse_raw`]
+ *
+ * Returns NULL on error, the message is returned by [`fmri_last_error`]. Returned handle must be
+ * freed with [`fmri_free`].
+ *
+ * # Safety
+ *
+ * `raw_fmri` must be NULL or a nul-terminated string
+ */
+fmri_t *fmri_parse(const char *raw_fmri);
+
+/**
+ * Parses fmri like [`FMRI::parse_with_options`] with [`ParseOptions::strict`]
+ *
+ * Returns NULL on error, the message is returned by [`fmri_last_error`]. Returned handle must be
+ * freed with [`fmri_free`].
+ *
+ * # Safety
+ *
+ * `raw_fmri` must be NULL or a nul-terminated string
+ */
+fmri_t *fmri_parse_strict(const char *raw_fmri);
+
+/**
+ * Frees handle returned by [`fmri_parse`] or [`fmri_parse_strict`], NULL is ignored
+ *
+ * # Safety
+ *
+ * `fmri` must be NULL or a handle which wasn't freed yet
+ */
+void fmri_free(fmri_t *fmri);
+
+/**
+ * Returns fmri as string ("pkg://publisher/name@1.2-0"), NULL if `fmri` is NULL
+ *
+ * Returned string must be freed with [`fmri_string_free`].
+ *
+ * # Safety
+ *
+ * `fmri` must be NULL or a valid handle
+ */
+char *fmri_to_string(const fmri_t *fmri);
+
+/**
+ * Returns publisher ("openindiana.org"), NULL if there isn't publisher
+ *
+ * Returned string must be freed with [`fmri_string_free`].
+ *
+ * # Safety
+ *
+ * `fmri` must be NULL or a valid handle
+ */
+char *fmri_get_publisher(const fmri_t *fmri);
+
+/**
+ * Returns package name ("shell/bash"), NULL if `fmri` is NULL
+ *
+ * Returned string must be freed with [`fmri_string_free`].
+ *
+ * # Safety
+ *
+ * `fmri` must be NULL or a valid handle
+ */
+char *fmri_get_package_name(const fmri_t *fmri);
+
+/**
+ * Returns version without '@' ("5.2.15,5.11-2023.0.0.1:20230801T080000Z"), NULL if there isn't
+ * version
+ *
+ * Returned string must be freed with [`fmri_string_free`].
+ *
+ * # Safety
+ *
+ * `fmri` must be NULL or a valid handle
+ */
+char *fmri_get_version(const fmri_t *fmri);
+
+/**
+ * Returns component version ("5.2.15"), NULL if there isn't version
+ *
+ * Returned string must be freed with [`fmri_string_free`].
+ *
+ * # Safety
+ *
+ * `fmri` must be NULL or a valid handle
+ */
+char *fmri_get_component_version(const fmri_t *fmri);
+
+/**
+ * Returns build version ("5.11"), NULL if there isn't build version
+ *
+ * Returned string must be freed with [`fmri_string_free`].
+ *
+ * # Safety
+ *
+ * `fmri` must be NULL or a valid handle
+ */
+char *fmri_get_build_version(const fmri_t *fmri);
+
+/**
+ * Returns branch version ("2023.0.0.1"), NULL if there isn't branch version
+ *
+ * Returned string must be freed with [`fmri_string_free`].
+ *
+ * # Safety
+ *
+ * `fmri` must be NULL or a valid handle
+ */
+char *fmri_get_branch_version(const fmri_t *fmri);
+
+/**
+ * Returns timestamp ("20230801T080000Z"), NULL if there isn't timestamp
+ *
+ * Returned string must be freed with [`fmri_string_free`].
+ *
+ * # Safety
+ *
+ * `fmri` must be NULL or a valid handle
+ */
+char *fmri_get_timestamp(const fmri_t *fmri);
+
+/**
+ * Compares versions of fmris like [`Version::cmp_without_timestamp`], fmri without version is
+ * the oldest
+ *
+ * Returns -1, 0 or 1 if version of `a` is older, the same or newer than version of `b`. NULL
+ * handles are treated as fmris without version.
+ *
+ * # Safety
+ *
+ * `a` and `b` must be NULL or valid handles
+ */
+int fmri_version_cmp(const fmri_t *a, const fmri_t *b);
+
+/**
+ * Frees string returned by this library, NULL is ignored
+ *
+ * # Safety
+ *
+ * `string` must be NULL or a string returned by this library which wasn't freed yet
+ */
+void fmri_string_free(char *string);
+
+/**
+ * Returns message of the last failed call on this thread, NULL if no call failed
+ *
+ * The message is valid until the next failed call on this thread, it must not be freed. Panic
+ * inside this library fails the call with message starting with "panic: ".
+ */
+const char *fmri_last_error(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* FMRI_H */
@@ -0,0 +1,237 @@
+use std::cmp::Ordering;
+use std::io::{self, BufRead};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use serde_json::{json, Value};
+
+use fmri::{version::segments::Segments, FMRIList, ParseOptions, FMRI};
+
+/// Exit code used when the command line is invalid (sysexits EX_USAGE)
+const EXIT_USAGE: u8 = 64;
+/// Exit code used when some input isn't valid [`FMRI`] (sysexits EX_DATAERR)
+const EXIT_DATA: u8 = 65;
+/// Exit code used when reading of stdin failed (sysexits EX_IOERR)
+const EXIT_IO: u8 = 74;
+
+/// Parses, compares and filters IPS package identifiers (FMRIs)
+#[derive(Parser)]
+#[command(name = "fmri", version, about)]
+struct Cli {
+    /// Accepts only pkg(5) grammar, see `ParseOptions::strict`
+    #[arg(long, global = true)]
+    strict: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Prints components of FMRIs (from arguments or stdin)
+    Parse {
+        /// Prints components as JSON objects, one per line
+        #[arg(long)]
+        json: bool,
+        fmris: Vec<String>,
+    },
+    /// Compares versions of two FMRIs, timestamps are ignored
+    ///
+    /// Exit code is 0 if A equals B, 1 if A is older than B and 2 if A is newer than B.
+    Compare { a: String, b: String },
+    /// Sorts FMRIs from stdin by version, oldest first
+    Sort,
+    /// Prints newest FMRI of every package name from stdin
+    Latest,
+    /// Prints FMRIs from stdin matching the pattern, exit code is 1 if none matched
+    Match { pattern: String },
+    /// Checks FMRIs (from arguments or stdin), invalid ones are reported on stderr
+    Validate { fmris: Vec<String> },
+}
+
+fn main() -> ExitCode {
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(error) => {
+            let _ = error.print();
+            return if error.use_stderr() {
+                ExitCode::from(EXIT_USAGE)
+            } else {
+                ExitCode::SUCCESS
+            };
+        }
+    };
+
+    let options = match cli.strict {
+        true => ParseOptions::strict(),
+        false => ParseOptions::lenient(),
+    };
+
+    match run(cli.command, options) {
+        Ok(code) => code,
+        Err((code, message)) => {
+            eprintln!("fmri: {}", message);
+            ExitCode::from(code)
+        }
+    }
+}
+
+fn run(command: Command, options: ParseOptions) -> Result<ExitCode, (u8, String)> {
+    match command {
+        Command::Parse { json, fmris } => {
+            for fmri in &parse_all(arguments_or_stdin(fmris)?, options)? {
+                if json {
+                    println!("{}", to_json(fmri));
+                } else {
+                    print_components(fmri);
+                }
+            }
+        }
+        Command::Compare { a, b } => {
+            let a = parse_one(&a, None, options)?;
+            let b = parse_one(&b, None, options)?;
+            // timestamp doesn't make package newer, missing version is older
+            let ordering = match (a.get_version_ref(), b.get_version_ref()) {
+                (Some(a), Some(b)) => a.cmp_without_timestamp(b),
+                (a, b) => a.is_some().cmp(&b.is_some()),
+            };
+            let (symbol, code) = match ordering {
+                Ordering::Equal => ("=", 0),
+                Ordering::Less => ("<", 1),
+                Ordering::Greater => (">", 2),
+            };
+            println!("{}", symbol);
+            return Ok(ExitCode::from(code));
+        }
+        Command::Sort => {
+            let mut fmris = parse_all(stdin_lines()?, options)?.get();
+            fmris.sort_by(|a, b| a.get_version_ref().cmp(b.get_version_ref()));
+            fmris.iter().for_each(|fmri| println!("{}", fmri));
+        }
+        Command::Latest => {
+            for fmri in &parse_all(stdin_lines()?, options)?.latest() {
+                println!("{}", fmri);
+            }
+        }
+        Command::Match { pattern } => {
+            let mut matched = false;
+            for fmri in &parse_all(stdin_lines()?, options)? {
+                if fmri.matches_pattern(&pattern) {
+                    matched = true;
+                    println!("{}", fmri);
+                }
+            }
+            if !matched {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::Validate { fmris } => {
+            let mut valid = true;
+            for (position, raw_fmri) in &arguments_or_stdin(fmris)? {
+                if let Err((_, message)) = parse_one(raw_fmri, Some(position), options) {
+                    valid = false;
+                    eprintln!("{}", message);
+                }
+            }
+            if !valid {
+                return Ok(ExitCode::from(EXIT_DATA));
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Returns non-empty lines of stdin with their positions ("line 3") for error messages
+fn stdin_lines() -> Result<Vec<(String, String)>, (u8, String)> {
+    let mut lines = vec![];
+    for (index, line) in io::stdin().lock().lines().enumerate() {
+        let line = line.map_err(|e| (EXIT_IO, format!("can't read stdin: {}", e)))?;
+        if !line.trim().is_empty() {
+            lines.push((format!("line {}", index + 1), line.trim().to_owned()));
+        }
+    }
+    Ok(lines)
+}
+
+/// Returns arguments with their positions ("argument 2"), stdin lines if there are none
+fn arguments_or_stdin(arguments: Vec<String>) -> Result<Vec<(String, String)>, (u8, String)> {
+    if arguments.is_empty() {
+        return stdin_lines();
+    }
+    Ok(arguments
+        .into_iter()
+        .enumerate()
+        .map(|(index, argument)| (format!("argument {}", index + 1), argument))
+        .collect())
+}
+
+fn parse_one(
+    raw_fmri: &str,
+    position: Option<&str>,
+    options: ParseOptions,
+) -> Result<FMRI, (u8, String)> {
+    FMRI::parse_with_options(raw_fmri, options).map_err(|e| {
+        let message = match position {
+            None => format!("invalid fmri \"{}\": {}", raw_fmri, e),
+            Some(position) => format!("{}: invalid fmri \"{}\": {}", position, raw_fmri, e),
+        };
+        (EXIT_DATA, message)
+    })
+}
+
+fn parse_all(
+    raw_fmris: Vec<(String, String)>,
+    options: ParseOptions,
+) -> Result<FMRIList, (u8, String)> {
+    let mut list = FMRIList::new();
+    for (position, raw_fmri) in &raw_fmris {
+        list.add(parse_one(raw_fmri, Some(position), options)?);
+    }
+    Ok(list)
+}
+
+fn print_components(fmri: &FMRI) {
+    println!("fmri: {}", fmri);
+    println!(
+        "publisher: {}",
+        fmri.get_publisher_as_ref_string()
+            .map(String::as_str)
+            .unwrap_or_default()
+    );
+    println!("package_name: {}", fmri.get_package_name_as_ref_string());
+    match fmri.get_version_ref() {
+        None => {
+            for key in [
+                "component_version",
+                "build_version",
+                "branch_version",
+                "timestamp",
+            ] {
+                println!("{}: ", key);
+            }
+        }
+        Some(version) => {
+            println!("component_version: {}", version.get_component_version_ref());
+            println!("build_version: {}", version.get_build_version_ref());
+            println!("branch_version: {}", version.get_branch_version_ref());
+            println!("timestamp: {}", version.get_timestamp_ref());
+        }
+    }
+}
+
+fn to_json(fmri: &FMRI) -> Value {
+    let part = |segments: Option<&Segments>| match segments {
+        None | Some(Segments::None) => Value::Null,
+        Some(segments) => Value::String(segments.to_string()),
+    };
+    let version = fmri.get_version_ref().as_ref();
+
+    json!({
+        "fmri": fmri.to_string(),
+        "publisher": fmri.get_publisher_as_ref_string(),
+        "package_name": fmri.get_package_name_as_ref_string(),
+        "component_version": part(version.map(|v| v.get_component_version_ref())),
+        "build_version": part(version.map(|v| v.get_build_version_ref())),
+        "branch_version": part(version.map(|v| v.get_branch_version_ref())),
+        "timestamp": part(version.map(|v| v.get_timestamp_ref())),
+    })
+}
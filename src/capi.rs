@@ -0,0 +1,298 @@
+use std::{
+    cell::RefCell,
+    ffi::{c_char, c_int, CStr, CString},
+    panic::{self, UnwindSafe},
+    ptr,
+};
+
+use crate::{version::segments::Segments, version::Version, ParseOptions, FMRI};
+
+thread_local! {
+    /// Message of the last failed call on this thread
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(error: String) {
+    let error = CString::new(error.replace('\0', "\\0")).expect("nul characters are replaced");
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some(error));
+}
+
+/// Runs body of exported function, panic is reported as the last error and `default` is returned
+///
+/// Unwinding into C is undefined behavior.
+fn catch_panic<T>(default: T, body: impl FnOnce() -> T + UnwindSafe) -> T {
+    panic::catch_unwind(body).unwrap_or_else(|panic| {
+        let message = match panic.downcast_ref::<&str>() {
+            Some(message) => message.to_string(),
+            None => panic
+                .downcast_ref::<String>()
+                .cloned()
+                .unwrap_or_else(|| "unknown panic".to_owned()),
+        };
+        set_last_error(format!("panic: {}", message));
+        default
+    })
+}
+
+/// Returns [`FMRI`] behind handle or sets the last error if it is NULL
+unsafe fn handle<'a>(fmri: *const FMRI) -> Option<&'a FMRI> {
+    let fmri = fmri.as_ref();
+    if fmri.is_none() {
+        set_last_error("fmri is NULL".to_owned());
+    }
+    fmri
+}
+
+/// Returns string allocated for C, it must be freed with [`fmri_string_free`]
+fn to_c_string(string: String) -> *mut c_char {
+    match CString::new(string) {
+        Ok(string) => string.into_raw(),
+        Err(e) => {
+            set_last_error(e.to_string());
+            ptr::null_mut()
+        }
+    }
+}
+
+unsafe fn parse(raw_fmri: *const c_char, options: ParseOptions) -> *mut FMRI {
+    if raw_fmri.is_null() {
+        set_last_error("raw fmri is NULL".to_owned());
+        return ptr::null_mut();
+    }
+    let result = CStr::from_ptr(raw_fmri)
+        .to_str()
+        .map_err(|e| format!("raw fmri isn't valid UTF-8: {}", e))
+        .and_then(|raw_fmri| FMRI::parse_with_options(raw_fmri, options));
+    match result {
+        Ok(fmri) => Box::into_raw(Box::new(fmri)),
+        Err(e) => {
+            set_last_error(e);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Returns part of version as string, NULL if there isn't version or the part
+unsafe fn version_part(fmri: *const FMRI, part: fn(&Version) -> &Segments) -> *mut c_char {
+    match handle(fmri)
+        .and_then(|fmri| fmri.get_version_ref().as_ref())
+        .map(part)
+    {
+        None | Some(Segments::None) => ptr::null_mut(),
+        Some(segments) => to_c_string(segments.to_string()),
+    }
+}
+
+/// Parses fmri like [`FMRI::parse_raw`]
+///
+/// Returns NULL on error, the message is returned by [`fmri_last_error`]. Returned handle must be
+/// freed with [`fmri_free`].
+///
+/// # Safety
+///
+/// `raw_fmri` must be NULL or a nul-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn fmri_parse(raw_fmri: *const c_char) -> *mut FMRI {
+    catch_panic(ptr::null_mut(), || parse(raw_fmri, ParseOptions::lenient()))
+}
+
+/// Parses fmri like [`FMRI::parse_with_options`] with [`ParseOptions::strict`]
+///
+/// Returns NULL on error, the message is returned by [`fmri_last_error`]. Returned handle must be
+/// freed with [`fmri_free`].
+///
+/// # Safety
+///
+/// `raw_fmri` must be NULL or a nul-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn fmri_parse_strict(raw_fmri: *const c_char) -> *mut FMRI {
+    catch_panic(ptr::null_mut(), || parse(raw_fmri, ParseOptions::strict()))
+}
+
+/// Frees handle returned by [`fmri_parse`] or [`fmri_parse_strict`], NULL is ignored
+///
+/// # Safety
+///
+/// `fmri` must be NULL or a handle which wasn't freed yet
+#[no_mangle]
+pub unsafe extern "C" fn fmri_free(fmri: *mut FMRI) {
+    catch_panic((), || {
+        if !fmri.is_null() {
+            drop(Box::from_raw(fmri))
+        }
+    })
+}
+
+/// Returns fmri as string ("pkg://publisher/name@1.2-0"), NULL if `fmri` is NULL
+///
+/// Returned string must be freed with [`fmri_string_free`].
+///
+/// # Safety
+///
+/// `fmri` must be NULL or a valid handle
+#[no_mangle]
+pub unsafe extern "C" fn fmri_to_string(fmri: *const FMRI) -> *mut c_char {
+    catch_panic(ptr::null_mut(), || {
+        handle(fmri).map_or(ptr::null_mut(), |fmri| to_c_string(fmri.to_string()))
+    })
+}
+
+/// Returns publisher ("openindiana.org"), NULL if there isn't publisher
+///
+/// Returned string must be freed with [`fmri_string_free`].
+///
+/// # Safety
+///
+/// `fmri` must be NULL or a valid handle
+#[no_mangle]
+pub unsafe extern "C" fn fmri_get_publisher(fmri: *const FMRI) -> *mut c_char {
+    catch_panic(ptr::null_mut(), || {
+        match handle(fmri).and_then(FMRI::get_publisher_as_ref_string) {
+            None => ptr::null_mut(),
+            Some(publisher) => to_c_string(publisher.clone()),
+        }
+    })
+}
+
+/// Returns package name ("shell/bash"), NULL if `fmri` is NULL
+///
+/// Returned string must be freed with [`fmri_string_free`].
+///
+/// # Safety
+///
+/// `fmri` must be NULL or a valid handle
+#[no_mangle]
+pub unsafe extern "C" fn fmri_get_package_name(fmri: *const FMRI) -> *mut c_char {
+    catch_panic(ptr::null_mut(), || {
+        handle(fmri).map_or(ptr::null_mut(), |fmri| {
+            to_c_string(fmri.get_package_name_as_ref_string().clone())
+        })
+    })
+}
+
+/// Returns version without '@' ("5.2.15,5.11-2023.0.0.1:20230801T080000Z"), NULL if there isn't
+/// version
+///
+/// Returned string must be freed with [`fmri_string_free`].
+///
+/// # Safety
+///
+/// `fmri` must be NULL or a valid handle
+#[no_mangle]
+pub unsafe extern "C" fn fmri_get_version(fmri: *const FMRI) -> *mut c_char {
+    catch_panic(ptr::null_mut(), || {
+        match handle(fmri).and_then(|fmri| fmri.get_version_ref().as_ref()) {
+            None => ptr::null_mut(),
+            Some(version) => to_c_string(version.to_string()[1..].to_owned()),
+        }
+    })
+}
+
+/// Returns component version ("5.2.15"), NULL if there isn't version
+///
+/// Returned string must be freed with [`fmri_string_free`].
+///
+/// # Safety
+///
+/// `fmri` must be NULL or a valid handle
+#[no_mangle]
+pub unsafe extern "C" fn fmri_get_component_version(fmri: *const FMRI) -> *mut c_char {
+    catch_panic(ptr::null_mut(), || {
+        version_part(fmri, Version::get_component_version_ref)
+    })
+}
+
+/// Returns build version ("5.11"), NULL if there isn't build version
+///
+/// Returned string must be freed with [`fmri_string_free`].
+///
+/// # Safety
+///
+/// `fmri` must be NULL or a valid handle
+#[no_mangle]
+pub unsafe extern "C" fn fmri_get_build_version(fmri: *const FMRI) -> *mut c_char {
+    catch_panic(ptr::null_mut(), || {
+        version_part(fmri, Version::get_build_version_ref)
+    })
+}
+
+/// Returns branch version ("2023.0.0.1"), NULL if there isn't branch version
+///
+/// Returned string must be freed with [`fmri_string_free`].
+///
+/// # Safety
+///
+/// `fmri` must be NULL or a valid handle
+#[no_mangle]
+pub unsafe extern "C" fn fmri_get_branch_version(fmri: *const FMRI) -> *mut c_char {
+    catch_panic(ptr::null_mut(), || {
+        version_part(fmri, Version::get_branch_version_ref)
+    })
+}
+
+/// Returns timestamp ("20230801T080000Z"), NULL if there isn't timestamp
+///
+/// Returned string must be freed with [`fmri_string_free`].
+///
+/// # Safety
+///
+/// `fmri` must be NULL or a valid handle
+#[no_mangle]
+pub unsafe extern "C" fn fmri_get_timestamp(fmri: *const FMRI) -> *mut c_char {
+    catch_panic(ptr::null_mut(), || {
+        version_part(fmri, Version::get_timestamp_ref)
+    })
+}
+
+/// Compares versions of fmris like [`Version::cmp_without_timestamp`], fmri without version is
+/// the oldest
+///
+/// Returns -1, 0 or 1 if version of `a` is older, the same or newer than version of `b`. NULL
+/// handles are treated as fmris without version.
+///
+/// # Safety
+///
+/// `a` and `b` must be NULL or valid handles
+#[no_mangle]
+pub unsafe extern "C" fn fmri_version_cmp(a: *const FMRI, b: *const FMRI) -> c_int {
+    catch_panic(0, || {
+        let version = |fmri: *const FMRI| {
+            fmri.as_ref()
+                .and_then(|fmri| fmri.get_version_ref().as_ref())
+        };
+        match (version(a), version(b)) {
+            (Some(a), Some(b)) => a.cmp_without_timestamp(b) as c_int,
+            (a, b) => a.is_some().cmp(&b.is_some()) as c_int,
+        }
+    })
+}
+
+/// Frees string returned by this library, NULL is ignored
+///
+/// # Safety
+///
+/// `string` must be NULL or a string returned by this library which wasn't freed yet
+#[no_mangle]
+pub unsafe extern "C" fn fmri_string_free(string: *mut c_char) {
+    catch_panic((), || {
+        if !string.is_null() {
+            drop(CString::from_raw(string))
+        }
+    })
+}
+
+/// Returns message of the last failed call on this thread, NULL if no call failed
+///
+/// The message is valid until the next failed call on this thread, it must not be freed. Panic
+/// inside this library fails the call with message starting with "panic: ".
+#[no_mangle]
+pub extern "C" fn fmri_last_error() -> *const c_char {
+    catch_panic(ptr::null(), || {
+        LAST_ERROR.with(|last_error| {
+            last_error
+                .borrow()
+                .as_ref()
+                .map_or(ptr::null(), |error| error.as_ptr())
+        })
+    })
+}
@@ -0,0 +1,270 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+
+use crate::{
+    manifest::{action::Action, Manifest},
+    FMRI,
+};
+
+/// Attributes, which must match for refcounted `dir` actions
+const DIR_ATTRIBUTES: [&str; 3] = ["mode", "owner", "group"];
+
+/// [`Rule`] is pkg(5) rule violated by actions delivering the same path
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub enum Rule {
+    /// Path is delivered by different action types, like `file` and `dir`
+    ActionTypes(Vec<String>),
+    /// Path is delivered by more `file` actions without overlay attributes
+    DuplicateFile,
+    /// Overlaying needs exactly one `overlay=allow` and one `overlay=true` file
+    Overlay,
+    /// Refcounted `dir` actions have different value of attribute
+    Attribute(String),
+    /// `link` or `hardlink` actions have different targets
+    LinkTarget,
+    /// Mediated links must all have the same mediator
+    Mediator,
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ActionTypes(types) => {
+                write!(f, "delivered as different actions ({})", types.join(", "))
+            }
+            Self::DuplicateFile => write!(f, "file delivered more times without overlay"),
+            Self::Overlay => write!(
+                f,
+                "overlay needs one file with overlay=allow and one with overlay=true"
+            ),
+            Self::Attribute(attribute) => {
+                write!(f, "directories have different {}", attribute)
+            }
+            Self::LinkTarget => write!(f, "links have different targets"),
+            Self::Mediator => write!(f, "links have different mediators"),
+        }
+    }
+}
+
+/// [`Conflict`] is path delivered by incompatible actions
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct Conflict {
+    path: String,
+    rule: Rule,
+    fmris: Vec<FMRI>,
+}
+
+impl Conflict {
+    pub fn get_path_ref(&self) -> &String {
+        &self.path
+    }
+
+    pub fn get_rule_ref(&self) -> &Rule {
+        &self.rule
+    }
+
+    /// Returns packages delivering conflicting actions
+    pub fn get_fmris_ref(&self) -> &Vec<FMRI> {
+        &self.fmris
+    }
+}
+
+impl Display for Conflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let fmris: Vec<String> = self.fmris.iter().map(|fmri| fmri.to_string()).collect();
+        write!(f, "{}: {} by {}", self.path, self.rule, fmris.join(", "))
+    }
+}
+
+/// [`ConflictChecker`] finds paths delivered by incompatible actions of packages
+///
+/// Actions are compared only if they can be installed together, actions with different values
+/// of the same `variant.*` attribute never conflict.
+///
+/// # Examples
+///
+/// ```
+/// use fmri::{conflict::{ConflictChecker, Rule}, manifest::Manifest};
+/// let mut checker = ConflictChecker::new();
+/// for manifest in [
+///     "set name=pkg.fmri value=pkg:/shell/bash@5.2
+///      file path=usr/bin/sh mode=0555",
+///     "set name=pkg.fmri value=pkg:/shell/ksh93@93.21
+///      link path=usr/bin/sh target=ksh93",
+/// ] {
+///     checker.add_manifest(&Manifest::parse(manifest).unwrap()).unwrap();
+/// }
+///
+/// let conflicts = checker.check();
+/// assert_eq!(conflicts.len(), 1);
+/// assert_eq!(
+///     conflicts[0].get_rule_ref(),
+///     &Rule::ActionTypes(vec!["file".to_owned(), "link".to_owned()])
+/// );
+/// assert_eq!(
+///     conflicts[0].to_string(),
+///     "usr/bin/sh: delivered as different actions (file, link) by pkg:/shell/bash@5.2, pkg:/shell/ksh93@93.21"
+/// );
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct ConflictChecker {
+    paths: BTreeMap<String, Vec<(FMRI, Action)>>,
+}
+
+impl ConflictChecker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds actions with path of package [`Manifest`]
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if manifest doesn't have valid `pkg.fmri`
+    pub fn add_manifest(&mut self, manifest: &Manifest) -> Result<(), String> {
+        let fmri = manifest
+            .get_fmri()?
+            .ok_or("manifest doesn't have pkg.fmri")?;
+        for action in manifest.iter() {
+            if let Some(path) = action.get_attribute("path") {
+                self.paths
+                    .entry(path.trim_matches('/').to_owned())
+                    .or_default()
+                    .push((fmri.clone(), action.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns conflicts sorted by path
+    pub fn check(&self) -> Vec<Conflict> {
+        let mut conflicts = vec![];
+        for (path, actions) in &self.paths {
+            if actions.len() < 2 {
+                continue;
+            }
+
+            let mut found: BTreeMap<Rule, Vec<FMRI>> = BTreeMap::new();
+            for installable in variant_combinations(actions) {
+                if installable.len() < 2 {
+                    continue;
+                }
+                for rule in check_actions(&installable) {
+                    let fmris = found.entry(rule).or_default();
+                    for (fmri, _) in &installable {
+                        if !fmris.contains(fmri) {
+                            fmris.push((*fmri).clone());
+                        }
+                    }
+                }
+            }
+
+            conflicts.extend(found.into_iter().map(|(rule, fmris)| Conflict {
+                path: path.clone(),
+                rule,
+                fmris,
+            }));
+        }
+        conflicts
+    }
+}
+
+/// Returns actions installed together for every combination of variant values
+fn variant_combinations(actions: &[(FMRI, Action)]) -> Vec<Vec<&(FMRI, Action)>> {
+    let mut variants: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for (_, action) in actions {
+        for (key, value) in action.get_attributes_ref() {
+            if key.starts_with("variant.") {
+                variants.entry(key).or_default().insert(value);
+            }
+        }
+    }
+
+    let mut combinations: Vec<Vec<(&str, &str)>> = vec![vec![]];
+    for (key, values) in variants {
+        combinations = combinations
+            .into_iter()
+            .flat_map(|combination| {
+                values.iter().map(move |value| {
+                    let mut combination = combination.clone();
+                    combination.push((key, value));
+                    combination
+                })
+            })
+            .collect();
+    }
+
+    combinations
+        .into_iter()
+        .map(|combination| {
+            actions
+                .iter()
+                .filter(|(_, action)| {
+                    combination.iter().all(|(key, value)| {
+                        action
+                            .get_attribute(key)
+                            .map_or(true, |own| own.as_str() == *value)
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns rules violated by actions delivering the same path
+fn check_actions(actions: &[&(FMRI, Action)]) -> Vec<Rule> {
+    let types: BTreeSet<&String> = actions
+        .iter()
+        .map(|(_, action)| action.get_action_type_ref())
+        .collect();
+    if types.len() > 1 {
+        return vec![Rule::ActionTypes(types.into_iter().cloned().collect())];
+    }
+    let values = |key: &str| -> BTreeSet<Option<&String>> {
+        actions
+            .iter()
+            .map(|(_, action)| action.get_attribute(key))
+            .collect()
+    };
+
+    match types.first().map(|action_type| action_type.as_str()) {
+        Some("file") => {
+            let overlay: Vec<Option<&String>> = actions
+                .iter()
+                .map(|(_, action)| action.get_attribute("overlay"))
+                .collect();
+            let count = |value: &str| {
+                overlay
+                    .iter()
+                    .filter(|overlay| overlay.is_some_and(|overlay| overlay == value))
+                    .count()
+            };
+            if overlay.iter().all(Option::is_none) {
+                vec![Rule::DuplicateFile]
+            } else if actions.len() != 2 || count("allow") != 1 || count("true") != 1 {
+                vec![Rule::Overlay]
+            } else {
+                vec![]
+            }
+        }
+        Some("dir") => DIR_ATTRIBUTES
+            .iter()
+            .filter(|attribute| values(attribute).len() > 1)
+            .map(|attribute| Rule::Attribute(attribute.to_string()))
+            .collect(),
+        Some("link" | "hardlink") => {
+            let mediators = values("mediator");
+            if mediators.iter().any(Option::is_some) {
+                match mediators.len() {
+                    1 => vec![],
+                    _ => vec![Rule::Mediator],
+                }
+            } else if values("target").len() > 1 {
+                vec![Rule::LinkTarget]
+            } else {
+                vec![]
+            }
+        }
+        _ => vec![],
+    }
+}
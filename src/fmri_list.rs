@@ -1,11 +1,33 @@
-use std::fmt::{Debug, Display, Formatter};
+use alloc::{collections::BTreeMap, string::String, vec, vec::Vec};
+use core::fmt::{Debug, Display, Formatter};
 
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::FMRI;
+#[cfg(doc)]
+use crate::version::Version;
+use crate::{
+    fmri_list::{
+        diff::FMRIListDiff,
+        format::ListFormat,
+        index::{cmp_newest, FMRIIndex},
+    },
+    publisher::Publisher,
+    FMRI,
+};
+
+pub mod arena;
+pub mod diff;
+pub mod format;
+pub mod index;
+#[cfg(feature = "rayon")]
+pub mod parallel;
 
 /// [`FMRIList`] contains more [`FMRIs`][FMRI]
-#[derive(Serialize, Deserialize, Clone, Eq, Ord, PartialEq, PartialOrd, Hash)]
+///
+/// Lookups on the list itself are linear, use [`FMRIList::index`] for catalog-sized lists.
+#[derive(Clone, Eq, Ord, PartialEq, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct FMRIList(Vec<FMRI>);
 
 impl FMRIList {
@@ -40,6 +62,11 @@ impl FMRIList {
         false
     }
 
+    pub fn iter(&self) -> core::slice::Iter<'_, FMRI> {
+        self.0.iter()
+    }
+
+    /// Checks if there is [`FMRI`] with the same package name
     pub fn contains(&self, checking_fmri: &FMRI) -> bool {
         for fmri in self.get_ref() {
             if fmri.package_name_eq(checking_fmri) {
@@ -48,41 +75,125 @@ impl FMRIList {
         }
         false
     }
-}
 
-impl From<Vec<FMRI>> for FMRIList {
-    fn from(value: Vec<FMRI>) -> Self {
-        Self(value)
+    /// Compares this (old) list with `other` (new) list
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmri::{FMRI, FMRIList};
+    /// let old = FMRIList::from(vec![FMRI::parse_raw("shell/bash@5.1").unwrap()]);
+    /// let new = FMRIList::from(vec![FMRI::parse_raw("shell/bash@5.2").unwrap()]);
+    /// let diff = old.diff(&new);
+    /// assert!(diff.get_upgraded_ref().contains_key("shell/bash"));
+    /// assert_eq!(format!("{}", diff), "upgraded shell/bash: @5.1 -> @5.2\n");
+    /// ```
+    pub fn diff(&self, other: &FMRIList) -> FMRIListDiff {
+        FMRIListDiff::new(self, other)
     }
-}
 
-impl Display for FMRIList {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let mut string: String = "".to_owned();
+    /// Returns [`FMRIIndex`] of [`FMRIs`][FMRI] in the list
+    pub fn index(&self) -> FMRIIndex {
+        FMRIIndex::from(self)
+    }
 
-        let fmris = self.get_ref();
-        let len = fmris.len() - 1;
-        for (index, fmri) in fmris.iter().enumerate() {
-            string.push_str(&format!("{}", fmri));
+    /// Returns the newest [`FMRI`] of every package name, sorted by package name
+    pub fn latest(&self) -> FMRIList {
+        self.index().latest()
+    }
 
-            if index < len {
-                string.push_str(", ");
+    /// Removes all but the newest [`FMRI`] of every package name, keeps order of the rest
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmri::{FMRI, FMRIList};
+    /// let mut list: FMRIList = ["b@1", "a@2", "b@3", "a@1"]
+    ///     .iter()
+    ///     .map(|fmri| FMRI::parse_raw(fmri).unwrap())
+    ///     .collect();
+    /// list.dedup_keep_newest();
+    /// assert_eq!(format!("{}", list), "pkg:/a@2, pkg:/b@3");
+    /// ```
+    pub fn dedup_keep_newest(&mut self) {
+        let mut newest: BTreeMap<&String, usize> = BTreeMap::new();
+        for (position, fmri) in self.0.iter().enumerate() {
+            let name = fmri.get_package_name_as_ref_string();
+            match newest.get(name) {
+                Some(current) if cmp_newest(fmri, &self.0[*current]).is_le() => {}
+                _ => {
+                    newest.insert(name, position);
+                }
             }
         }
 
-        write!(f, "{}", string)
+        let mut keep = vec![false; self.0.len()];
+        newest
+            .into_values()
+            .for_each(|position| keep[position] = true);
+        let mut keep = keep.into_iter();
+        self.0.retain(|_| keep.next().unwrap_or(false));
+    }
+
+    /// Sorts [`FMRIs`][FMRI] by [`Version`] from the oldest, [`FMRIs`][FMRI] without version are
+    /// the first and sort is stable
+    pub fn sort_by_version(&mut self) {
+        self.0
+            .sort_by(|a, b| a.get_version_ref().cmp(b.get_version_ref()))
+    }
+
+    /// Keeps only [`FMRIs`][FMRI] from given [`Publisher`]
+    pub fn retain_publisher(&mut self, publisher: &Publisher) {
+        self.0
+            .retain(|fmri| fmri.get_publisher_ref().as_ref() == Some(publisher))
     }
 }
 
-impl Debug for FMRIList {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let mut string: String = "".to_owned();
+impl FromIterator<FMRI> for FMRIList {
+    fn from_iter<T: IntoIterator<Item = FMRI>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
 
-        for (index, fmri) in self.get_ref().iter().enumerate() {
-            string.push_str(&format!("{}. {}\n", index + 1, fmri));
-        }
+impl IntoIterator for FMRIList {
+    type Item = FMRI;
+    type IntoIter = alloc::vec::IntoIter<FMRI>;
 
-        write!(f, "{}", string)
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a FMRIList {
+    type Item = &'a FMRI;
+    type IntoIter = core::slice::Iter<'a, FMRI>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl Extend<FMRI> for FMRIList {
+    fn extend<T: IntoIterator<Item = FMRI>>(&mut self, iter: T) {
+        self.0.extend(iter)
+    }
+}
+
+impl From<Vec<FMRI>> for FMRIList {
+    fn from(value: Vec<FMRI>) -> Self {
+        Self(value)
+    }
+}
+
+impl Display for FMRIList {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.display_as(ListFormat::Inline))
+    }
+}
+
+impl Debug for FMRIList {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.display_as(ListFormat::Numbered))
     }
 }
 
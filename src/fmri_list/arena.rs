@@ -0,0 +1,452 @@
+use alloc::{boxed::Box, collections::BTreeMap, format, string::String, sync::Arc, vec::Vec};
+use core::cmp::Ordering;
+use core::fmt::{Debug, Display, Formatter};
+
+use crate::{
+    publisher::Publisher,
+    version::{segment::Segment, segments::Segments, Version},
+    FMRIList, FMRI,
+};
+
+/// Missing publisher
+const NO_PUBLISHER: u32 = u32::MAX;
+/// Missing timestamp
+const NO_TIMESTAMP: u64 = u64::MAX;
+/// Flag of timestamp, which isn't "YYYYMMDDTHHMMSSZ" and is interned
+const INTERNED_TIMESTAMP: u64 = 1 << 63;
+
+/// [`FMRIArena`] stores many [`FMRIs`][FMRI] compactly, like a whole catalog
+///
+/// Publishers, package names and odd timestamps are interned, dot sequences are deduplicated and
+/// stored in one shared buffer and timestamps are packed into numbers. Stored [`FMRIs`][FMRI]
+/// are accessed through [`FMRIView`], which is displayed and ordered like [`FMRI`].
+///
+/// # Examples
+///
+/// ```
+/// use fmri::{FMRI, fmri_list::arena::FMRIArena};
+/// let arena: FMRIArena = [
+///     "pkg://openindiana.org/shell/bash@5.2.15,5.11-2023.0.0.1:20230801T080000Z",
+///     "pkg://openindiana.org/shell/bash@5.2.21,5.11-2023.0.0.0:20230901T080000Z",
+/// ]
+/// .iter()
+/// .map(|fmri| FMRI::parse_raw(fmri).unwrap())
+/// .collect();
+///
+/// let newest = arena.iter().max().unwrap();
+/// assert_eq!(newest.get_package_name_ref(), "shell/bash");
+/// assert_eq!(newest.get_component_version_ref(), Some(&[5, 2, 21][..]));
+/// assert_eq!(
+///     newest.to_fmri(),
+///     FMRI::parse_raw("pkg://openindiana.org/shell/bash@5.2.21,5.11-2023.0.0.0:20230901T080000Z").unwrap()
+/// );
+/// ```
+#[derive(Clone, Default)]
+pub struct FMRIArena {
+    strings: Interner,
+    /// Numbers of all dot sequences
+    numbers: Vec<u64>,
+    /// Start of every distinct dot sequence in numbers
+    segments: BTreeMap<Box<[u64]>, u32>,
+    entries: Vec<Entry>,
+}
+
+/// Stored [`FMRI`], strings are indexes into [`Interner`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Entry {
+    publisher: u32,
+    package_name: u32,
+    /// Empty without version
+    component_version: Span,
+    build_version: Span,
+    branch_version: Span,
+    timestamp: u64,
+}
+
+/// Dot sequence in numbers of [`FMRIArena`], empty span is missing dot sequence
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+struct Span {
+    start: u32,
+    len: u32,
+}
+
+/// Distinct strings shared by [`Arc`]
+#[derive(Clone, Default)]
+struct Interner {
+    strings: Vec<Arc<str>>,
+    ids: BTreeMap<Arc<str>, u32>,
+}
+
+impl Interner {
+    fn intern(&mut self, string: &str) -> u32 {
+        if let Some(id) = self.ids.get(string) {
+            return *id;
+        }
+        let id = u32::try_from(self.strings.len()).expect("too many strings in FMRIArena");
+        let string: Arc<str> = Arc::from(string);
+        self.strings.push(string.clone());
+        self.ids.insert(string, id);
+        id
+    }
+
+    fn get(&self, id: u32) -> &str {
+        &self.strings[id as usize]
+    }
+}
+
+impl FMRIArena {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores copy of [`FMRI`], returns its index
+    pub fn add(&mut self, fmri: &FMRI) -> usize {
+        let publisher = match fmri.get_publisher_as_ref_string() {
+            Some(publisher) => self.strings.intern(publisher),
+            None => NO_PUBLISHER,
+        };
+        let package_name = self.strings.intern(fmri.get_package_name_as_ref_string());
+
+        let mut entry = Entry {
+            publisher,
+            package_name,
+            component_version: Span::default(),
+            build_version: Span::default(),
+            branch_version: Span::default(),
+            timestamp: NO_TIMESTAMP,
+        };
+        if let Some(version) = fmri.get_version_ref() {
+            entry.component_version = self.add_segment(version.get_component_version_ref());
+            entry.build_version = self.add_segment(version.get_build_version_ref());
+            entry.branch_version = self.add_segment(version.get_branch_version_ref());
+            if let Segments::Timestamp(timestamp) = version.get_timestamp_ref() {
+                entry.timestamp = pack_timestamp(timestamp)
+                    .unwrap_or_else(|| INTERNED_TIMESTAMP | self.strings.intern(timestamp) as u64);
+            }
+        }
+
+        self.entries.push(entry);
+        self.entries.len() - 1
+    }
+
+    fn add_segment(&mut self, segments: &Segments) -> Span {
+        let Some(segment) = segments.get_segment_ref() else {
+            return Span::default();
+        };
+        let numbers = segment.get_ref();
+        let len = u32::try_from(numbers.len()).expect("dot sequence is too long");
+        if let Some(start) = self.segments.get(numbers.as_slice()) {
+            return Span { start: *start, len };
+        }
+        let start = u32::try_from(self.numbers.len()).expect("too many numbers in FMRIArena");
+        self.numbers.extend_from_slice(numbers);
+        self.segments.insert(numbers.as_slice().into(), start);
+        Span { start, len }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns [`FMRIView`] of [`FMRI`] with given index
+    pub fn get(&self, index: usize) -> Option<FMRIView<'_>> {
+        self.entries
+            .get(index)
+            .map(|entry| FMRIView { arena: self, entry })
+    }
+
+    /// Returns [`FMRIViews`][FMRIView] in order of adding
+    pub fn iter(&self) -> impl Iterator<Item = FMRIView<'_>> {
+        self.entries
+            .iter()
+            .map(|entry| FMRIView { arena: self, entry })
+    }
+
+    /// Returns copies of all stored [`FMRIs`][FMRI]
+    pub fn to_fmri_list(&self) -> FMRIList {
+        self.iter().map(|view| view.to_fmri()).collect()
+    }
+
+    fn numbers(&self, span: Span) -> Option<&[u64]> {
+        match span.len {
+            0 => None,
+            len => Some(&self.numbers[span.start as usize..(span.start + len) as usize]),
+        }
+    }
+}
+
+impl FromIterator<FMRI> for FMRIArena {
+    fn from_iter<T: IntoIterator<Item = FMRI>>(iter: T) -> Self {
+        let mut arena = Self::new();
+        iter.into_iter().for_each(|fmri| {
+            arena.add(&fmri);
+        });
+        arena
+    }
+}
+
+impl From<&FMRIList> for FMRIArena {
+    fn from(value: &FMRIList) -> Self {
+        let mut arena = Self::new();
+        value.iter().for_each(|fmri| {
+            arena.add(fmri);
+        });
+        arena
+    }
+}
+
+impl Debug for FMRIArena {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// [`FMRIView`] is borrowed [`FMRI`] stored in [`FMRIArena`]
+///
+/// Equality and ordering are the same as of [`FMRI`].
+#[derive(Clone, Copy)]
+pub struct FMRIView<'a> {
+    arena: &'a FMRIArena,
+    entry: &'a Entry,
+}
+
+impl<'a> FMRIView<'a> {
+    pub fn get_publisher_ref(&self) -> Option<&'a str> {
+        match self.entry.publisher {
+            NO_PUBLISHER => None,
+            id => Some(self.arena.strings.get(id)),
+        }
+    }
+
+    pub fn get_package_name_ref(&self) -> &'a str {
+        self.arena.strings.get(self.entry.package_name)
+    }
+
+    pub fn has_version(&self) -> bool {
+        self.entry.component_version.len != 0
+    }
+
+    pub fn get_component_version_ref(&self) -> Option<&'a [u64]> {
+        self.arena.numbers(self.entry.component_version)
+    }
+
+    pub fn get_build_version_ref(&self) -> Option<&'a [u64]> {
+        self.arena.numbers(self.entry.build_version)
+    }
+
+    pub fn get_branch_version_ref(&self) -> Option<&'a [u64]> {
+        self.arena.numbers(self.entry.branch_version)
+    }
+
+    /// Returns timestamp in format "20171212T185746Z"
+    pub fn get_timestamp(&self) -> Option<String> {
+        match self.entry.timestamp {
+            NO_TIMESTAMP => None,
+            timestamp if timestamp & INTERNED_TIMESTAMP != 0 => Some(
+                self.arena
+                    .strings
+                    .get((timestamp & !INTERNED_TIMESTAMP) as u32)
+                    .into(),
+            ),
+            timestamp => Some(format!(
+                "{:08}T{:06}Z",
+                timestamp / 1_000_000,
+                timestamp % 1_000_000
+            )),
+        }
+    }
+
+    /// Returns packed or interned timestamp without allocation
+    fn timestamp(&self) -> Option<Timestamp<'a>> {
+        match self.entry.timestamp {
+            NO_TIMESTAMP => None,
+            timestamp if timestamp & INTERNED_TIMESTAMP != 0 => Some(Timestamp::Interned(
+                self.arena
+                    .strings
+                    .get((timestamp & !INTERNED_TIMESTAMP) as u32),
+            )),
+            timestamp => Some(Timestamp::Packed(timestamp)),
+        }
+    }
+
+    /// Checks if package names are same
+    pub fn package_name_eq(&self, other: &FMRIView) -> bool {
+        self.get_package_name_ref() == other.get_package_name_ref()
+    }
+
+    /// Returns owned copy of [`FMRI`]
+    pub fn to_fmri(&self) -> FMRI {
+        let mut fmri = FMRI {
+            publisher: None,
+            package_name: self.get_package_name_ref().into(),
+            version: None,
+        };
+        if let Some(publisher) = self.get_publisher_ref() {
+            fmri.change_publisher(
+                Publisher::new(publisher.into()).expect("publisher of stored FMRI is valid"),
+            );
+        }
+        if let Some(component) = self.get_component_version_ref() {
+            let segment = |numbers: &[u64]| Segment::new(numbers.to_vec()).expect("not empty");
+            let mut version = Version::builder().component_version(segment(component));
+            if let Some(build) = self.get_build_version_ref() {
+                version = version.build_version(segment(build));
+            }
+            if let Some(branch) = self.get_branch_version_ref() {
+                version = version.branch_version(segment(branch));
+            }
+            let mut version = version.build().expect("has component version");
+            if let Some(timestamp) = self.get_timestamp() {
+                version.set_timestamp_unchecked(timestamp);
+            }
+            fmri.change_version(version);
+        }
+        fmri
+    }
+}
+
+impl PartialEq for FMRIView<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        if core::ptr::eq(self.arena, other.arena) {
+            return self.entry == other.entry;
+        }
+        self.get_publisher_ref() == other.get_publisher_ref()
+            && self.get_package_name_ref() == other.get_package_name_ref()
+            && self.get_component_version_ref() == other.get_component_version_ref()
+            && self.get_build_version_ref() == other.get_build_version_ref()
+            && self.get_branch_version_ref() == other.get_branch_version_ref()
+            && self.timestamp() == other.timestamp()
+    }
+}
+
+impl Eq for FMRIView<'_> {}
+
+impl PartialOrd for FMRIView<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FMRIView<'_> {
+    /// Compares like [`FMRI`]
+    fn cmp(&self, other: &Self) -> Ordering {
+        let parts = |view: &Self| {
+            [
+                view.get_component_version_ref(),
+                view.get_branch_version_ref(),
+                view.get_build_version_ref(),
+            ]
+        };
+        parts(self)
+            .cmp(&parts(other))
+            .then_with(|| self.timestamp().cmp(&other.timestamp()))
+            .then_with(|| {
+                self.get_package_name_ref()
+                    .cmp(other.get_package_name_ref())
+            })
+            .then_with(|| self.get_publisher_ref().cmp(&other.get_publisher_ref()))
+    }
+}
+
+impl Display for FMRIView<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self.get_publisher_ref() {
+            Some(publisher) => write!(f, "pkg://{}/", publisher)?,
+            None => write!(f, "pkg:/")?,
+        }
+        write!(f, "{}", self.get_package_name_ref())?;
+
+        let dot_sequence = |f: &mut Formatter<'_>, separator: char, numbers: &[u64]| {
+            write!(f, "{}", separator)?;
+            for (index, number) in numbers.iter().enumerate() {
+                match index {
+                    0 => write!(f, "{}", number)?,
+                    _ => write!(f, ".{}", number)?,
+                }
+            }
+            Ok(())
+        };
+        if let Some(component) = self.get_component_version_ref() {
+            dot_sequence(f, '@', component)?;
+        }
+        if let Some(build) = self.get_build_version_ref() {
+            dot_sequence(f, ',', build)?;
+        }
+        if let Some(branch) = self.get_branch_version_ref() {
+            dot_sequence(f, '-', branch)?;
+        }
+        if let Some(timestamp) = self.get_timestamp() {
+            write!(f, ":{}", timestamp)?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for FMRIView<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+/// Timestamp of stored [`FMRI`], it is ordered like string
+///
+/// Timestamp is interned only if it can't be packed, so packed and interned timestamps are never
+/// equal.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Timestamp<'a> {
+    Packed(u64),
+    Interned(&'a str),
+}
+
+impl PartialOrd for Timestamp<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Timestamp<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            // digits have fixed width, so numbers are ordered like strings
+            (Timestamp::Packed(a), Timestamp::Packed(b)) => a.cmp(b),
+            (Timestamp::Interned(a), Timestamp::Interned(b)) => a.cmp(b),
+            (Timestamp::Packed(a), Timestamp::Interned(b)) => {
+                unpack_timestamp(*a).as_slice().cmp(b.as_bytes())
+            }
+            (Timestamp::Interned(a), Timestamp::Packed(b)) => {
+                a.as_bytes().cmp(unpack_timestamp(*b).as_slice())
+            }
+        }
+    }
+}
+
+/// Unpacks number YYYYMMDDHHMMSS into "YYYYMMDDTHHMMSSZ"
+fn unpack_timestamp(packed: u64) -> [u8; 16] {
+    let mut timestamp = *b"00000000T000000Z";
+    let (mut date, mut time) = (packed / 1_000_000, packed % 1_000_000);
+    for digit in timestamp[..8].iter_mut().rev() {
+        *digit = b'0' + (date % 10) as u8;
+        date /= 10;
+    }
+    for digit in timestamp[9..15].iter_mut().rev() {
+        *digit = b'0' + (time % 10) as u8;
+        time /= 10;
+    }
+    timestamp
+}
+
+/// Packs "YYYYMMDDTHHMMSSZ" into number YYYYMMDDHHMMSS
+fn pack_timestamp(timestamp: &str) -> Option<u64> {
+    let (date, time) = timestamp.strip_suffix('Z')?.split_once('T')?;
+    if date.len() != 8
+        || time.len() != 6
+        || !date.chars().chain(time.chars()).all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    Some(date.parse::<u64>().ok()? * 1_000_000 + time.parse::<u64>().ok()?)
+}
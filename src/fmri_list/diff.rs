@@ -0,0 +1,180 @@
+use alloc::{
+    borrow::ToOwned,
+    collections::BTreeMap,
+    string::{String, ToString},
+};
+use core::cmp::Ordering;
+use core::fmt::{Display, Formatter};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(doc)]
+use crate::version::Version;
+use crate::{fmri_list::index::cmp_newest, FMRIList, FMRI};
+
+/// [`FMRIListDiff`] is result of comparing two [`FMRILists`][FMRIList]
+///
+/// All entries are keyed by package name. If a list contains more [`FMRIs`][FMRI] with the same
+/// package name, only the newest one is compared.
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FMRIListDiff {
+    added: BTreeMap<String, FMRI>,
+    removed: BTreeMap<String, FMRI>,
+    upgraded: BTreeMap<String, FMRIChange>,
+    downgraded: BTreeMap<String, FMRIChange>,
+    /// Same [`Version`], but different timestamp or publisher
+    republished: BTreeMap<String, FMRIChange>,
+}
+
+/// [`FMRIChange`] is pair of old and new [`FMRI`] with the same package name
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FMRIChange {
+    old: FMRI,
+    new: FMRI,
+}
+
+impl FMRIChange {
+    pub fn new(old: FMRI, new: FMRI) -> Self {
+        Self { old, new }
+    }
+
+    pub fn get_old_ref(&self) -> &FMRI {
+        &self.old
+    }
+
+    pub fn get_new_ref(&self) -> &FMRI {
+        &self.new
+    }
+}
+
+impl FMRIListDiff {
+    /// Compares `old` and `new` [`FMRIList`]
+    pub fn new(old: &FMRIList, new: &FMRIList) -> Self {
+        let old = newest_per_package(old);
+        let mut new = newest_per_package(new);
+        let mut diff = Self::default();
+
+        for (name, old_fmri) in old {
+            let Some(new_fmri) = new.remove(&name) else {
+                diff.removed.insert(name, old_fmri.clone());
+                continue;
+            };
+
+            if old_fmri == new_fmri {
+                continue;
+            }
+
+            let change = FMRIChange {
+                old: old_fmri.clone(),
+                new: new_fmri.clone(),
+            };
+            let ordering = match (old_fmri.get_version_ref(), new_fmri.get_version_ref()) {
+                (Some(old), Some(new)) => old.cmp_without_timestamp(new),
+                (old, new) => old.cmp(new),
+            };
+            match ordering {
+                Ordering::Less => diff.upgraded.insert(name, change),
+                Ordering::Greater => diff.downgraded.insert(name, change),
+                Ordering::Equal => diff.republished.insert(name, change),
+            };
+        }
+
+        for (name, new_fmri) in new {
+            diff.added.insert(name, new_fmri.clone());
+        }
+
+        diff
+    }
+
+    pub fn get_added_ref(&self) -> &BTreeMap<String, FMRI> {
+        &self.added
+    }
+
+    pub fn get_removed_ref(&self) -> &BTreeMap<String, FMRI> {
+        &self.removed
+    }
+
+    pub fn get_upgraded_ref(&self) -> &BTreeMap<String, FMRIChange> {
+        &self.upgraded
+    }
+
+    pub fn get_downgraded_ref(&self) -> &BTreeMap<String, FMRIChange> {
+        &self.downgraded
+    }
+
+    pub fn get_republished_ref(&self) -> &BTreeMap<String, FMRIChange> {
+        &self.republished
+    }
+
+    /// Returns true if lists contain the same packages in the same versions
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.upgraded.is_empty()
+            && self.downgraded.is_empty()
+            && self.republished.is_empty()
+    }
+}
+
+/// Returns the newest [`FMRI`] of every package name
+fn newest_per_package(list: &FMRIList) -> BTreeMap<String, &FMRI> {
+    let mut newest: BTreeMap<String, &FMRI> = BTreeMap::new();
+
+    for fmri in list {
+        let name = fmri.get_package_name_as_ref_string();
+        match newest.get(name) {
+            Some(current) if cmp_newest(fmri, current).is_le() => {}
+            _ => {
+                newest.insert(name.clone(), fmri);
+            }
+        }
+    }
+
+    newest
+}
+
+impl Display for FMRIListDiff {
+    /// Writes one change per line
+    ///
+    /// ```plain
+    /// added pkg:/library/zlib@1.3
+    /// upgraded shell/bash: @5.1 -> @5.2
+    /// ```
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        for fmri in self.added.values() {
+            writeln!(f, "added {}", fmri)?;
+        }
+        for fmri in self.removed.values() {
+            writeln!(f, "removed {}", fmri)?;
+        }
+        for (kind, changes) in [
+            ("upgraded", &self.upgraded),
+            ("downgraded", &self.downgraded),
+            ("republished", &self.republished),
+        ] {
+            for (name, change) in changes {
+                writeln!(
+                    f,
+                    "{} {}: {} -> {}",
+                    kind,
+                    name,
+                    version_or_publisher(&change.old, &change.new),
+                    version_or_publisher(&change.new, &change.old)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Formats version of `fmri`, whole [`FMRI`] is used when versions differ only in publisher
+fn version_or_publisher(fmri: &FMRI, other: &FMRI) -> String {
+    if fmri.get_version_ref() == other.get_version_ref() {
+        return fmri.to_string();
+    }
+    fmri.get_version_as_string()
+        .unwrap_or_else(|| "(no version)".to_owned())
+}
@@ -0,0 +1,326 @@
+use alloc::{
+    borrow::ToOwned,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt::{Display, Formatter};
+
+#[cfg(feature = "json")]
+use serde_json::Value;
+
+use crate::{version::segments::Segments, FMRIList, Version, FMRI};
+
+/// [`ListFormat`] selects text representation of [`FMRIList`] for parsing and writing
+///
+/// # Examples
+///
+/// ```plain
+/// Inline       pkg:/a@1, pkg:/b@2
+/// Lines        pkg:/a@1
+/// Numbered     1. pkg:/a@1
+/// PkgList      pkg://openindiana.org/shell/bash@5.2-2023.0.0.0:20230101T000000Z   i--
+/// PkgContents  PKG.FMRI
+///              pkg://openindiana.org/shell/bash@5.2-2023.0.0.0:20230101T000000Z
+/// PkgrepoTsv   openindiana.org<TAB>shell/bash<TAB>...<TAB>pkg://openindiana.org/shell/bash@...
+/// Json         ["pkg:/a@1", "pkg:/b@2"]
+/// ```
+///
+/// [`ListFormat::Json`] exists only with `json` feature, so the enum is non-exhaustive.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Hash)]
+#[non_exhaustive]
+pub enum ListFormat {
+    /// Comma separated, used by [`Display`] of [`FMRIList`]
+    #[default]
+    Inline,
+    /// One [`FMRI`] per line, empty lines and lines starting with '#' are skipped
+    Lines,
+    /// Numbered lines, used by [`Debug`] of [`FMRIList`]
+    Numbered,
+    /// Output of `pkg list -v` or `pkg list -Hv`, [`FMRI`] is the first column
+    ///
+    /// Written lists mark every package as installed (`i--`).
+    PkgList,
+    /// Output of `pkg contents -o pkg.fmri`, with or without header
+    ///
+    /// Without header the first column is used.
+    PkgContents,
+    /// Output of `pkgrepo list -F tsv`, with or without header
+    ///
+    /// Without header the first column starting with "pkg:/" is used.
+    PkgrepoTsv,
+    /// JSON array of strings, or objects with "fmri" key (`pkgrepo list -F json`)
+    #[cfg(feature = "json")]
+    Json,
+}
+
+/// Headers written by [`ListFormat::PkgrepoTsv`]
+const PKGREPO_TSV_HEADER: [&str; 8] = [
+    "PUBLISHER",
+    "NAME",
+    "O",
+    "RELEASE",
+    "BUILD RELEASE",
+    "BRANCH",
+    "PACKAGING DATE",
+    "FMRI",
+];
+
+impl FMRIList {
+    /// Parses [`FMRIList`] from text in given [`ListFormat`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmri::{FMRIList, fmri_list::format::ListFormat};
+    /// let output = "\
+    /// FMRI                                                        IFO
+    /// pkg://openindiana.org/shell/bash@5.2-2023.0.0.0:20230101T000000Z i--
+    /// ";
+    /// let list = FMRIList::parse_formatted(output, ListFormat::PkgList).unwrap();
+    /// assert_eq!(list.len(), 1);
+    /// ```
+    ///
+    /// # Error
+    ///
+    /// Returns a string with line number (entry number for [`ListFormat::Json`]) and error message
+    pub fn parse_formatted(input: &str, format: ListFormat) -> Result<Self, String> {
+        let raw_fmris: Vec<(usize, String)> = match format {
+            ListFormat::Inline => input
+                .split(", ")
+                .map(str::trim)
+                .filter(|fmri| !fmri.is_empty())
+                .enumerate()
+                .map(|(index, fmri)| (index + 1, fmri.to_owned()))
+                .collect(),
+            ListFormat::Lines => lines(input)
+                .filter(|(_, line)| !line.starts_with('#'))
+                .map(|(number, line)| (number, line.to_owned()))
+                .collect(),
+            ListFormat::Numbered => lines(input)
+                .map(|(number, line)| match line.split_once(". ") {
+                    Some((_, fmri)) => Ok((number, fmri.trim().to_owned())),
+                    None => Err(format!("line {}: missing number", number)),
+                })
+                .collect::<Result<Vec<(usize, String)>, String>>()?,
+            ListFormat::PkgList => parse_pkg_list(input)?,
+            ListFormat::PkgContents => parse_columns(input, false)?,
+            ListFormat::PkgrepoTsv => parse_columns(input, true)?,
+            #[cfg(feature = "json")]
+            ListFormat::Json => parse_json(input)?,
+        };
+
+        raw_fmris
+            .into_iter()
+            .map(|(number, raw_fmri)| {
+                FMRI::parse_raw(&raw_fmri).map_err(|e| format!("line {}: {}", number, e))
+            })
+            .collect()
+    }
+
+    /// Returns [`FMRIList`] wrapper which is displayed in given [`ListFormat`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmri::{FMRI, FMRIList, fmri_list::format::ListFormat};
+    /// let list = FMRIList::from(vec![FMRI::parse_raw("a@1").unwrap(), FMRI::parse_raw("b").unwrap()]);
+    /// assert_eq!(list.display_as(ListFormat::Lines).to_string(), "pkg:/a@1\npkg:/b\n");
+    /// #[cfg(feature = "json")]
+    /// assert_eq!(list.display_as(ListFormat::Json).to_string(), "[\"pkg:/a@1\",\"pkg:/b\"]");
+    /// ```
+    pub fn display_as(&self, format: ListFormat) -> FormattedFMRIList<'_> {
+        FormattedFMRIList { list: self, format }
+    }
+}
+
+/// [`FormattedFMRIList`] displays [`FMRIList`] in selected [`ListFormat`]
+pub struct FormattedFMRIList<'a> {
+    list: &'a FMRIList,
+    format: ListFormat,
+}
+
+impl Display for FormattedFMRIList<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let fmris = self.list.get_ref();
+
+        match self.format {
+            ListFormat::Inline => {
+                for (index, fmri) in fmris.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", fmri)?;
+                }
+            }
+            ListFormat::Lines => {
+                for fmri in fmris {
+                    writeln!(f, "{}", fmri)?;
+                }
+            }
+            ListFormat::Numbered => {
+                for (index, fmri) in fmris.iter().enumerate() {
+                    writeln!(f, "{}. {}", index + 1, fmri)?;
+                }
+            }
+            ListFormat::PkgList => {
+                let width = fmris
+                    .iter()
+                    .map(|fmri| fmri.to_string().len())
+                    .max()
+                    .unwrap_or_default();
+                writeln!(f, "{:width$} IFO", "FMRI")?;
+                for fmri in fmris {
+                    writeln!(f, "{:width$} i--", fmri.to_string())?;
+                }
+            }
+            ListFormat::PkgContents => {
+                writeln!(f, "PKG.FMRI")?;
+                for fmri in fmris {
+                    writeln!(f, "{}", fmri)?;
+                }
+            }
+            ListFormat::PkgrepoTsv => {
+                writeln!(f, "{}", PKGREPO_TSV_HEADER.join("\t"))?;
+                for fmri in fmris {
+                    let version = fmri.get_version_ref().as_ref();
+                    let part = |get: fn(&Version) -> &Segments| {
+                        version.map(|v| get(v).to_string()).unwrap_or_default()
+                    };
+                    writeln!(
+                        f,
+                        "{}\t{}\t\t{}\t{}\t{}\t{}\t{}",
+                        fmri.get_publisher_as_ref_string()
+                            .map(String::as_str)
+                            .unwrap_or_default(),
+                        fmri.get_package_name_as_ref_string(),
+                        part(Version::get_component_version_ref),
+                        part(Version::get_build_version_ref),
+                        part(Version::get_branch_version_ref),
+                        part(Version::get_timestamp_ref),
+                        fmri
+                    )?;
+                }
+            }
+            #[cfg(feature = "json")]
+            ListFormat::Json => {
+                let fmris: Vec<String> = fmris.iter().map(FMRI::to_string).collect();
+                write!(
+                    f,
+                    "{}",
+                    serde_json::to_string(&fmris).map_err(|_| core::fmt::Error)?
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns non-empty trimmed lines with line numbers
+fn lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+}
+
+fn parse_pkg_list(input: &str) -> Result<Vec<(usize, String)>, String> {
+    let mut raw_fmris = vec![];
+
+    for (index, (number, line)) in lines(input).enumerate() {
+        if line.starts_with("NAME (PUBLISHER)") {
+            return Err(format!(
+                "line {}: output of \"pkg list\" without -v isn't supported",
+                number
+            ));
+        }
+        // header of "pkg list -v" is the first non-empty line
+        if index == 0 && line.starts_with("FMRI") {
+            continue;
+        }
+        let fmri = line.split_whitespace().next().unwrap_or_default();
+        raw_fmris.push((number, fmri.to_owned()));
+    }
+
+    Ok(raw_fmris)
+}
+
+/// Parses table columns, [`FMRI`] column is found by header
+///
+/// Tab separated tables keep empty columns, other tables are separated by any whitespace.
+fn parse_columns(input: &str, tab_separated: bool) -> Result<Vec<(usize, String)>, String> {
+    let fields = |line: &'_ str| -> Vec<String> {
+        match tab_separated {
+            true => line
+                .split('\t')
+                .map(|field| field.trim().to_owned())
+                .collect(),
+            false => line.split_whitespace().map(str::to_owned).collect(),
+        }
+    };
+    // leading tabs are significant, so lines can't be trimmed
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty())
+        .peekable();
+    let mut column: Option<usize> = None;
+
+    if let Some((_, header)) = lines.peek() {
+        let header_column = fields(header)
+            .iter()
+            .position(|name| matches!(name.as_str(), "FMRI" | "PKG.FMRI"));
+        if header_column.is_some() {
+            column = header_column;
+            lines.next();
+        } else if !tab_separated {
+            column = Some(0);
+        }
+    }
+
+    let mut raw_fmris = vec![];
+    for (number, line) in lines {
+        let fields = fields(line);
+        let field = match column {
+            Some(column) => fields.get(column),
+            None => fields.iter().find(|field| field.starts_with("pkg:/")),
+        };
+        match field {
+            Some(fmri) if !fmri.is_empty() => raw_fmris.push((number, fmri.to_owned())),
+            _ => return Err(format!("line {}: missing FMRI column", number)),
+        }
+    }
+
+    Ok(raw_fmris)
+}
+
+#[cfg(feature = "json")]
+fn parse_json(input: &str) -> Result<Vec<(usize, String)>, String> {
+    let value: Value = serde_json::from_str(input).map_err(|e| format!("invalid json: {}", e))?;
+    let Value::Array(entries) = value else {
+        return Err("json must be an array".to_owned());
+    };
+
+    entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let fmri = match entry {
+                Value::String(fmri) => Some(fmri),
+                Value::Object(object) => match object.get("fmri") {
+                    Some(Value::String(fmri)) => Some(fmri),
+                    _ => None,
+                },
+                _ => None,
+            };
+            fmri.map(|fmri| (index + 1, fmri.to_owned())).ok_or(format!(
+                "line {}: entry must be string or object with \"fmri\" string",
+                index + 1
+            ))
+        })
+        .collect()
+}
@@ -0,0 +1,122 @@
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use core::cmp::Ordering;
+
+use crate::{publisher::Publisher, FMRIList, FMRI};
+
+/// [`FMRIIndex`] indexes [`FMRIs`][FMRI] by package name and publisher
+///
+/// Lookups by package name are `O(log n)`, versions of every package are kept sorted from the
+/// oldest to the newest.
+///
+/// # Examples
+///
+/// ```
+/// use fmri::{FMRI, FMRIList, fmri_list::index::FMRIIndex};
+/// let list: FMRIList = ["shell/bash@5.2", "shell/bash@5.1", "library/zlib@1.3"]
+///     .iter()
+///     .map(|fmri| FMRI::parse_raw(fmri).unwrap())
+///     .collect();
+///
+/// let index = FMRIIndex::from(&list);
+/// assert_eq!(index.get("shell/bash"), Some(&FMRI::parse_raw("shell/bash@5.2").unwrap()));
+/// assert_eq!(index.versions_of("shell/bash").len(), 2);
+/// assert!(index.get("editor/vim").is_none());
+/// ```
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct FMRIIndex {
+    packages: BTreeMap<String, Vec<FMRI>>,
+    len: usize,
+}
+
+impl FMRIIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, fmri: FMRI) {
+        let versions = self
+            .packages
+            .entry(fmri.get_package_name_as_ref_string().clone())
+            .or_default();
+        let position = versions.partition_point(|other| cmp_newest(other, &fmri).is_le());
+        versions.insert(position, fmri);
+        self.len += 1;
+    }
+
+    /// Returns the newest [`FMRI`] with given package name
+    pub fn get(&self, package_name: &str) -> Option<&FMRI> {
+        self.versions_of(package_name).last()
+    }
+
+    /// Returns the newest [`FMRI`] with given package name from given [`Publisher`]
+    pub fn get_from_publisher(&self, package_name: &str, publisher: &Publisher) -> Option<&FMRI> {
+        self.versions_of(package_name)
+            .iter()
+            .rev()
+            .find(|fmri| fmri.get_publisher_ref().as_ref() == Some(publisher))
+    }
+
+    /// Returns all [`FMRIs`][FMRI] with given package name sorted from the oldest
+    pub fn versions_of(&self, package_name: &str) -> &[FMRI] {
+        self.packages
+            .get(package_name)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn contains(&self, package_name: &str) -> bool {
+        self.packages.contains_key(package_name)
+    }
+
+    /// Returns the newest [`FMRI`] of every package name, sorted by package name
+    pub fn latest(&self) -> FMRIList {
+        self.packages
+            .values()
+            .filter_map(|versions| versions.last().cloned())
+            .collect()
+    }
+
+    pub fn package_names(&self) -> impl Iterator<Item = &String> {
+        self.packages.keys()
+    }
+
+    /// Returns count of indexed [`FMRIs`][FMRI]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// Compares [`FMRIs`][FMRI] by version, missing version is older
+pub(crate) fn cmp_newest(fmri: &FMRI, other: &FMRI) -> Ordering {
+    fmri.get_version_ref().cmp(other.get_version_ref())
+}
+
+impl From<&FMRIList> for FMRIIndex {
+    fn from(value: &FMRIList) -> Self {
+        value.iter().cloned().collect()
+    }
+}
+
+impl From<FMRIList> for FMRIIndex {
+    fn from(value: FMRIList) -> Self {
+        value.into_iter().collect()
+    }
+}
+
+impl FromIterator<FMRI> for FMRIIndex {
+    fn from_iter<T: IntoIterator<Item = FMRI>>(iter: T) -> Self {
+        let mut index = Self::new();
+        index.extend(iter);
+        index
+    }
+}
+
+impl Extend<FMRI> for FMRIIndex {
+    fn extend<T: IntoIterator<Item = FMRI>>(&mut self, iter: T) {
+        iter.into_iter().for_each(|fmri| self.add(fmri))
+    }
+}
@@ -0,0 +1,90 @@
+use std::collections::BTreeMap;
+
+use rayon::prelude::*;
+
+use crate::{fmri_list::index::cmp_newest, FMRIList, FMRI};
+
+impl FMRIList {
+    /// Parses lines with [`FMRI::parse_raw`] in parallel
+    ///
+    /// Lines are trimmed, empty lines and lines starting with '#' are skipped like in
+    /// [`ListFormat::Lines`][crate::fmri_list::format::ListFormat::Lines]. Order of lines is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmri::FMRIList;
+    /// let list = FMRIList::par_parse(&["shell/bash@5.2", "", "# comment", "library/zlib@1.3"]).unwrap();
+    /// assert_eq!(list.to_string(), "pkg:/shell/bash@5.2, pkg:/library/zlib@1.3");
+    ///
+    /// let errors = FMRIList::par_parse(&["shell/bash@5.2", "shell/bash@5.a", "x@1,5.a"]).unwrap_err();
+    /// assert_eq!(errors.iter().map(|(line, _)| *line).collect::<Vec<_>>(), [2, 3]);
+    /// ```
+    ///
+    /// # Error
+    ///
+    /// Returns line number (from 1) and error message of every invalid line
+    pub fn par_parse<S: AsRef<str> + Sync>(lines: &[S]) -> Result<Self, Vec<(usize, String)>> {
+        let results: Vec<(usize, Result<FMRI, String>)> = lines
+            .par_iter()
+            .enumerate()
+            .filter_map(|(index, line)| {
+                let line = line.as_ref().trim();
+                match line.is_empty() || line.starts_with('#') {
+                    true => None,
+                    false => Some((index + 1, FMRI::parse_raw(line))),
+                }
+            })
+            .collect();
+
+        let mut fmris = Vec::with_capacity(results.len());
+        let mut errors = vec![];
+        for (number, result) in results {
+            match result {
+                Ok(fmri) => fmris.push(fmri),
+                Err(e) => errors.push((number, e)),
+            }
+        }
+        match errors.is_empty() {
+            true => Ok(Self(fmris)),
+            false => Err(errors),
+        }
+    }
+
+    /// Parallel [`FMRIList::sort_by_version`], the result is the same
+    pub fn par_sort_by_version(&mut self) {
+        self.0
+            .par_sort_by(|a, b| a.get_version_ref().cmp(b.get_version_ref()))
+    }
+
+    /// Parallel [`FMRIList::latest`], the result is the same
+    pub fn par_latest_per_package(&self) -> FMRIList {
+        self.0
+            .par_iter()
+            .fold(BTreeMap::new, |mut newest, fmri| {
+                keep_newest(&mut newest, fmri);
+                newest
+            })
+            .reduce(BTreeMap::new, |mut newest, other| {
+                other
+                    .into_values()
+                    .for_each(|fmri| keep_newest(&mut newest, fmri));
+                newest
+            })
+            .into_values()
+            .cloned()
+            .collect()
+    }
+}
+
+/// Keeps the newest [`FMRI`] of package, the later one of the same versions like
+/// [`FMRIIndex`][crate::fmri_list::index::FMRIIndex]
+fn keep_newest<'a>(newest: &mut BTreeMap<&'a String, &'a FMRI>, fmri: &'a FMRI) {
+    let name = fmri.get_package_name_as_ref_string();
+    match newest.get(name) {
+        Some(current) if cmp_newest(fmri, current).is_lt() => {}
+        _ => {
+            newest.insert(name, fmri);
+        }
+    }
+}
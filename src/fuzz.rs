@@ -0,0 +1,126 @@
+use std::fmt::{Display, Formatter};
+
+use crate::FMRI;
+
+#[cfg(feature = "proptest")]
+pub mod strategy;
+#[cfg(feature = "arbitrary")]
+mod unstructured;
+
+/// Real publishers, generated publishers use them often
+const PUBLISHERS: [&str; 5] = [
+    "openindiana.org",
+    "hipster-encumbered",
+    "on-nightly",
+    "solaris",
+    "omnios",
+];
+
+/// Real first components of package names
+const CATEGORIES: [&str; 8] = [
+    "library",
+    "system",
+    "developer",
+    "shell",
+    "text",
+    "network",
+    "runtime",
+    "web",
+];
+
+/// Characters which are never valid in package name
+const INVALID_CHARACTERS: [char; 6] = ['!', ' ', '%', '*', '~', '$'];
+
+/// Number of kinds of mistakes made by [`malform`]
+const MISTAKES: usize = 8;
+
+/// [`MalformedFMRI`] is a string of [`FMRI`] with one mistake, strict parsing always rejects it
+///
+/// Mistakes are an invalid character or an empty component in package name, an invalid publisher,
+/// a leading zero, an empty number, an overflowing number, a bad timestamp and trailing garbage
+/// after version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MalformedFMRI(String);
+
+impl MalformedFMRI {
+    pub fn get(self) -> String {
+        self.0
+    }
+
+    pub fn get_ref(&self) -> &String {
+        &self.0
+    }
+}
+
+impl Display for MalformedFMRI {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Formats timestamp as "YYYYMMDDTHHMMSSZ"
+fn timestamp((year, month, day, hour, minute, second): (u32, u32, u32, u32, u32, u32)) -> String {
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        year, month, day, hour, minute, second
+    )
+}
+
+/// Returns string of [`FMRI`] with a mistake, `position` picks where the mistake is made
+fn malform(fmri: &FMRI, mistake: usize, position: usize) -> String {
+    let mut publisher = fmri.get_publisher_as_ref_string().cloned();
+    let mut package_name = fmri.get_package_name_as_ref_string().clone();
+    let mut version = fmri
+        .get_version_as_string()
+        .map(|version| version.trim_start_matches('@').to_owned());
+
+    match mistake % MISTAKES {
+        0 => {
+            let character = INVALID_CHARACTERS[position % INVALID_CHARACTERS.len()];
+            package_name.insert(position % (package_name.len() + 1), character);
+        }
+        1 => match position % 2 {
+            0 => package_name.clear(),
+            _ => package_name.push('/'),
+        },
+        2 => {
+            let name = publisher.unwrap_or_else(|| PUBLISHERS[0].to_owned());
+            let index = 1 + position % name.len();
+            publisher = Some(format!("{}_{}", &name[..index], &name[index..]))
+        }
+        3 => version = Some(format!("0{}", version.as_deref().unwrap_or("0"))),
+        4 => {
+            version = Some(match version {
+                Some(version) if version.contains('.') => version.replacen('.', "..", 1),
+                version => format!("{}.", version.as_deref().unwrap_or("1")),
+            })
+        }
+        5 => {
+            let version = version.get_or_insert_with(|| "1".to_owned());
+            let end = version.find([',', '-', ':']).unwrap_or(version.len());
+            version.replace_range(..end, "18446744073709551616");
+        }
+        6 => {
+            let mut bad_timestamp = timestamp((2023, 1, 1, 0, 0, 0));
+            bad_timestamp.remove(position % bad_timestamp.len());
+            let version = version.get_or_insert_with(|| "1".to_owned());
+            version.truncate(version.find(':').unwrap_or(version.len()));
+            version.push(':');
+            version.push_str(&bad_timestamp);
+        }
+        _ => {
+            let garbage = ["a", "rc1", "~beta", "+git"][position % 4];
+            version = Some(format!("{}{}", version.as_deref().unwrap_or("1"), garbage));
+        }
+    }
+
+    let mut malformed = match publisher {
+        Some(publisher) => format!("pkg://{}/{}", publisher, package_name),
+        None => format!("pkg:/{}", package_name),
+    };
+    if let Some(version) = version {
+        malformed.push('@');
+        malformed.push_str(&version);
+    }
+    malformed
+}
@@ -0,0 +1,115 @@
+use proptest::{collection, option, prelude::*, sample::select};
+
+use crate::{
+    fuzz::{malform, timestamp, MalformedFMRI, CATEGORIES, MISTAKES, PUBLISHERS},
+    version::{segment::Segment, Version},
+    FMRIList, Publisher, FMRI,
+};
+
+/// Mostly small numbers like in real versions
+pub fn number() -> impl Strategy<Value = u64> {
+    prop_oneof![5 => 0..10u64, 4 => 10..2100u64, 1 => any::<u64>()]
+}
+
+pub fn segment() -> impl Strategy<Value = Segment> {
+    collection::vec(number(), 1..=5)
+        .prop_map(|numbers| Segment::new(numbers).expect("segment has at least one number"))
+}
+
+/// Timestamp in "YYYYMMDDTHHMMSSZ" format
+pub fn timestamp_string() -> impl Strategy<Value = String> {
+    (
+        1970..2100u32,
+        1..=12u32,
+        1..=28u32,
+        0..24u32,
+        0..60u32,
+        0..60u32,
+    )
+        .prop_map(timestamp)
+}
+
+pub fn version() -> impl Strategy<Value = Version> {
+    (
+        segment(),
+        option::of(segment()),
+        option::of(segment()),
+        option::of(timestamp_string()),
+    )
+        .prop_map(|(component, build, branch, timestamp)| {
+            let mut builder = Version::builder().component_version(component);
+            if let Some(build) = build {
+                builder = builder.build_version(build);
+            }
+            if let Some(branch) = branch {
+                builder = builder.branch_version(branch);
+            }
+            if let Some(timestamp) = timestamp {
+                builder = builder.timestamp(timestamp);
+            }
+            builder.build().expect("generated timestamp is valid")
+        })
+}
+
+pub fn publisher() -> impl Strategy<Value = Publisher> {
+    prop_oneof![
+        select(&PUBLISHERS[..]).prop_map(str::to_owned),
+        "[a-zA-Z0-9][a-zA-Z0-9.-]{0,20}",
+    ]
+    .prop_map(|publisher| Publisher::new(publisher).expect("generated publisher is valid"))
+}
+
+/// Package name like "library/libfoo" or "x11/Mesa+extra/demos"
+pub fn package_name() -> impl Strategy<Value = String> {
+    (
+        option::of(select(&CATEGORIES[..])),
+        collection::vec("[a-zA-Z0-9][a-zA-Z0-9_.+-]{0,15}", 1..=3),
+    )
+        .prop_map(|(category, components)| {
+            category
+                .map(str::to_owned)
+                .into_iter()
+                .chain(components)
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+}
+
+/// # Examples
+///
+/// ```
+/// use fmri::{fuzz::strategy, FMRI};
+/// use proptest::prelude::*;
+///
+/// proptest!(|(fmri in strategy::fmri())| {
+///     prop_assert_eq!(FMRI::parse_raw(&fmri.to_string()).unwrap(), fmri);
+/// });
+/// ```
+pub fn fmri() -> impl Strategy<Value = FMRI> {
+    (
+        option::of(publisher()),
+        package_name(),
+        option::of(version()),
+    )
+        .prop_map(|(publisher, package_name, version)| {
+            let mut fmri =
+                FMRI::new_from_package_name(package_name).expect("generated package name is valid");
+            if let Some(publisher) = publisher {
+                fmri.change_publisher(publisher);
+            }
+            if let Some(version) = version {
+                fmri.change_version(version);
+            }
+            fmri
+        })
+}
+
+pub fn fmri_list() -> impl Strategy<Value = FMRIList> {
+    collection::vec(fmri(), 0..20).prop_map(FMRIList::from)
+}
+
+/// Near-valid strings, see [`MalformedFMRI`]
+pub fn malformed_fmri() -> impl Strategy<Value = MalformedFMRI> {
+    (fmri(), 0..MISTAKES, any::<usize>())
+        .prop_map(|(fmri, mistake, position)| MalformedFMRI(malform(&fmri, mistake, position)))
+}
@@ -0,0 +1,114 @@
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::{
+    fuzz::{malform, timestamp, MalformedFMRI, CATEGORIES, MISTAKES, PUBLISHERS},
+    version::{segment::Segment, Version},
+    FMRIList, Publisher, FMRI,
+};
+
+/// Characters which can start package name component or publisher
+const FIRST_CHARACTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// Characters which can continue package name component
+const PACKAGE_NAME_CHARACTERS: &[u8] =
+    b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_-.+";
+
+/// Characters which can continue publisher
+const PUBLISHER_CHARACTERS: &[u8] =
+    b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789-.";
+
+/// Returns name which starts with letter or digit and continues with given characters
+fn name(u: &mut Unstructured, characters: &[u8], max_len: usize) -> Result<String> {
+    let mut name = String::from(*u.choose(FIRST_CHARACTERS)? as char);
+    for _ in 0..u.int_in_range(0..=max_len)? {
+        name.push(*u.choose(characters)? as char);
+    }
+    Ok(name)
+}
+
+/// Returns mostly small numbers like in real versions
+fn number(u: &mut Unstructured) -> Result<u64> {
+    match u.int_in_range(0..=9)? {
+        0 => u64::arbitrary(u),
+        1..=4 => u.int_in_range(10..=2100),
+        _ => u.int_in_range(0..=9),
+    }
+}
+
+impl<'a> Arbitrary<'a> for Segment {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let numbers = (0..u.int_in_range(1..=5)?)
+            .map(|_| number(u))
+            .collect::<Result<_>>()?;
+        Ok(Segment::new(numbers).expect("segment has at least one number"))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Version {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut builder = Version::builder().component_version(u.arbitrary()?);
+        if u.arbitrary()? {
+            builder = builder.build_version(u.arbitrary()?);
+        }
+        if u.arbitrary()? {
+            builder = builder.branch_version(u.arbitrary()?);
+        }
+        if u.arbitrary()? {
+            builder = builder.timestamp(timestamp((
+                u.int_in_range(1970..=2099)?,
+                u.int_in_range(1..=12)?,
+                u.int_in_range(1..=28)?,
+                u.int_in_range(0..=23)?,
+                u.int_in_range(0..=59)?,
+                u.int_in_range(0..=59)?,
+            )));
+        }
+        Ok(builder.build().expect("generated timestamp is valid"))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Publisher {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let publisher = match u.ratio(1, 2)? {
+            true => u.choose(&PUBLISHERS)?.to_string(),
+            false => name(u, PUBLISHER_CHARACTERS, 20)?,
+        };
+        Ok(Publisher::new(publisher).expect("generated publisher is valid"))
+    }
+}
+
+impl<'a> Arbitrary<'a> for FMRI {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut components = vec![];
+        if u.ratio(1, 2)? {
+            components.push(u.choose(&CATEGORIES)?.to_string());
+        }
+        for _ in 0..u.int_in_range(1..=3)? {
+            components.push(name(u, PACKAGE_NAME_CHARACTERS, 15)?);
+        }
+
+        let mut fmri = FMRI::new_from_package_name(components.join("/"))
+            .expect("generated package name is valid");
+        if let Some(publisher) = u.arbitrary()? {
+            fmri.change_publisher(publisher);
+        }
+        if let Some(version) = u.arbitrary()? {
+            fmri.change_version(version);
+        }
+        Ok(fmri)
+    }
+}
+
+impl<'a> Arbitrary<'a> for FMRIList {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_iter()?.collect()
+    }
+}
+
+impl<'a> Arbitrary<'a> for MalformedFMRI {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let fmri = u.arbitrary()?;
+        let mistake = u.choose_index(MISTAKES)?;
+        Ok(Self(malform(&fmri, mistake, u.arbitrary()?)))
+    }
+}
@@ -1,3 +1,11 @@
+use alloc::{
+    borrow::ToOwned,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
 /// Checks if inserted string contains @
 pub fn check_character_collision(string: &str) -> Result<(), String> {
     for char in string.chars() {
@@ -15,3 +23,115 @@ pub fn remove_first_and_last_characters(string: &str, character: char) -> String
         .trim_end_matches(character)
         .to_owned()
 }
+
+/// Checks if string matches glob pattern, `*` matches any sequence and `?` any single character
+pub fn glob_match(pattern: &str, string: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let string: Vec<char> = string.chars().collect();
+
+    let (mut p, mut s) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while s < string.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, s));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == string[s] => {
+                p += 1;
+                s += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    s = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Checks if timestamp has pkg(5) format "20171212T185746Z"
+pub fn check_timestamp(timestamp: &str) -> Result<(), String> {
+    let valid = timestamp.len() == 16
+        && timestamp.char_indices().all(|(index, c)| match index {
+            8 => c == 'T',
+            15 => c == 'Z',
+            _ => c.is_ascii_digit(),
+        });
+    if !valid {
+        return Err(format!(
+            "invalid timestamp \"{}\", expected format is YYYYMMDDTHHMMSSZ",
+            timestamp
+        ));
+    }
+    Ok(())
+}
+
+/// Checks if package name follows pkg(5) grammar, returns index of invalid character and reason
+pub fn check_package_name(package_name: &str) -> Result<(), (usize, String)> {
+    let mut start = 0;
+    for component in package_name.split('/') {
+        check_name(component, |c| matches!(c, '_' | '-' | '.' | '+'))
+            .map_err(|(index, reason)| (start + index, reason))?;
+        start += component.len() + 1;
+    }
+    Ok(())
+}
+
+/// Checks if publisher follows pkg(5) grammar, returns index of invalid character and reason
+pub fn check_publisher_name(publisher: &str) -> Result<(), (usize, String)> {
+    check_name(publisher, |c| matches!(c, '-' | '.'))
+}
+
+/// Name must start with letter or digit and continue with letters, digits or allowed characters
+fn check_name(name: &str, allowed: fn(char) -> bool) -> Result<(), (usize, String)> {
+    for (index, c) in name.char_indices() {
+        if !(c.is_ascii_alphanumeric() || (index > 0 && allowed(c))) {
+            return Err((index, format!("invalid character '{}'", c)));
+        }
+    }
+    if name.is_empty() {
+        return Err((0, "empty name".to_owned()));
+    }
+    Ok(())
+}
+
+/// Percent-encodes all characters except ASCII letters, digits and "-._~"
+pub fn percent_encode(string: &str) -> String {
+    string
+        .bytes()
+        .map(|byte| match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Decodes percent-encoded string
+pub fn percent_decode(string: &str) -> Result<String, String> {
+    let mut bytes = vec![];
+    let mut rest = string.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte != b'%' {
+            bytes.push(byte);
+            rest = tail;
+            continue;
+        }
+        let hex = tail
+            .get(..2)
+            .and_then(|hex| core::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            .ok_or(format!("invalid percent-encoding in \"{}\"", string))?;
+        bytes.push(hex);
+        rest = &tail[2..];
+    }
+    String::from_utf8(bytes).map_err(|_| format!("invalid UTF-8 in \"{}\"", string))
+}
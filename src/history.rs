@@ -0,0 +1,381 @@
+use std::fs;
+use std::path::Path;
+
+use roxmltree::{Document, Node};
+
+use crate::{fmri_list::diff::FMRIListDiff, FMRIList, FMRI};
+
+/// Directory with history of image operations, relative to image root
+pub const HISTORY_DIR: &str = "var/pkg/history";
+
+/// [`HistoryEntry`] is one operation recorded in image history (`var/pkg/history/*.xml`)
+///
+/// Start and end states are lists of [`FMRIs`][FMRI] changed by the operation, they are parsed
+/// from package changes of the recorded plan ("old -> new", "None" is missing package).
+///
+/// # Examples
+///
+/// ```
+/// use fmri::history::HistoryEntry;
+/// let entry = HistoryEntry::parse(r#"<?xml version="1.0" encoding="utf-8"?>
+/// <history>
+///   <client name="pkg" version="e2c3bca5">
+///     <args><arg><![CDATA[/usr/bin/pkg]]></arg><arg><![CDATA[update]]></arg><arg><![CDATA[bash]]></arg></args>
+///   </client>
+///   <operation id="1693908930.654321" name="update" username="root" userid="0"
+///       result="Succeeded" start_time="20230905T101530Z" end_time="20230905T101612Z">
+///     <end_state><![CDATA[pkg://openindiana.org/shell/bash@5.2.15-2023.0.0.1:20230801T080000Z -> pkg://openindiana.org/shell/bash@5.2.21-2023.0.0.0:20230901T080000Z
+/// ]]></end_state>
+///   </operation>
+/// </history>"#).unwrap();
+///
+/// assert_eq!(entry.get_operation_ref(), "update");
+/// assert_eq!(entry.get_client_args_ref().join(" "), "/usr/bin/pkg update bash");
+/// assert!(entry.is_succeeded());
+/// assert!(entry.diff().get_upgraded_ref().contains_key("shell/bash"));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct HistoryEntry {
+    client_name: String,
+    client_version: Option<String>,
+    client_args: Vec<String>,
+    operation: String,
+    /// Time in pkg(5) format, like "20230905T101530Z"
+    start_time: Option<String>,
+    end_time: Option<String>,
+    userid: Option<u32>,
+    username: Option<String>,
+    /// "Succeeded" or "Failed" with reason, like ["Failed", "Constrained"]
+    result: Vec<String>,
+    be: Option<String>,
+    new_be: Option<String>,
+    start_state: FMRIList,
+    end_state: FMRIList,
+    errors: Vec<String>,
+}
+
+impl HistoryEntry {
+    /// Parses history XML file
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if XML is invalid, doesn't contain client and
+    /// operation or recorded plan contains invalid package change
+    pub fn parse(xml: &str) -> Result<Self, String> {
+        let document = Document::parse(xml).map_err(|e| format!("invalid XML: {}", e))?;
+        let root = document.root_element();
+        if !root.has_tag_name("history") {
+            return Err(format!(
+                "expected <history>, found <{}>",
+                root.tag_name().name()
+            ));
+        }
+        let client = child(root, "client").ok_or("history without <client>")?;
+        let operation = child(root, "operation").ok_or("history without <operation>")?;
+        let attribute = |name: &str| operation.attribute(name).map(str::to_owned);
+        // end state contains only package changes, full plan is recorded before execution
+        let (start_state, end_state) =
+            match child(operation, "end_state").or_else(|| child(operation, "start_state")) {
+                Some(plan) => plan_changes(plan)?,
+                None => Default::default(),
+            };
+
+        Ok(Self {
+            client_name: client.attribute("name").unwrap_or_default().to_owned(),
+            client_version: client.attribute("version").map(str::to_owned),
+            client_args: child(client, "args")
+                .map(|args| texts(args, "arg"))
+                .unwrap_or_default(),
+            operation: attribute("name").ok_or("operation without name")?,
+            start_time: attribute("start_time"),
+            end_time: attribute("end_time"),
+            userid: match operation.attribute("userid") {
+                Some(userid) => Some(
+                    userid
+                        .parse()
+                        .map_err(|_| format!("invalid userid \"{}\"", userid))?,
+                ),
+                None => None,
+            },
+            username: attribute("username"),
+            result: operation
+                .attribute("result")
+                .map(|result| result.split(", ").map(str::to_owned).collect())
+                .unwrap_or_default(),
+            be: attribute("be"),
+            new_be: attribute("new_be"),
+            start_state,
+            end_state,
+            errors: child(operation, "errors")
+                .map(|errors| texts(errors, "error"))
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Returns name of client, like "pkg" or "packagemanager"
+    pub fn get_client_name_ref(&self) -> &String {
+        &self.client_name
+    }
+
+    pub fn get_client_version_ref(&self) -> &Option<String> {
+        &self.client_version
+    }
+
+    /// Returns command line of client
+    pub fn get_client_args_ref(&self) -> &Vec<String> {
+        &self.client_args
+    }
+
+    /// Returns operation name, like "install", "uninstall" or "update"
+    pub fn get_operation_ref(&self) -> &String {
+        &self.operation
+    }
+
+    pub fn get_start_time_ref(&self) -> &Option<String> {
+        &self.start_time
+    }
+
+    pub fn get_end_time_ref(&self) -> &Option<String> {
+        &self.end_time
+    }
+
+    pub fn get_userid(&self) -> Option<u32> {
+        self.userid
+    }
+
+    pub fn get_username_ref(&self) -> &Option<String> {
+        &self.username
+    }
+
+    pub fn get_result_ref(&self) -> &Vec<String> {
+        &self.result
+    }
+
+    pub fn is_succeeded(&self) -> bool {
+        self.result
+            .first()
+            .is_some_and(|result| result == "Succeeded")
+    }
+
+    /// Returns boot environment of the operation
+    pub fn get_be_ref(&self) -> &Option<String> {
+        &self.be
+    }
+
+    /// Returns boot environment created by the operation
+    pub fn get_new_be_ref(&self) -> &Option<String> {
+        &self.new_be
+    }
+
+    pub fn get_start_state_ref(&self) -> &FMRIList {
+        &self.start_state
+    }
+
+    pub fn get_end_state_ref(&self) -> &FMRIList {
+        &self.end_state
+    }
+
+    pub fn get_errors_ref(&self) -> &Vec<String> {
+        &self.errors
+    }
+
+    /// Returns changes between start and end state
+    pub fn diff(&self) -> FMRIListDiff {
+        self.start_state.diff(&self.end_state)
+    }
+
+    /// Returns [`FMRIs`][FMRI] in end state, which weren't in start state
+    pub fn installed(&self) -> FMRIList {
+        self.end_state
+            .iter()
+            .filter(|fmri| !self.start_state.get_ref().contains(fmri))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns [`FMRIs`][FMRI] in start state, which aren't in end state
+    pub fn removed(&self) -> FMRIList {
+        self.start_state
+            .iter()
+            .filter(|fmri| !self.end_state.get_ref().contains(fmri))
+            .cloned()
+            .collect()
+    }
+}
+
+/// [`History`] is operation history of image sorted from the oldest operation
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use fmri::{FMRI, history::History};
+/// let history = History::read_image(Path::new("/mnt/oi")).unwrap();
+/// for entry in history.installations_of(&FMRI::parse_raw("shell/bash@5.2").unwrap()) {
+///     println!("{:?} {}", entry.get_end_time_ref(), entry.get_operation_ref());
+/// }
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct History(Vec<HistoryEntry>);
+
+impl History {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads history of image mounted at root
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if some history file can't be read or parsed
+    pub fn read_image(root: &Path) -> Result<Self, String> {
+        Self::read_dir(&root.join(HISTORY_DIR))
+    }
+
+    /// Reads all `.xml` files in history directory
+    ///
+    /// Files are named by time of operation, so they are sorted by name.
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if some history file can't be read or parsed
+    pub fn read_dir(directory: &Path) -> Result<Self, String> {
+        let mut files: Vec<_> = fs::read_dir(directory)
+            .map_err(|e| format!("can't read {}: {}", directory.display(), e))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|extension| extension == "xml"))
+            .collect();
+        files.sort();
+
+        files
+            .iter()
+            .map(|path| {
+                let xml = fs::read_to_string(path)
+                    .map_err(|e| format!("can't read {}: {}", path.display(), e))?;
+                HistoryEntry::parse(&xml).map_err(|e| format!("{}: {}", path.display(), e))
+            })
+            .collect()
+    }
+
+    pub fn add(&mut self, entry: HistoryEntry) {
+        self.0.push(entry)
+    }
+
+    pub fn get(self) -> Vec<HistoryEntry> {
+        self.0
+    }
+
+    pub fn get_ref(&self) -> &Vec<HistoryEntry> {
+        &self.0
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, HistoryEntry> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns succeeded operations, which installed matching [`FMRI`]
+    ///
+    /// [`FMRI`] without version matches all versions of the package.
+    pub fn installations_of(&self, fmri: &FMRI) -> Vec<&HistoryEntry> {
+        self.succeeded()
+            .filter(|entry| entry.installed().iter().any(|other| matches(fmri, other)))
+            .collect()
+    }
+
+    /// Returns succeeded operations, which removed matching [`FMRI`]
+    ///
+    /// [`FMRI`] without version matches all versions of the package.
+    pub fn removals_of(&self, fmri: &FMRI) -> Vec<&HistoryEntry> {
+        self.succeeded()
+            .filter(|entry| entry.removed().iter().any(|other| matches(fmri, other)))
+            .collect()
+    }
+
+    fn succeeded(&self) -> impl Iterator<Item = &HistoryEntry> {
+        self.0.iter().filter(|entry| entry.is_succeeded())
+    }
+}
+
+impl FromIterator<HistoryEntry> for History {
+    fn from_iter<T: IntoIterator<Item = HistoryEntry>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<'a> IntoIterator for &'a History {
+    type Item = &'a HistoryEntry;
+    type IntoIter = std::slice::Iter<'a, HistoryEntry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// Checks package name and version (if present) of [`FMRI`]
+fn matches(fmri: &FMRI, other: &FMRI) -> bool {
+    fmri.package_name_eq(other)
+        && match (fmri.get_version_ref(), other.get_version_ref()) {
+            (None, _) => true,
+            (Some(version), Some(other)) => other.is_successor(version),
+            (Some(_), None) => false,
+        }
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(name))
+}
+
+/// Returns texts of children with given name
+fn texts(node: Node, name: &str) -> Vec<String> {
+    node.children()
+        .filter(|child| child.has_tag_name(name))
+        .map(|child| child.text().unwrap_or_default().trim().to_owned())
+        .collect()
+}
+
+/// Parses package changes of recorded plan into start and end state
+///
+/// Full plan (pkg(5) `str(ImagePlan)`) starts with solver statistics and lists changes after
+/// "Package version changes:" line, short plan contains only changes. Each change is
+/// "old -> new" line, "None" is missing package.
+fn plan_changes(node: Node) -> Result<(FMRIList, FMRIList), String> {
+    let plan = node.text().unwrap_or_default();
+    let changes = match plan.split_once("Package version changes:") {
+        Some((_, changes)) => changes
+            .trim_start()
+            .split("\n\n")
+            .next()
+            .unwrap_or_default(),
+        None if plan.starts_with("Solver:") || plan.starts_with("UNEVALUATED") => "",
+        None => plan,
+    };
+
+    let mut start_state = FMRIList::new();
+    let mut end_state = FMRIList::new();
+    for change in changes
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+    {
+        let (old, new) = change
+            .split_once(" -> ")
+            .ok_or_else(|| format!("invalid package change \"{}\"", change))?;
+        for (raw_fmri, state) in [(old, &mut start_state), (new, &mut end_state)] {
+            if raw_fmri != "None" {
+                state.add(
+                    FMRI::parse_raw(raw_fmri)
+                        .map_err(|e| format!("invalid package change \"{}\": {}", change, e))?,
+                );
+            }
+        }
+    }
+    Ok((start_state, end_state))
+}
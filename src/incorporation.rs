@@ -0,0 +1,214 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use crate::{
+    manifest::{action::Action, Manifest},
+    FMRIList, Version, FMRI,
+};
+
+/// [`Violation`] is package, which version isn't allowed by an incorporation
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct Violation {
+    fmri: FMRI,
+    constraint: FMRI,
+    incorporation: FMRI,
+}
+
+impl Violation {
+    /// Returns checked package
+    pub fn get_fmri_ref(&self) -> &FMRI {
+        &self.fmri
+    }
+
+    /// Returns [`FMRI`] from `depend type=incorporate`, which package doesn't satisfy
+    pub fn get_constraint_ref(&self) -> &FMRI {
+        &self.constraint
+    }
+
+    /// Returns incorporation package, which contains the constraint
+    pub fn get_incorporation_ref(&self) -> &FMRI {
+        &self.incorporation
+    }
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} doesn't match {} incorporated by {}",
+            self.fmri, self.constraint, self.incorporation
+        )
+    }
+}
+
+/// [`Incorporations`] checks package versions against incorporation packages
+///
+/// Incorporation constrains versions of packages with `depend type=incorporate` actions,
+/// package version must satisfy [`Version::is_successor`].
+///
+/// # Examples
+///
+/// ```
+/// use fmri::{FMRI, FMRIList, incorporation::Incorporations, manifest::Manifest};
+/// let mut incorporations = Incorporations::new();
+/// incorporations.add_manifest(&Manifest::parse("\
+/// set name=pkg.fmri value=pkg://openindiana.org/consolidation/userland/userland-incorporation@0.5.11-2023.0.0.1
+/// depend fmri=shell/bash@5.2-2023.0 type=incorporate
+/// ").unwrap()).unwrap();
+///
+/// let list = FMRIList::from(vec![
+///     FMRI::parse_raw("shell/bash@5.2-2023.0.0.3").unwrap(),
+///     FMRI::parse_raw("shell/zsh@5.9").unwrap(),
+/// ]);
+/// assert!(incorporations.check(&list).is_empty());
+///
+/// let list = FMRIList::from(vec![FMRI::parse_raw("shell/bash@5.1-2022.0.0.1").unwrap()]);
+/// assert_eq!(incorporations.check(&list).len(), 1);
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Incorporations {
+    /// Constraints of every package name with incorporation they come from
+    constraints: BTreeMap<String, Vec<(FMRI, FMRI)>>,
+}
+
+impl Incorporations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds constraints from incorporation [`Manifest`], it must contain `pkg.fmri`
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if [`FMRI`] is missing or some constraint is
+    /// invalid or without version
+    pub fn add_manifest(&mut self, manifest: &Manifest) -> Result<(), String> {
+        let incorporation = manifest
+            .get_fmri()?
+            .ok_or("manifest doesn't contain pkg.fmri")?;
+
+        for action in manifest.actions_of_type("depend").filter(|action| {
+            action
+                .get_attribute("type")
+                .is_some_and(|t| t == "incorporate")
+        }) {
+            for raw_fmri in action.get_attribute_values("fmri") {
+                self.add(FMRI::parse_raw(raw_fmri)?, incorporation.clone())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Adds constraint from incorporation
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if constraint doesn't have version
+    pub fn add(&mut self, constraint: FMRI, incorporation: FMRI) -> Result<(), String> {
+        if !constraint.has_version() {
+            return Err(format!(
+                "incorporated {} from {} doesn't have version",
+                constraint, incorporation
+            ));
+        }
+        self.constraints
+            .entry(constraint.get_package_name_as_ref_string().clone())
+            .or_default()
+            .push((constraint, incorporation));
+        Ok(())
+    }
+
+    /// Returns constraints of package name with incorporations they come from
+    pub fn get(&self, package_name: &str) -> Option<&Vec<(FMRI, FMRI)>> {
+        self.constraints.get(package_name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.constraints.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.constraints.is_empty()
+    }
+
+    /// Returns [`Violations`][Violation] of all constraints by packages in [`FMRIList`]
+    ///
+    /// Packages without version and packages which aren't incorporated are skipped.
+    pub fn check(&self, list: &FMRIList) -> Vec<Violation> {
+        let mut violations = vec![];
+
+        for fmri in list {
+            let (Some(version), Some(constraints)) = (
+                fmri.get_version_ref(),
+                self.get(fmri.get_package_name_as_ref_string()),
+            ) else {
+                continue;
+            };
+
+            for (constraint, incorporation) in constraints {
+                let satisfied = match constraint.get_version_ref() {
+                    Some(incorporated) => version.is_successor(incorporated),
+                    None => true,
+                };
+                if !satisfied {
+                    violations.push(Violation {
+                        fmri: fmri.clone(),
+                        constraint: constraint.clone(),
+                        incorporation: incorporation.clone(),
+                    });
+                }
+            }
+        }
+
+        violations
+    }
+
+    /// Generates incorporation [`Manifest`] for the newest versions of packages in [`FMRIList`]
+    ///
+    /// Constraints are sorted by package name, they don't contain publisher nor timestamp.
+    /// Packages without version are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmri::{FMRI, FMRIList, incorporation::Incorporations};
+    /// let list = FMRIList::from(vec![
+    ///     FMRI::parse_raw("pkg://openindiana.org/shell/bash@5.2-2023.0.0.3:20230101T000000Z").unwrap(),
+    ///     FMRI::parse_raw("shell/bash@5.1-2022.0.0.1").unwrap(),
+    /// ]);
+    /// let incorporation = FMRI::parse_raw("pkg://openindiana.org/my-incorporation@1.0").unwrap();
+    /// assert_eq!(
+    ///     Incorporations::generate(&incorporation, &list).to_string(),
+    ///     "set name=pkg.fmri value=pkg://openindiana.org/my-incorporation@1.0\n\
+    ///      depend fmri=pkg:/shell/bash@5.2-2023.0.0.3 type=incorporate\n"
+    /// );
+    /// ```
+    pub fn generate(incorporation: &FMRI, list: &FMRIList) -> Manifest {
+        let mut manifest = Manifest::new();
+
+        let mut set_fmri = Action::new("set".to_owned());
+        set_fmri.add_attribute("name".to_owned(), "pkg.fmri".to_owned());
+        set_fmri.add_attribute("value".to_owned(), incorporation.to_string());
+        manifest.add(set_fmri);
+
+        for fmri in &list.latest() {
+            let Some(version) = fmri.get_version_ref() else {
+                continue;
+            };
+            let mut version: Version = version.clone();
+            version.remove_timestamp();
+
+            let mut constraint = fmri.clone();
+            constraint.remove_publisher();
+            constraint.change_version(version);
+
+            let mut depend = Action::new("depend".to_owned());
+            depend.add_attribute("fmri".to_owned(), constraint.to_string());
+            depend.add_attribute("type".to_owned(), "incorporate".to_owned());
+            manifest.add(depend);
+        }
+
+        manifest
+    }
+}
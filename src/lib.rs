@@ -1,17 +1,64 @@
-use std::cmp::Ordering;
-use std::cmp::Ordering::Equal;
-use std::fmt::{Debug, Display, Formatter};
+#![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+
+use alloc::{
+    borrow::ToOwned,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::cmp::Ordering;
+use core::fmt::{Debug, Display, Formatter};
+
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::helpers::{check_character_collision, remove_first_and_last_characters};
+use crate::helpers::{
+    check_character_collision, check_package_name, check_publisher_name, glob_match,
+    remove_first_and_last_characters,
+};
 
-pub use self::{fmri_list::FMRIList, publisher::Publisher, version::Version};
+pub use self::{
+    fmri_list::FMRIList, parse_options::ParseOptions, publisher::Publisher, version::Version,
+};
 
+#[cfg(feature = "capi")]
+pub mod capi;
+#[cfg(feature = "manifest")]
+pub mod conflict;
 pub mod fmri_list;
+#[cfg(any(feature = "arbitrary", feature = "proptest"))]
+pub mod fuzz;
 mod helpers;
+#[cfg(feature = "history")]
+pub mod history;
+#[cfg(feature = "manifest")]
+pub mod incorporation;
+#[cfg(feature = "manifest")]
+pub mod manifest;
+#[cfg(feature = "manifest")]
+pub mod mediator;
+#[cfg(feature = "manifest")]
+pub mod mogrify;
+pub mod parse_options;
+#[cfg(feature = "payload")]
+pub mod payload;
+#[cfg(feature = "pkgdepend")]
+pub mod pkgdepend;
 pub mod publisher;
-#[cfg(test)]
+pub mod purl;
+#[cfg(all(feature = "manifest", feature = "json"))]
+pub mod rename;
+#[cfg(feature = "sbom")]
+pub mod sbom;
+#[cfg(feature = "manifest")]
+pub mod search;
+#[cfg(feature = "signature")]
+pub mod signature;
+// the oldest tests pass `&"...".to_owned()` where `&str` is expected
+#[cfg(all(test, feature = "std"))]
+#[allow(clippy::unnecessary_to_owned)]
 mod tests;
 pub mod version;
 
@@ -25,7 +72,8 @@ pub mod version;
 /// pkg://solaris/system/library
 /// pkg://solaris/system/library@0.5.11-0.175.1.0.0.2.1:20120919T082311Z
 /// ```
-#[derive(PartialEq, Serialize, Deserialize, Clone, Eq, Hash)]
+#[derive(PartialEq, Clone, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct FMRI {
     /// Publisher is optional
     publisher: Option<Publisher>,
@@ -37,13 +85,16 @@ pub struct FMRI {
 
 impl FMRI {
     /// Returns [`FMRI`] with given package name
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if package name is empty or contains '@'
     pub fn new_from_package_name(mut package_name: String) -> Result<Self, String> {
-        if package_name.is_empty() {
-            panic!("package name can't be empty")
-        }
-
         check_character_collision(&package_name)?;
         package_name = remove_first_and_last_characters(&package_name, '/').to_owned();
+        if package_name.is_empty() {
+            return Err("package name can't be empty".to_owned());
+        }
 
         Ok(Self {
             publisher: None,
@@ -79,7 +130,7 @@ impl FMRI {
                 let (_, end_str) = package_name
                     .trim_start_matches("pkg://")
                     .split_once('/')
-                    .expect("Fmri must contain \"/package_name\"");
+                    .ok_or("Fmri must contain \"/package_name\"")?;
                 package_name = end_str.to_owned()
             }
             Err(e) => return Err(e),
@@ -89,7 +140,9 @@ impl FMRI {
             Ok(None) => {}
             Ok(Some(v)) => {
                 version = Some(v);
-                let (start_str, _) = package_name.split_once('@').expect("error");
+                let (start_str, _) = package_name
+                    .split_once('@')
+                    .ok_or("version must follow package name")?;
                 package_name = start_str.to_owned()
             }
             Err(e) => return Err(e),
@@ -105,12 +158,156 @@ impl FMRI {
         Ok(fmri)
     }
 
+    /// Returns [`FMRI`] from raw fmri using given [`ParseOptions`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmri::{FMRI, ParseOptions};
+    /// let strict = ParseOptions::strict();
+    /// assert!(FMRI::parse_with_options("pkg://publisher/test@1-1:20220913T082027Z", strict).is_ok());
+    /// assert!(FMRI::parse_with_options("fmri=test@1", strict).is_err());
+    /// assert!(FMRI::parse_with_options("test@1.2abc", strict).is_err());
+    /// assert!(FMRI::parse_with_options("fmri=test@1", ParseOptions::lenient()).is_ok());
+    /// ```
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if fmri is invalid
+    pub fn parse_with_options(raw_fmri: &str, options: ParseOptions) -> Result<Self, String> {
+        if !options.is_strict() {
+            return Self::parse_raw(raw_fmri);
+        }
+
+        let at_index = |name: &'static str, offset: usize| {
+            move |(index, reason): (usize, String)| {
+                format!("invalid {}: {} at index {}", name, reason, offset + index)
+            }
+        };
+
+        let mut publisher: Option<Publisher> = None;
+        let (mut offset, mut rest) = (0, raw_fmri);
+        if let Some(after_scheme) = raw_fmri.strip_prefix("pkg://") {
+            let (name, after_publisher) = after_scheme
+                .split_once('/')
+                .ok_or("missing package name after publisher")?;
+            check_publisher_name(name).map_err(at_index("publisher", 6))?;
+            publisher = Some(Publisher::new(name.to_owned())?);
+            (offset, rest) = (6 + name.len() + 1, after_publisher);
+        } else if let Some(after_scheme) = raw_fmri.strip_prefix("pkg:/") {
+            (offset, rest) = (5, after_scheme);
+        }
+
+        let (package_name, version) = match rest.split_once('@') {
+            None => (rest, None),
+            Some((package_name, version)) => (package_name, Some(version)),
+        };
+        check_package_name(package_name).map_err(at_index("package name", offset))?;
+
+        Ok(Self {
+            publisher,
+            package_name: package_name.to_owned(),
+            version: version
+                .map(|version| Version::parse_with_options(version, options))
+                .transpose()?,
+        })
+    }
+
+    /// Returns pkg(5) canonical form of [`FMRI`]
+    ///
+    /// Canonical form always starts with "pkg:/" (or "pkg://publisher/"), package name is without
+    /// extra slashes and version contains only parts which are present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmri::FMRI;
+    /// let fmri = FMRI::parse_raw("fmri=pkg://publisher//system/library/@0.5.11-2023.0.0.1").unwrap();
+    /// assert_eq!(fmri.canonicalize(), "pkg://publisher/system/library@0.5.11-2023.0.0.1");
+    ///
+    /// let fmri = FMRI::parse_raw("system//library").unwrap();
+    /// assert_eq!(fmri.canonicalize(), "pkg:/system/library");
+    /// ```
+    pub fn canonicalize(&self) -> String {
+        let package_name = self
+            .package_name
+            .split('/')
+            .filter(|component| !component.is_empty())
+            .collect::<Vec<&str>>()
+            .join("/");
+
+        let mut canonical = match self.get_publisher_as_ref_string() {
+            Some(publisher) => format!("pkg://{}/{}", publisher, package_name),
+            None => format!("pkg:/{}", package_name),
+        };
+        if let Some(version) = &self.version {
+            canonical.push_str(&version.to_string());
+        }
+        canonical
+    }
+
     /// Checks if package names are same
     pub fn package_name_eq(&self, comparing_to: &FMRI) -> bool {
         self.get_package_name_as_ref_string()
             .eq(comparing_to.get_package_name_as_ref_string())
     }
 
+    /// Checks if [`FMRI`] matches pkg(5) style pattern
+    ///
+    /// Package name part can contain `*` and `?` wildcards. Pattern without leading `pkg:/` or `/`
+    /// matches also trailing components of the package name (`library` matches `system/library`).
+    /// Publisher and version are checked only if the pattern contains them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmri::FMRI;
+    /// let fmri = FMRI::parse_raw("pkg://openindiana.org/system/library@0.5.11-2023.0.0.1").unwrap();
+    /// assert!(fmri.matches_pattern("library"));
+    /// assert!(fmri.matches_pattern("pkg:/system/*"));
+    /// assert!(fmri.matches_pattern("pkg://openindiana.org/system/library@0.5.*"));
+    /// assert!(!fmri.matches_pattern("/library"));
+    /// ```
+    pub fn matches_pattern(&self, pattern: &str) -> bool {
+        let mut pattern = pattern.trim_start_matches("fmri=");
+        let mut anchored = false;
+
+        if let Some(rest) = pattern.strip_prefix("pkg://") {
+            let Some((publisher, rest)) = rest.split_once('/') else {
+                return false;
+            };
+            match self.get_publisher_as_ref_string() {
+                Some(own) if glob_match(publisher, own) => {}
+                _ => return false,
+            }
+            pattern = rest;
+            anchored = true;
+        } else if let Some(rest) = pattern.strip_prefix("pkg:/") {
+            pattern = rest;
+            anchored = true;
+        } else if let Some(rest) = pattern.strip_prefix('/') {
+            pattern = rest;
+            anchored = true;
+        }
+
+        if let Some((name, version)) = pattern.split_once('@') {
+            match self.get_version_as_string() {
+                Some(own) if glob_match(version, own.trim_start_matches('@')) => {}
+                _ => return false,
+            }
+            pattern = name;
+        }
+
+        let package_name = self.get_package_name_as_ref_string();
+        if glob_match(pattern, package_name) {
+            return true;
+        }
+        !anchored
+            && package_name
+                .match_indices('/')
+                .any(|(index, _)| glob_match(pattern, &package_name[index + 1..]))
+    }
+
     pub fn get_package_name_as_string(self) -> String {
         self.package_name
     }
@@ -195,17 +392,20 @@ impl PartialOrd<Self> for FMRI {
 }
 
 impl Ord for FMRI {
-    /// Compares versions of FMRI
+    /// Compares versions of FMRI, missing version is older
+    ///
+    /// Same versions are decided by package name and publisher, so the ordering is total and
+    /// consistent with equality.
     fn cmp(&self, other: &Self) -> Ordering {
         self.version
-            .as_ref()
-            .and_then(|ver| other.version.as_ref().map(|ver2| ver.cmp(ver2)))
-            .unwrap_or(Equal)
+            .cmp(&other.version)
+            .then_with(|| self.package_name.cmp(&other.package_name))
+            .then_with(|| self.publisher.cmp(&other.publisher))
     }
 }
 
 impl Display for FMRI {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         let mut string: String = "".to_owned();
 
         if let Some(publisher) = self.get_publisher_as_ref_string() {
@@ -227,7 +427,7 @@ impl Display for FMRI {
 }
 
 impl Debug for FMRI {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self)
     }
 }
@@ -0,0 +1,177 @@
+use std::fmt::{Debug, Display, Formatter};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{manifest::action::Action, FMRI};
+
+pub mod action;
+
+/// [`Manifest`] is parsed pkg(5) package manifest, list of [`Actions`][Action]
+///
+/// # Examples
+///
+/// ```plain
+/// set name=pkg.fmri value=pkg://openindiana.org/shell/bash@5.2-2023.0.0.0
+/// set name=pkg.summary value="GNU Bourne Again Shell"
+/// depend fmri=pkg:/library/ncurses type=require
+/// file path=usr/bin/bash owner=root group=bin mode=0555 \
+///     pkg.depend.bypass-generate=.*
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Manifest(Vec<Action>);
+
+impl Manifest {
+    pub fn new() -> Self {
+        Self(vec![])
+    }
+
+    /// Parses [`Manifest`] from text
+    ///
+    /// Empty lines and comments are skipped, lines ending with '\' continue on the next line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmri::{FMRI, manifest::Manifest};
+    /// let manifest = Manifest::parse("\
+    /// set name=pkg.fmri value=pkg://openindiana.org/shell/bash@5.2
+    /// ## comment
+    /// depend fmri=pkg:/library/ncurses \\
+    ///     type=require
+    /// ").unwrap();
+    /// assert_eq!(manifest.len(), 2);
+    /// assert_eq!(manifest.get_fmri().unwrap(), Some(FMRI::parse_raw("pkg://openindiana.org/shell/bash@5.2").unwrap()));
+    /// ```
+    ///
+    /// # Error
+    ///
+    /// Returns a string with line number and error message
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut manifest = Self::new();
+
+        for (number, line) in logical_lines(text) {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            manifest.add(Action::parse(line).map_err(|e| format!("line {}: {}", number, e))?);
+        }
+
+        Ok(manifest)
+    }
+
+    pub fn add(&mut self, action: Action) {
+        self.0.push(action)
+    }
+
+    pub fn get(self) -> Vec<Action> {
+        self.0
+    }
+
+    pub fn get_ref(&self) -> &Vec<Action> {
+        &self.0
+    }
+
+    pub fn get_ref_mut(&mut self) -> &mut Vec<Action> {
+        &mut self.0
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Action> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns [`Actions`][Action] of given type
+    pub fn actions_of_type<'a>(&'a self, action_type: &'a str) -> impl Iterator<Item = &'a Action> {
+        self.0
+            .iter()
+            .filter(move |action| action.get_action_type_ref() == action_type)
+    }
+
+    /// Returns value of `set name=<name> value=<value>` action
+    pub fn get_set_value(&self, name: &str) -> Option<&String> {
+        self.actions_of_type("set")
+            .find(|action| action.get_attribute("name").is_some_and(|n| n == name))
+            .and_then(|action| action.get_attribute("value"))
+    }
+
+    /// Checks if `set name=<name> value=true` action is present
+    pub fn is_set_true(&self, name: &str) -> bool {
+        self.get_set_value(name)
+            .is_some_and(|value| value.eq_ignore_ascii_case("true"))
+    }
+
+    /// Returns [`FMRI`] from `set name=pkg.fmri` action, [`None`] if it isn't present
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if [`FMRI`] is invalid
+    pub fn get_fmri(&self) -> Result<Option<FMRI>, String> {
+        self.get_set_value("pkg.fmri")
+            .map(|fmri| FMRI::parse_raw(fmri))
+            .transpose()
+    }
+}
+
+/// Joins lines ending with '\' with the following line, returns number of the first line
+pub(crate) fn logical_lines(text: &str) -> Vec<(usize, String)> {
+    let mut lines: Vec<(usize, String)> = vec![];
+    let mut continued = false;
+
+    for (index, line) in text.lines().enumerate() {
+        let (content, continues) = match line.trim_end().strip_suffix('\\') {
+            Some(content) => (content, true),
+            None => (line, false),
+        };
+        match lines.last_mut() {
+            Some((_, joined)) if continued => {
+                joined.push(' ');
+                joined.push_str(content.trim());
+            }
+            _ => lines.push((index + 1, content.to_owned())),
+        }
+        continued = continues;
+    }
+
+    lines
+}
+
+impl FromIterator<Action> for Manifest {
+    fn from_iter<T: IntoIterator<Item = Action>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<'a> IntoIterator for &'a Manifest {
+    type Item = &'a Action;
+    type IntoIter = std::slice::Iter<'a, Action>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl Display for Manifest {
+    /// Writes one [`Action`] per line
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for action in &self.0 {
+            writeln!(f, "{}", action)?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for Manifest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
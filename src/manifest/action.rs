@@ -0,0 +1,279 @@
+use std::fmt::{Debug, Display, Formatter};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(doc)]
+use crate::manifest::Manifest;
+
+/// [`Action`] is one line of [`Manifest`]
+///
+/// # Examples
+///
+/// ```plain
+/// set name=pkg.fmri value=pkg://openindiana.org/shell/bash@5.2-2023.0.0.0
+/// depend fmri=pkg:/library/ncurses type=require
+/// file 7a4bd2b9... path=usr/bin/bash owner=root group=bin mode=0555
+/// dir path=usr/share/doc owner=root group=bin mode=0755
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Action {
+    action_type: String,
+    /// Payload hash written right after action type, it is optional
+    payload: Option<String>,
+    /// Attributes in order of appearance, key can be repeated
+    attributes: Vec<(String, String)>,
+}
+
+impl Action {
+    pub fn new(action_type: String) -> Self {
+        Self {
+            action_type,
+            payload: None,
+            attributes: vec![],
+        }
+    }
+
+    /// Parses [`Action`] from one (already joined) manifest line
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmri::manifest::action::Action;
+    /// let action = Action::parse("set name=pkg.summary value=\"GNU Bourne Again Shell\"").unwrap();
+    /// assert_eq!(action.get_action_type_ref(), "set");
+    /// assert_eq!(action.get_attribute("value").unwrap(), "GNU Bourne Again Shell");
+    /// ```
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if line isn't valid action
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let mut tokens = tokenize(line.trim())?.into_iter();
+
+        let action_type = match tokens.next() {
+            Some(Token::Word(action_type)) if is_valid_action_type(&action_type) => action_type,
+            _ => return Err(format!("invalid action type in \"{}\"", line.trim())),
+        };
+
+        let mut action = Self::new(action_type);
+        for (index, token) in tokens.enumerate() {
+            match token {
+                Token::Attribute(key, value) => action.attributes.push((key, value)),
+                Token::Word(payload) if index == 0 => action.payload = Some(payload),
+                Token::Word(word) => {
+                    return Err(format!(
+                        "attribute \"{}\" without value in \"{}\"",
+                        word,
+                        line.trim()
+                    ))
+                }
+            }
+        }
+
+        Ok(action)
+    }
+
+    pub fn get_action_type_ref(&self) -> &String {
+        &self.action_type
+    }
+
+    /// Returns payload hash, or value of "hash" attribute if there isn't payload
+    pub fn get_payload_ref(&self) -> Option<&String> {
+        self.payload.as_ref().or_else(|| self.get_attribute("hash"))
+    }
+
+    pub fn set_payload(&mut self, payload: Option<String>) {
+        self.payload = payload
+    }
+
+    pub fn get_attributes_ref(&self) -> &Vec<(String, String)> {
+        &self.attributes
+    }
+
+    pub fn get_attributes_ref_mut(&mut self) -> &mut Vec<(String, String)> {
+        &mut self.attributes
+    }
+
+    /// Returns the first value of attribute
+    pub fn get_attribute(&self, key: &str) -> Option<&String> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns all values of attribute
+    pub fn get_attribute_values(&self, key: &str) -> Vec<&String> {
+        self.attributes
+            .iter()
+            .filter(|(attribute, _)| attribute == key)
+            .map(|(_, value)| value)
+            .collect()
+    }
+
+    pub fn has_attribute(&self, key: &str) -> bool {
+        self.get_attribute(key).is_some()
+    }
+
+    /// Checks if attribute has value "true" (case insensitive)
+    pub fn is_attribute_true(&self, key: &str) -> bool {
+        self.get_attribute(key)
+            .is_some_and(|value| value.eq_ignore_ascii_case("true"))
+    }
+
+    /// Adds another value of attribute
+    pub fn add_attribute(&mut self, key: String, value: String) {
+        self.attributes.push((key, value))
+    }
+
+    /// Replaces all values of attribute with one value
+    pub fn set_attribute(&mut self, key: String, value: String) {
+        match self
+            .attributes
+            .iter()
+            .position(|(attribute, _)| *attribute == key)
+        {
+            None => self.attributes.push((key, value)),
+            Some(position) => {
+                self.attributes[position].1 = value;
+                let mut index = 0;
+                self.attributes.retain(|(attribute, _)| {
+                    index += 1;
+                    index - 1 == position || *attribute != key
+                });
+            }
+        }
+    }
+
+    pub fn remove_attribute(&mut self, key: &str) {
+        self.attributes.retain(|(attribute, _)| attribute != key)
+    }
+
+    /// Returns value of the key attribute, which identifies action in package
+    ///
+    /// `path` for file system actions, `fmri` for depend, `name` for set, payload for signature
+    /// and so on, [`None`] for unknown action types.
+    pub fn get_key_attribute(&self) -> Option<&String> {
+        match self.get_key_attribute_name() {
+            Some(key) => self.get_attribute(key),
+            None if self.action_type == "signature" => self.get_payload_ref(),
+            None => None,
+        }
+    }
+
+    /// Returns name of the key attribute, [`None`] for signature and unknown action types
+    pub fn get_key_attribute_name(&self) -> Option<&'static str> {
+        match self.action_type.as_str() {
+            "file" | "dir" | "link" | "hardlink" => Some("path"),
+            "depend" => Some("fmri"),
+            "set" | "driver" => Some("name"),
+            "group" => Some("groupname"),
+            "user" => Some("username"),
+            "legacy" => Some("pkg"),
+            "license" => Some("license"),
+            _ => None,
+        }
+    }
+}
+
+/// Action type must start with letter and contain only letters, digits, '-' and '_'
+fn is_valid_action_type(action_type: &str) -> bool {
+    action_type.starts_with(|c: char| c.is_ascii_alphabetic())
+        && action_type
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+enum Token {
+    Word(String),
+    Attribute(String, String),
+}
+
+/// Splits action line into words and key=value attributes, values can be quoted
+fn tokenize(line: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = line.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            return Ok(tokens);
+        }
+
+        let mut word = String::new();
+        while let Some(c) = chars.next_if(|c| !c.is_whitespace() && *c != '=') {
+            word.push(c);
+        }
+
+        if chars.next_if_eq(&'=').is_none() {
+            tokens.push(Token::Word(word));
+            continue;
+        }
+        if word.is_empty() {
+            return Err(format!("attribute without key in \"{}\"", line));
+        }
+
+        let mut value = String::new();
+        match chars.next_if(|c| *c == '"' || *c == '\'') {
+            Some(quote) => loop {
+                match chars.next() {
+                    None => {
+                        return Err(format!(
+                            "unterminated quoted value of \"{}\" in \"{}\"",
+                            word, line
+                        ))
+                    }
+                    Some('\\') if chars.peek().is_some_and(|c| *c == quote || *c == '\\') => {
+                        value.extend(chars.next())
+                    }
+                    Some(c) if c == quote => break,
+                    Some(c) => value.push(c),
+                }
+            },
+            None => {
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                    value.push(c);
+                }
+            }
+        }
+        tokens.push(Token::Attribute(word, value));
+    }
+}
+
+/// Quotes value like pkg(5) does, if it contains whitespace, quotes or is empty
+///
+/// Backslashes in quoted values are escaped.
+fn quote(value: &str) -> String {
+    if !value.is_empty() && !value.contains(|c: char| c.is_whitespace() || c == '"' || c == '\'') {
+        return value.to_owned();
+    }
+    let value = value.replace('\\', "\\\\");
+    if !value.contains('"') {
+        return format!("\"{}\"", value);
+    }
+    if !value.contains('\'') {
+        return format!("'{}'", value);
+    }
+    format!("\"{}\"", value.replace('"', "\\\""))
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.action_type)?;
+        if let Some(payload) = &self.payload {
+            write!(f, " {}", payload)?;
+        }
+        for (key, value) in &self.attributes {
+            write!(f, " {}={}", key, quote(value))?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for Action {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
@@ -0,0 +1,388 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use crate::{
+    manifest::{action::Action, Manifest},
+    version::segment::Segment,
+    FMRI,
+};
+
+/// [`Priority`] is `mediator-priority` of mediated link, site overrides vendor
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Default, Hash)]
+pub enum Priority {
+    #[default]
+    None,
+    Vendor,
+    Site,
+}
+
+impl Priority {
+    /// # Error
+    ///
+    /// Returns a string with error message if priority isn't "vendor" or "site"
+    pub fn parse(priority: &str) -> Result<Self, String> {
+        match priority {
+            "vendor" => Ok(Self::Vendor),
+            "site" => Ok(Self::Site),
+            _ => Err(format!("invalid mediator-priority \"{}\"", priority)),
+        }
+    }
+}
+
+/// [`Mediation`] is one alternative of mediator, taken from mediated `link` or `hardlink`
+///
+/// # Examples
+///
+/// ```
+/// use fmri::{manifest::action::Action, mediator::{Mediation, Priority}};
+/// let action = Action::parse("link path=usr/bin/python target=python3.9 mediator=python \
+///     mediator-version=3.9 mediator-priority=vendor").unwrap();
+/// let (mediator, mediation) = Mediation::from_action(&action).unwrap().unwrap();
+/// assert_eq!(mediator, "python");
+/// assert_eq!(mediation.get_version_ref().as_ref().unwrap().as_string(), "3.9");
+/// assert_eq!(mediation.get_priority(), Priority::Vendor);
+/// assert_eq!(mediation.to_string(), "mediator-version=3.9 mediator-priority=vendor");
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default, Hash)]
+pub struct Mediation {
+    version: Option<Segment>,
+    implementation: Option<String>,
+    implementation_version: Option<Segment>,
+    priority: Priority,
+}
+
+impl Mediation {
+    /// Returns mediator name and [`Mediation`] of link, [`None`] if link isn't mediated
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if mediator attributes are invalid
+    pub fn from_action(action: &Action) -> Result<Option<(String, Self)>, String> {
+        let Some(mediator) = action.get_attribute("mediator") else {
+            return Ok(None);
+        };
+        if mediator.is_empty()
+            || !mediator
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            return Err(format!("invalid mediator \"{}\"", mediator));
+        }
+
+        let mut mediation = Self::new();
+        if let Some(version) = action.get_attribute("mediator-version") {
+            mediation.version = Some(parse_dot_sequence(version)?);
+        }
+        if let Some(implementation) = action.get_attribute("mediator-implementation") {
+            mediation.set_implementation(implementation)?;
+        }
+        if let Some(priority) = action.get_attribute("mediator-priority") {
+            mediation.priority = Priority::parse(priority)?;
+        }
+        if mediation.version.is_none() && mediation.implementation.is_none() {
+            return Err(format!(
+                "mediated link of \"{}\" needs mediator-version or mediator-implementation",
+                mediator
+            ));
+        }
+        Ok(Some((mediator.clone(), mediation)))
+    }
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_version_ref(&self) -> &Option<Segment> {
+        &self.version
+    }
+
+    pub fn set_version(&mut self, version: Option<Segment>) {
+        self.version = version
+    }
+
+    pub fn get_implementation_ref(&self) -> &Option<String> {
+        &self.implementation
+    }
+
+    pub fn get_implementation_version_ref(&self) -> &Option<Segment> {
+        &self.implementation_version
+    }
+
+    /// Sets implementation from "name" or "name@version"
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if implementation is empty or its version is invalid
+    pub fn set_implementation(&mut self, implementation: &str) -> Result<(), String> {
+        let (name, version) = match implementation.split_once('@') {
+            Some((name, version)) => (name, Some(parse_dot_sequence(version)?)),
+            None => (implementation, None),
+        };
+        if name.is_empty() {
+            return Err(format!(
+                "invalid mediator-implementation \"{}\"",
+                implementation
+            ));
+        }
+        self.implementation = Some(name.to_owned());
+        self.implementation_version = version;
+        Ok(())
+    }
+
+    pub fn get_priority(&self) -> Priority {
+        self.priority
+    }
+
+    /// Checks if mediation satisfies explicit setting, [`None`] parts of the setting match
+    /// anything
+    pub fn satisfies(&self, setting: &Mediation) -> bool {
+        fn matches<T: PartialEq>(setting: &Option<T>, own: &Option<T>) -> bool {
+            setting.is_none() || setting == own
+        }
+        matches(&setting.version, &self.version)
+            && matches(&setting.implementation, &self.implementation)
+            && matches(
+                &setting.implementation_version,
+                &self.implementation_version,
+            )
+    }
+
+    /// Compares preference of mediations, the greater is selected
+    ///
+    /// Priority decides first, then the highest version and implementation version. Same
+    /// versions of different implementations are decided by implementation name.
+    pub fn cmp_preference(&self, other: &Mediation) -> Ordering {
+        (
+            self.priority,
+            &self.version,
+            &self.implementation_version,
+            Reverse(&self.implementation),
+        )
+            .cmp(&(
+                other.priority,
+                &other.version,
+                &other.implementation_version,
+                Reverse(&other.implementation),
+            ))
+    }
+}
+
+impl Display for Mediation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut attributes = vec![];
+        if let Some(version) = &self.version {
+            attributes.push(format!("mediator-version={}", version.as_string()));
+        }
+        if let Some(implementation) = &self.implementation {
+            match &self.implementation_version {
+                Some(version) => attributes.push(format!(
+                    "mediator-implementation={}@{}",
+                    implementation,
+                    version.as_string()
+                )),
+                None => attributes.push(format!("mediator-implementation={}", implementation)),
+            }
+        }
+        match self.priority {
+            Priority::None => {}
+            Priority::Vendor => attributes.push("mediator-priority=vendor".to_owned()),
+            Priority::Site => attributes.push("mediator-priority=site".to_owned()),
+        }
+        write!(f, "{}", attributes.join(" "))
+    }
+}
+
+/// [`MediatedLink`] is mediated `link` or `hardlink` action of package
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct MediatedLink {
+    fmri: FMRI,
+    action: Action,
+    mediation: Mediation,
+}
+
+impl MediatedLink {
+    pub fn get_fmri_ref(&self) -> &FMRI {
+        &self.fmri
+    }
+
+    pub fn get_action_ref(&self) -> &Action {
+        &self.action
+    }
+
+    pub fn get_mediation_ref(&self) -> &Mediation {
+        &self.mediation
+    }
+
+    pub fn get_path_ref(&self) -> Option<&String> {
+        self.action.get_attribute("path")
+    }
+
+    pub fn get_target_ref(&self) -> Option<&String> {
+        self.action.get_attribute("target")
+    }
+}
+
+/// [`Selection`] is selected [`Mediation`] of mediator with its links
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct Selection {
+    mediation: Mediation,
+    links: Vec<MediatedLink>,
+}
+
+impl Selection {
+    pub fn get_mediation_ref(&self) -> &Mediation {
+        &self.mediation
+    }
+
+    /// Returns links of packages, which provide selected mediation
+    pub fn get_links_ref(&self) -> &Vec<MediatedLink> {
+        &self.links
+    }
+
+    /// Returns link targets by link path
+    pub fn targets(&self) -> BTreeMap<String, String> {
+        self.links
+            .iter()
+            .filter_map(|link| Some((link.get_path_ref()?.clone(), link.get_target_ref()?.clone())))
+            .collect()
+    }
+}
+
+/// [`Mediators`] selects mediation of mediated links like pkg(5) does
+///
+/// Explicit settings (`pkg set-mediator`) limit candidates, then the most preferred mediation
+/// is selected by [`Mediation::cmp_preference`].
+///
+/// # Examples
+///
+/// ```
+/// use fmri::{manifest::Manifest, mediator::{Mediation, Mediators}};
+/// let mut mediators = Mediators::new();
+/// for manifest in [
+///     "set name=pkg.fmri value=pkg:/runtime/python-39@3.9.18
+///      link path=usr/bin/python target=python3.9 mediator=python mediator-version=3.9",
+///     "set name=pkg.fmri value=pkg:/runtime/python-311@3.11.6
+///      link path=usr/bin/python target=python3.11 mediator=python mediator-version=3.11",
+/// ] {
+///     mediators.add_manifest(&Manifest::parse(manifest).unwrap()).unwrap();
+/// }
+///
+/// let selected = mediators.resolve().unwrap();
+/// assert_eq!(selected["python"].targets()["usr/bin/python"], "python3.11");
+///
+/// let mut setting = Mediation::new();
+/// setting.set_version(Some("3.9".try_into().unwrap()));
+/// mediators.set("python".to_owned(), setting);
+/// let selected = mediators.resolve().unwrap();
+/// assert_eq!(selected["python"].targets()["usr/bin/python"], "python3.9");
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Mediators {
+    links: BTreeMap<String, Vec<MediatedLink>>,
+    settings: BTreeMap<String, Mediation>,
+}
+
+impl Mediators {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds mediated links of installed package [`Manifest`]
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if manifest doesn't have valid `pkg.fmri` or some
+    /// mediated link is invalid
+    pub fn add_manifest(&mut self, manifest: &Manifest) -> Result<(), String> {
+        let fmri = manifest
+            .get_fmri()?
+            .ok_or("manifest doesn't have pkg.fmri")?;
+        let links = manifest
+            .actions_of_type("link")
+            .chain(manifest.actions_of_type("hardlink"));
+        for action in links {
+            if let Some((mediator, mediation)) = Mediation::from_action(action)? {
+                self.links.entry(mediator).or_default().push(MediatedLink {
+                    fmri: fmri.clone(),
+                    action: action.clone(),
+                    mediation,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets explicit mediation of mediator, like `pkg set-mediator`
+    pub fn set(&mut self, mediator: String, setting: Mediation) {
+        self.settings.insert(mediator, setting);
+    }
+
+    /// Removes explicit mediation of mediator, like `pkg unset-mediator`
+    pub fn unset(&mut self, mediator: &str) {
+        self.settings.remove(mediator);
+    }
+
+    pub fn get_setting(&self, mediator: &str) -> Option<&Mediation> {
+        self.settings.get(mediator)
+    }
+
+    /// Returns all mediated links of mediator
+    pub fn get(&self, mediator: &str) -> Option<&Vec<MediatedLink>> {
+        self.links.get(mediator)
+    }
+
+    /// Returns names of mediators
+    pub fn mediators(&self) -> impl Iterator<Item = &String> {
+        self.links.keys()
+    }
+
+    /// Returns selected [`Mediation`] of every mediator
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if explicit setting isn't satisfied by any link
+    pub fn resolve(&self) -> Result<BTreeMap<String, Selection>, String> {
+        self.links
+            .iter()
+            .map(|(mediator, links)| {
+                let setting = self.settings.get(mediator).cloned().unwrap_or_default();
+                let mediation = links
+                    .iter()
+                    .map(|link| &link.mediation)
+                    .filter(|mediation| mediation.satisfies(&setting))
+                    .max_by(|a, b| a.cmp_preference(b))
+                    .ok_or(format!(
+                        "no link of mediator {} matches \"{}\"",
+                        mediator, setting
+                    ))?;
+
+                // selected links can differ only in priority
+                let selected = |other: &Mediation| {
+                    Mediation {
+                        priority: Priority::None,
+                        ..other.clone()
+                    } == Mediation {
+                        priority: Priority::None,
+                        ..mediation.clone()
+                    }
+                };
+                let selection = Selection {
+                    mediation: mediation.clone(),
+                    links: links
+                        .iter()
+                        .filter(|link| selected(&link.mediation))
+                        .cloned()
+                        .collect(),
+                };
+                Ok((mediator.clone(), selection))
+            })
+            .collect()
+    }
+}
+
+/// Parses pkg(5) dot sequence, like "3.11"
+fn parse_dot_sequence(version: &str) -> Result<Segment, String> {
+    Segment::try_from(version)
+        .map_err(|e| format!("invalid mediator version \"{}\": {}", version, e))
+}
@@ -0,0 +1,237 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    manifest::{action::Action, logical_lines, Manifest},
+    mogrify::transform::Transform,
+};
+
+pub mod transform;
+
+/// [`Mogrify`] transforms [`Manifests`][Manifest] like pkgmogrify(1)
+///
+/// Input text can contain actions and `<transform ...>` and `<include file>` directives,
+/// `$(MACRO)` is replaced by defined macros before lines are parsed. Transforms are applied
+/// in order of appearance to all actions, actions emitted by a transform aren't transformed.
+///
+/// # Examples
+///
+/// ```
+/// use fmri::mogrify::Mogrify;
+/// let mut mogrify = Mogrify::new();
+/// mogrify.define("COMPONENT_VERSION".to_owned(), "5.2".to_owned());
+/// let manifest = mogrify.process("\
+/// <transform file path=usr/share/man/.* -> default facet.doc.man true>
+/// <transform file path=usr/share/info/.* -> drop>
+/// set name=pkg.fmri value=pkg:/shell/bash@$(COMPONENT_VERSION)
+/// file path=usr/share/man/man1/bash.1
+/// file path=usr/share/info/bash.info
+/// ").unwrap();
+///
+/// assert_eq!(manifest.to_string(), "\
+/// set name=pkg.fmri value=pkg:/shell/bash@5.2
+/// file path=usr/share/man/man1/bash.1 facet.doc.man=true
+/// ");
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Mogrify {
+    macros: BTreeMap<String, String>,
+    include_dirs: Vec<PathBuf>,
+    transforms: Vec<Transform>,
+}
+
+impl Mogrify {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Defines `$(name)` macro, like `-D name=value`
+    pub fn define(&mut self, name: String, value: String) {
+        self.macros.insert(name, value);
+    }
+
+    /// Adds directory searched by `<include>`, like `-I directory`
+    pub fn add_include_dir(&mut self, directory: PathBuf) {
+        self.include_dirs.push(directory)
+    }
+
+    pub fn add_transform(&mut self, transform: Transform) {
+        self.transforms.push(transform)
+    }
+
+    pub fn get_transforms_ref(&self) -> &Vec<Transform> {
+        &self.transforms
+    }
+
+    /// Replaces defined `$(MACRO)` in text, undefined macros are kept
+    pub fn expand_macros(&self, text: &str) -> String {
+        let mut expanded = String::new();
+        let mut rest = text;
+
+        while let Some(position) = rest.find("$(") {
+            expanded.push_str(&rest[..position]);
+            rest = &rest[position..];
+            match rest[2..]
+                .split_once(')')
+                .and_then(|(name, after)| Some((self.macros.get(name)?, after)))
+            {
+                Some((value, after)) => {
+                    expanded.push_str(value);
+                    rest = after;
+                }
+                None => {
+                    expanded.push_str("$(");
+                    rest = &rest[2..];
+                }
+            }
+        }
+
+        expanded.push_str(rest);
+        expanded
+    }
+
+    /// Reads actions and directives from text, transforms are kept for [`Mogrify::apply`]
+    ///
+    /// Relative `<include>` files are searched next to `path` first, then in include directories.
+    ///
+    /// # Error
+    ///
+    /// Returns a string with line number and error message
+    pub fn load(&mut self, text: &str, path: Option<&Path>) -> Result<Manifest, String> {
+        self.load_included(text, path, &mut vec![])
+    }
+
+    /// Reads actions and directives from file, see [`Mogrify::load`]
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if file can't be read or is invalid
+    pub fn load_file(&mut self, path: &Path) -> Result<Manifest, String> {
+        self.load_file_included(path, &mut vec![])
+    }
+
+    /// Applies all transforms to actions of [`Manifest`]
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if some transform can't be applied
+    pub fn apply(&self, manifest: &Manifest) -> Result<Manifest, String> {
+        let mut transformed = Manifest::new();
+
+        for action in manifest {
+            let mut current = Some(action.clone());
+            let mut emitted: Vec<Action> = vec![];
+
+            for transform in &self.transforms {
+                let Some(action) = current.take() else {
+                    break;
+                };
+                match transform.matches(&action) {
+                    None => current = Some(action),
+                    Some(groups) => {
+                        let (action, mut emits) = transform
+                            .apply(action, &groups, manifest)
+                            .map_err(|e| format!("{}: {}", transform, e))?;
+                        current = action;
+                        emitted.append(&mut emits);
+                    }
+                }
+            }
+
+            transformed.get_ref_mut().extend(current);
+            transformed.get_ref_mut().append(&mut emitted);
+        }
+
+        Ok(transformed)
+    }
+
+    /// Loads text and applies transforms to its actions
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if text is invalid or some transform can't be
+    /// applied
+    pub fn process(&mut self, text: &str) -> Result<Manifest, String> {
+        let manifest = self.load(text, None)?;
+        self.apply(&manifest)
+    }
+
+    fn load_included(
+        &mut self,
+        text: &str,
+        path: Option<&Path>,
+        stack: &mut Vec<PathBuf>,
+    ) -> Result<Manifest, String> {
+        let mut manifest = Manifest::new();
+        let location = |number: usize| match path {
+            None => format!("line {}", number),
+            Some(path) => format!("{}:{}", path.display(), number),
+        };
+
+        for (number, line) in logical_lines(text) {
+            let line = self.expand_macros(&line);
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if line.starts_with("<transform") {
+                let transform =
+                    Transform::parse(line).map_err(|e| format!("{}: {}", location(number), e))?;
+                self.add_transform(transform);
+            } else if let Some(include) = line.strip_prefix("<include") {
+                let include = include.strip_suffix('>').unwrap_or(include).trim();
+                let file = self.find_include(include, path).ok_or(format!(
+                    "{}: can't find \"{}\"",
+                    location(number),
+                    include
+                ))?;
+                manifest
+                    .get_ref_mut()
+                    .append(&mut self.load_file_included(&file, stack)?.get());
+            } else if line.starts_with('<') {
+                return Err(format!(
+                    "{}: unknown directive \"{}\"",
+                    location(number),
+                    line
+                ));
+            } else {
+                manifest
+                    .add(Action::parse(line).map_err(|e| format!("{}: {}", location(number), e))?);
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    fn load_file_included(
+        &mut self,
+        path: &Path,
+        stack: &mut Vec<PathBuf>,
+    ) -> Result<Manifest, String> {
+        if stack.iter().any(|included| included == path) {
+            return Err(format!("{} includes itself", path.display()));
+        }
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("can't read {}: {}", path.display(), e))?;
+
+        stack.push(path.to_owned());
+        let manifest = self.load_included(&text, Some(path), stack);
+        stack.pop();
+        manifest
+    }
+
+    fn find_include(&self, include: &str, path: Option<&Path>) -> Option<PathBuf> {
+        let include = Path::new(include);
+        if include.is_absolute() {
+            return Some(include.to_owned()).filter(|file| file.is_file());
+        }
+
+        path.and_then(Path::parent)
+            .into_iter()
+            .chain(self.include_dirs.iter().map(PathBuf::as_path))
+            .map(|directory| directory.join(include))
+            .find(|file| file.is_file())
+    }
+}
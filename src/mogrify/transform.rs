@@ -0,0 +1,490 @@
+use std::fmt::{Display, Formatter};
+
+use regex::Regex;
+
+use crate::manifest::{action::Action, Manifest};
+
+/// [`Operation`] is what [`Transform`] does with matching [`Action`]
+///
+/// Values can contain `%{attribute}` (attribute of the action), `%{{attribute}}` (value of
+/// `set` action of the package) and `%<1>` (group of matching regular expression) references.
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum Operation {
+    /// Adds value to attribute
+    Add { attribute: String, value: String },
+    /// Sets attribute only if action doesn't have it
+    Default { attribute: String, value: String },
+    /// Removes values of attribute matching the regular expression
+    Delete { attribute: String, pattern: String },
+    /// Removes action from manifest
+    Drop,
+    /// Replaces matches of the regular expression in values of attribute, `\1` is group
+    Edit {
+        attribute: String,
+        pattern: String,
+        replacement: String,
+    },
+    /// Adds action (or comment) after matching action
+    Emit(String),
+    /// Replaces all values of attribute with one value
+    Set { attribute: String, value: String },
+}
+
+/// [`Transform`] is one `<transform ...>` rule of pkgmogrify
+///
+/// # Examples
+///
+/// ```plain
+/// <transform file path=usr/share/man/.* -> default facet.doc.man true>
+/// <transform file dir path=usr/lib/python3.9/.* -> drop>
+/// <transform file path=usr/bin/(.*) -> set pkg.linted.%<1> true>
+/// <transform set name=pkg.fmri -> edit value "@(.*)" "@$(COMPONENT_VERSION)">
+/// ```
+#[derive(Clone, Debug)]
+pub struct Transform {
+    /// Matched action types, empty means any action type
+    action_types: Vec<String>,
+    /// Attributes with regular expressions anchored at the beginning and end of value
+    conditions: Vec<(String, Regex)>,
+    operation: Operation,
+}
+
+impl Transform {
+    /// Parses [`Transform`] from `<transform ... -> ...>` directive (angle brackets are optional)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmri::mogrify::transform::{Operation, Transform};
+    /// let transform = Transform::parse("<transform file path=usr/share/man/.* -> default facet.doc.man true>").unwrap();
+    /// assert_eq!(transform.get_action_types_ref(), &vec!["file".to_owned()]);
+    /// assert_eq!(
+    ///     transform.get_operation_ref(),
+    ///     &Operation::Default { attribute: "facet.doc.man".to_owned(), value: "true".to_owned() }
+    /// );
+    /// ```
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if directive, regular expression or operation is
+    /// invalid
+    pub fn parse(directive: &str) -> Result<Self, String> {
+        let trimmed = directive.trim();
+        let trimmed = trimmed.strip_prefix('<').unwrap_or(trimmed);
+        let trimmed = trimmed.strip_suffix('>').unwrap_or(trimmed).trim();
+        let body = trimmed
+            .strip_prefix("transform")
+            .ok_or(format!("\"{}\" isn't transform", directive.trim()))?;
+        let (matching, operation) = body
+            .split_once("->")
+            .ok_or(format!("missing \"->\" in \"{}\"", directive.trim()))?;
+
+        let mut transform = Self {
+            action_types: vec![],
+            conditions: vec![],
+            operation: parse_operation(operation.trim())?,
+        };
+
+        for argument in split_arguments(matching)? {
+            match argument.split_once('=') {
+                None => transform.action_types.push(argument),
+                Some((attribute, pattern)) => {
+                    let regex = Regex::new(&format!("^(?:{})$", pattern))
+                        .map_err(|e| format!("invalid regex \"{}\": {}", pattern, e))?;
+                    transform.conditions.push((attribute.to_owned(), regex));
+                }
+            }
+        }
+
+        Ok(transform)
+    }
+
+    pub fn get_action_types_ref(&self) -> &Vec<String> {
+        &self.action_types
+    }
+
+    pub fn get_operation_ref(&self) -> &Operation {
+        &self.operation
+    }
+
+    /// Returns groups of matching regular expressions (`%<1>` is the first one) if
+    /// [`Action`] matches
+    pub fn matches(&self, action: &Action) -> Option<Vec<String>> {
+        if !self.action_types.is_empty()
+            && !self
+                .action_types
+                .iter()
+                .any(|action_type| action_type == action.get_action_type_ref())
+        {
+            return None;
+        }
+
+        let mut groups = vec![];
+        for (attribute, regex) in &self.conditions {
+            let captures = attribute_values(action, attribute)
+                .into_iter()
+                .find_map(|value| regex.captures(value))?;
+            groups.extend(
+                captures
+                    .iter()
+                    .skip(1)
+                    .map(|group| group.map(|g| g.as_str().to_owned()).unwrap_or_default()),
+            );
+        }
+        Some(groups)
+    }
+
+    /// Applies [`Operation`] to [`Action`] of package [`Manifest`]
+    ///
+    /// Returns transformed action ([`None`] if it is dropped) with emitted actions.
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if reference can't be resolved, regular expression
+    /// or emitted action is invalid
+    pub fn apply(
+        &self,
+        mut action: Action,
+        groups: &[String],
+        manifest: &Manifest,
+    ) -> Result<(Option<Action>, Vec<Action>), String> {
+        let substitute = |value: &str, action: &Action| substitute(value, action, groups, manifest);
+
+        match &self.operation {
+            Operation::Add { attribute, value } => {
+                let value = substitute(value, &action)?;
+                action.add_attribute(attribute.clone(), value)
+            }
+            Operation::Default { attribute, value } => {
+                if attribute_values(&action, attribute).is_empty() {
+                    let value = substitute(value, &action)?;
+                    set_attribute(&mut action, attribute, value)
+                }
+            }
+            Operation::Delete { attribute, pattern } => {
+                let pattern = substitute(pattern, &action)?;
+                let regex = Regex::new(&pattern)
+                    .map_err(|e| format!("invalid regex \"{}\": {}", pattern, e))?;
+                action
+                    .get_attributes_ref_mut()
+                    .retain(|(key, value)| key != attribute || !regex.is_match(value));
+            }
+            Operation::Drop => return Ok((None, vec![])),
+            Operation::Edit {
+                attribute,
+                pattern,
+                replacement,
+            } => {
+                let pattern = substitute(pattern, &action)?;
+                let regex = Regex::new(&pattern)
+                    .map_err(|e| format!("invalid regex \"{}\": {}", pattern, e))?;
+                let replacement = python_replacement(&substitute(replacement, &action)?);
+
+                if attribute == "action.hash" {
+                    let payload = action
+                        .get_payload_ref()
+                        .map(|payload| regex.replace_all(payload, &replacement).into_owned());
+                    action.set_payload(payload);
+                } else {
+                    for (key, value) in action.get_attributes_ref_mut() {
+                        if key == attribute {
+                            *value = regex.replace_all(value, &replacement).into_owned();
+                        }
+                    }
+                }
+            }
+            Operation::Emit(line) => {
+                let line = substitute(line, &action)?;
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    return Ok((Some(action), vec![]));
+                }
+                return Ok((Some(action), vec![Action::parse(line)?]));
+            }
+            Operation::Set { attribute, value } => {
+                let value = substitute(value, &action)?;
+                set_attribute(&mut action, attribute, value)
+            }
+        }
+
+        Ok((Some(action), vec![]))
+    }
+}
+
+impl PartialEq for Transform {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_string() == other.to_string()
+    }
+}
+
+impl Eq for Transform {}
+
+impl Display for Transform {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<transform")?;
+        for action_type in &self.action_types {
+            write!(f, " {}", action_type)?;
+        }
+        for (attribute, regex) in &self.conditions {
+            let pattern = regex.as_str();
+            let pattern = &pattern["^(?:".len()..pattern.len() - ")$".len()];
+            write!(f, " {}={}", attribute, quote(pattern))?;
+        }
+        write!(f, " -> ")?;
+        match &self.operation {
+            Operation::Add { attribute, value } => write!(f, "add {} {}", attribute, quote(value))?,
+            Operation::Default { attribute, value } => {
+                write!(f, "default {} {}", attribute, quote(value))?
+            }
+            Operation::Delete { attribute, pattern } => {
+                write!(f, "delete {} {}", attribute, quote(pattern))?
+            }
+            Operation::Drop => write!(f, "drop")?,
+            Operation::Edit {
+                attribute,
+                pattern,
+                replacement,
+            } => write!(
+                f,
+                "edit {} {} {}",
+                attribute,
+                quote(pattern),
+                quote(replacement)
+            )?,
+            Operation::Emit(line) => write!(f, "emit {}", line)?,
+            Operation::Set { attribute, value } => write!(f, "set {} {}", attribute, quote(value))?,
+        }
+        write!(f, ">")
+    }
+}
+
+fn parse_operation(operation: &str) -> Result<Operation, String> {
+    let (name, rest) = operation
+        .split_once(char::is_whitespace)
+        .unwrap_or((operation, ""));
+
+    if name == "emit" {
+        return Ok(Operation::Emit(rest.trim().to_owned()));
+    }
+
+    let mut arguments = split_arguments(rest)?.into_iter();
+    let mut argument = |description: &str| {
+        arguments
+            .next()
+            .ok_or(format!("missing {} of \"{}\" operation", description, name))
+    };
+
+    let operation = match name {
+        "add" => Operation::Add {
+            attribute: argument("attribute")?,
+            value: argument("value")?,
+        },
+        "default" => Operation::Default {
+            attribute: argument("attribute")?,
+            value: argument("value")?,
+        },
+        "delete" => Operation::Delete {
+            attribute: argument("attribute")?,
+            pattern: argument("value")?,
+        },
+        "drop" => Operation::Drop,
+        "edit" => Operation::Edit {
+            attribute: argument("attribute")?,
+            pattern: argument("pattern")?,
+            replacement: argument("replacement").unwrap_or_default(),
+        },
+        "set" => Operation::Set {
+            attribute: argument("attribute")?,
+            value: argument("value")?,
+        },
+        _ => return Err(format!("unknown operation \"{}\"", name)),
+    };
+
+    match arguments.next() {
+        None => Ok(operation),
+        Some(extra) => Err(format!(
+            "unexpected argument \"{}\" of \"{}\" operation",
+            extra, name
+        )),
+    }
+}
+
+/// Splits arguments by whitespace like shell does, quotes and backslash escapes are removed
+fn split_arguments(arguments: &str) -> Result<Vec<String>, String> {
+    let mut split = vec![];
+    let mut current: Option<String> = None;
+    let mut quote: Option<char> = None;
+    let mut chars = arguments.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (None, c) if c.is_whitespace() => split.extend(current.take()),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                current.get_or_insert_with(String::new);
+            }
+            (Some(q), c) if c == q => quote = None,
+            (Some('"') | None, '\\') => {
+                let escaped = chars
+                    .next()
+                    .ok_or(format!("trailing backslash in \"{}\"", arguments))?;
+                let current = current.get_or_insert_with(String::new);
+                // backslash is kept unless it escapes quote or backslash, regexes need it
+                if !matches!(escaped, '"' | '\'' | '\\') {
+                    current.push('\\');
+                }
+                current.push(escaped);
+            }
+            (_, c) => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    if quote.is_some() {
+        return Err(format!("unterminated quote in \"{}\"", arguments));
+    }
+    split.extend(current);
+    Ok(split)
+}
+
+/// Quotes argument for [`Display`] if it contains whitespace, quotes or is empty
+fn quote(argument: &str) -> String {
+    if !argument.is_empty()
+        && !argument.contains(|c: char| c.is_whitespace() || c == '"' || c == '\'')
+    {
+        return argument.to_owned();
+    }
+    format!(
+        "\"{}\"",
+        argument.replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
+/// Returns values of attribute, `action.hash`, `action.name` and `action.key` are special
+fn attribute_values<'a>(action: &'a Action, attribute: &str) -> Vec<&'a String> {
+    match attribute {
+        "action.hash" => action.get_payload_ref().into_iter().collect(),
+        "action.name" => vec![action.get_action_type_ref()],
+        "action.key" => action.get_key_attribute().into_iter().collect(),
+        _ => action.get_attribute_values(attribute),
+    }
+}
+
+fn set_attribute(action: &mut Action, attribute: &str, value: String) {
+    match attribute {
+        "action.hash" => action.set_payload(Some(value)),
+        _ => action.set_attribute(attribute.to_owned(), value),
+    }
+}
+
+/// Converts Python replacement (`\1`, `\g<1>`) to [`Regex`] replacement (`${1}`)
+fn python_replacement(replacement: &str) -> String {
+    let mut converted = String::new();
+    let mut chars = replacement.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '$' => converted.push_str("$$"),
+            '\\' if chars.peek().is_some_and(char::is_ascii_digit) => {
+                converted.push_str("${");
+                while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                    converted.push(digit);
+                }
+                converted.push('}');
+            }
+            '\\' if chars.peek() == Some(&'g') => {
+                let rest: String = chars.clone().collect();
+                match rest
+                    .strip_prefix("g<")
+                    .and_then(|rest| rest.split_once('>'))
+                {
+                    Some((group, _)) => {
+                        converted.push_str(&format!("${{{}}}", group));
+                        chars.nth(group.len() + 2);
+                    }
+                    None => converted.push(c),
+                }
+            }
+            '\\' if chars.peek() == Some(&'\\') => {
+                chars.next();
+                converted.push('\\');
+            }
+            c => converted.push(c),
+        }
+    }
+
+    converted
+}
+
+/// Replaces `%{attribute}`, `%{{attribute}}` and `%<n>` references
+///
+/// `%{attribute;notfound=value}` is replaced with the value if attribute is missing.
+fn substitute(
+    value: &str,
+    action: &Action,
+    groups: &[String],
+    manifest: &Manifest,
+) -> Result<String, String> {
+    let mut substituted = String::new();
+    let mut rest = value;
+
+    while let Some(position) = rest.find('%') {
+        substituted.push_str(&rest[..position]);
+        rest = &rest[position..];
+
+        if let Some(after) = rest.strip_prefix("%{{") {
+            let (reference, after) = after
+                .split_once("}}")
+                .ok_or(format!("unterminated reference in \"{}\"", value))?;
+            let (name, notfound) = split_notfound(reference);
+            let resolved = manifest.get_set_value(name).or(notfound.as_ref());
+            substituted.push_str(resolved.ok_or(format!(
+                "package attribute \"{}\" referenced in \"{}\" not found",
+                name, value
+            ))?);
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("%{") {
+            let (reference, after) = after
+                .split_once('}')
+                .ok_or(format!("unterminated reference in \"{}\"", value))?;
+            let (name, notfound) = split_notfound(reference);
+            let values = attribute_values(action, name);
+            let resolved = match values.is_empty() {
+                true => notfound.ok_or(format!(
+                    "attribute \"{}\" referenced in \"{}\" not found",
+                    name, value
+                ))?,
+                false => values
+                    .into_iter()
+                    .map(String::as_str)
+                    .collect::<Vec<&str>>()
+                    .join(" "),
+            };
+            substituted.push_str(&resolved);
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("%<") {
+            let (group, after) = after
+                .split_once('>')
+                .ok_or(format!("unterminated reference in \"{}\"", value))?;
+            let group = group
+                .parse::<usize>()
+                .ok()
+                .filter(|group| (1..=groups.len()).contains(group))
+                .ok_or(format!("invalid group %<{}> in \"{}\"", group, value))?;
+            substituted.push_str(&groups[group - 1]);
+            rest = after;
+        } else {
+            substituted.push('%');
+            rest = &rest[1..];
+        }
+    }
+
+    substituted.push_str(rest);
+    Ok(substituted)
+}
+
+fn split_notfound(reference: &str) -> (&str, Option<String>) {
+    match reference.split_once(";notfound=") {
+        None => (reference, None),
+        Some((name, notfound)) => (name, Some(notfound.to_owned())),
+    }
+}
@@ -0,0 +1,35 @@
+#[cfg(doc)]
+use crate::{version::Version, FMRI};
+
+/// [`ParseOptions`] selects grammar used for parsing [`FMRI`] and [`Version`]
+///
+/// Lenient grammar (default) is the one of [`FMRI::parse_raw`], it accepts "fmri=" prefix,
+/// extra slashes around publisher and package name and never checks characters of names.
+///
+/// Strict grammar follows pkg(5):
+///
+/// ```plain
+/// [pkg:/ | pkg://publisher/]package/name[@release[,build][-branch][:YYYYMMDDTHHMMSSZ]]
+/// ```
+///
+/// Publisher may contain only letters, digits, '-' and '.', components of package name only
+/// letters, digits, '_', '-', '.' and '+' and must start with letter or digit. Any trailing
+/// characters are rejected.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Hash)]
+pub struct ParseOptions {
+    strict: bool,
+}
+
+impl ParseOptions {
+    pub fn lenient() -> Self {
+        Self { strict: false }
+    }
+
+    pub fn strict() -> Self {
+        Self { strict: true }
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+}
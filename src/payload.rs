@@ -0,0 +1,187 @@
+use std::io::Write;
+
+use flate2::{Compression, GzBuilder};
+use goblin::elf::{section_header::SHT_NOBITS, Elf};
+use sha1::Sha1;
+use sha2::{Digest, Sha512_256};
+
+use crate::manifest::action::Action;
+
+pub mod verify;
+
+/// Sections ignored by ELF hash, like pkg(5) does
+const UNHASHED_SECTIONS: [&str; 10] = [
+    ".SUNW_signature",
+    ".comment",
+    ".SUNW_dof",
+    ".debug",
+    ".plt",
+    ".rela.bss",
+    ".rela.plt",
+    ".line",
+    ".note",
+    ".compcom",
+];
+
+/// [`PayloadHashes`] are pkg(5) hashes and sizes of `file` action payload
+///
+/// # Examples
+///
+/// ```
+/// use fmri::{manifest::action::Action, payload::PayloadHashes};
+/// let hashes = PayloadHashes::compute(b"hello\n").unwrap();
+/// assert_eq!(hashes.get_hash_ref(), "f572d396fae9206628714fb2ce00f72e94f2258f");
+///
+/// let mut action = Action::parse("file path=etc/motd mode=0644").unwrap();
+/// hashes.apply_to(&mut action);
+/// assert_eq!(action.get_payload_ref().unwrap(), "f572d396fae9206628714fb2ce00f72e94f2258f");
+/// assert_eq!(action.get_attribute("pkg.size").unwrap(), "6");
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct PayloadHashes {
+    /// Legacy SHA-1 of payload
+    hash: String,
+    /// "file:sha512t_256:..." of payload
+    content_hash: String,
+    /// "gelf:sha512t_256:..." of ELF objects
+    elf_hash: Option<String>,
+    size: u64,
+    /// Legacy SHA-1 of compressed payload
+    chash: String,
+    /// "gzip:sha512t_256:..." of compressed payload
+    compressed_content_hash: String,
+    csize: u64,
+}
+
+impl PayloadHashes {
+    /// Computes all hashes of payload
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if payload looks like ELF object, but is invalid, or
+    /// it can't be compressed
+    pub fn compute(content: &[u8]) -> Result<Self, String> {
+        let compressed = compress(content)?;
+        Ok(Self {
+            hash: sha1_hash(content),
+            content_hash: content_hash(content),
+            elf_hash: elf_hash(content)?,
+            size: content.len() as u64,
+            chash: sha1_hash(&compressed),
+            compressed_content_hash: format!("gzip:sha512t_256:{}", sha512t_256(&compressed)),
+            csize: compressed.len() as u64,
+        })
+    }
+
+    pub fn get_hash_ref(&self) -> &String {
+        &self.hash
+    }
+
+    pub fn get_content_hash_ref(&self) -> &String {
+        &self.content_hash
+    }
+
+    /// Returns [`None`] if payload isn't ELF object
+    pub fn get_elf_hash_ref(&self) -> Option<&String> {
+        self.elf_hash.as_ref()
+    }
+
+    pub fn get_size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn get_chash_ref(&self) -> &String {
+        &self.chash
+    }
+
+    pub fn get_compressed_content_hash_ref(&self) -> &String {
+        &self.compressed_content_hash
+    }
+
+    pub fn get_csize(&self) -> u64 {
+        self.csize
+    }
+
+    /// Sets payload, `chash`, `pkg.content-hash`, `pkg.size` and `pkg.csize` of [`Action`]
+    pub fn apply_to(&self, action: &mut Action) {
+        action.set_payload(Some(self.hash.clone()));
+
+        action.remove_attribute("pkg.content-hash");
+        let content_hashes = self
+            .elf_hash
+            .iter()
+            .chain([&self.content_hash, &self.compressed_content_hash]);
+        for content_hash in content_hashes {
+            action.add_attribute("pkg.content-hash".to_owned(), content_hash.clone());
+        }
+
+        action.set_attribute("chash".to_owned(), self.chash.clone());
+        action.set_attribute("pkg.size".to_owned(), self.size.to_string());
+        action.set_attribute("pkg.csize".to_owned(), self.csize.to_string());
+    }
+}
+
+/// Returns legacy SHA-1 hash of content (`hash` and `chash`)
+pub fn sha1_hash(content: &[u8]) -> String {
+    hex::encode(Sha1::digest(content))
+}
+
+/// Returns "file:sha512t_256:..." hash of content
+pub fn content_hash(content: &[u8]) -> String {
+    format!("file:sha512t_256:{}", sha512t_256(content))
+}
+
+/// Returns "gelf:sha512t_256:..." hash of ELF object, [`None`] if content isn't ELF object
+///
+/// Only sections with code and data are hashed, so the hash doesn't change when object is
+/// stripped or signed.
+///
+/// # Error
+///
+/// Returns a string with error message if content looks like ELF object, but is invalid
+pub fn elf_hash(content: &[u8]) -> Result<Option<String>, String> {
+    if !content.starts_with(b"\x7fELF") {
+        return Ok(None);
+    }
+    let elf = Elf::parse(content).map_err(|e| format!("invalid ELF object: {}", e))?;
+
+    let mut hasher = Sha512_256::new();
+    for section in &elf.section_headers {
+        let name = elf.shdr_strtab.get_at(section.sh_name).unwrap_or_default();
+        if section.sh_type == SHT_NOBITS
+            || UNHASHED_SECTIONS.contains(&name)
+            || name.starts_with(".debug")
+        {
+            continue;
+        }
+        if let Some(data) = section.file_range().and_then(|range| content.get(range)) {
+            hasher.update(data);
+        }
+    }
+
+    Ok(Some(format!(
+        "gelf:sha512t_256:{}",
+        hex::encode(hasher.finalize())
+    )))
+}
+
+/// Compresses content like pkg(5) does, gzip with the best compression and zero time
+///
+/// zlib does the compression like in Python of pkg(5), so `chash` and `pkg.csize` match.
+///
+/// # Error
+///
+/// Returns a string with error message if zlib fails
+pub fn compress(content: &[u8]) -> Result<Vec<u8>, String> {
+    let mut encoder = GzBuilder::new()
+        .mtime(0)
+        .write(Vec::new(), Compression::best());
+    encoder
+        .write_all(content)
+        .and_then(|_| encoder.finish())
+        .map_err(|e| format!("can't compress: {}", e))
+}
+
+fn sha512t_256(content: &[u8]) -> String {
+    hex::encode(Sha512_256::digest(content))
+}
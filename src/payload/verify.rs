@@ -0,0 +1,265 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    manifest::{action::Action, Manifest},
+    payload::{content_hash, elf_hash, sha1_hash},
+};
+
+/// [`Mismatch`] is one difference between `file` action and delivered file
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum Mismatch {
+    Missing,
+    /// Path exists, but isn't regular file
+    NotFile,
+    /// File can't be read
+    Unreadable(String),
+    /// Hash from `pkg.content-hash` (ELF hash for ELF objects) or legacy payload hash
+    Hash {
+        expected: String,
+        actual: String,
+    },
+    Size {
+        expected: u64,
+        actual: u64,
+    },
+    Mode {
+        expected: u32,
+        actual: u32,
+    },
+    Owner {
+        expected: String,
+        actual: String,
+    },
+    Group {
+        expected: String,
+        actual: String,
+    },
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing => write!(f, "missing"),
+            Self::NotFile => write!(f, "not a regular file"),
+            Self::Unreadable(error) => write!(f, "unreadable: {}", error),
+            Self::Hash { expected, actual } => {
+                write!(f, "hash: expected {}, found {}", expected, actual)
+            }
+            Self::Size { expected, actual } => {
+                write!(f, "size: expected {}, found {}", expected, actual)
+            }
+            Self::Mode { expected, actual } => {
+                write!(f, "mode: expected {:04o}, found {:04o}", expected, actual)
+            }
+            Self::Owner { expected, actual } => {
+                write!(f, "owner: expected {}, found {}", expected, actual)
+            }
+            Self::Group { expected, actual } => {
+                write!(f, "group: expected {}, found {}", expected, actual)
+            }
+        }
+    }
+}
+
+/// [`Verifier`] checks files in proto directory or installed root against `file` actions
+///
+/// Owner and group are checked only if enabled, names are taken from `etc/passwd` and
+/// `etc/group` of the root (or of the system if the root doesn't have them).
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::PathBuf;
+/// use fmri::{manifest::Manifest, payload::verify::Verifier};
+/// let manifest = Manifest::parse("file path=usr/bin/bash mode=0555 owner=root group=bin").unwrap();
+/// let mut verifier = Verifier::new(PathBuf::from("/"));
+/// verifier.set_check_ownership(true);
+/// for (path, mismatches) in verifier.verify(&manifest) {
+///     for mismatch in mismatches {
+///         println!("{}: {}", path, mismatch);
+///     }
+/// }
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Verifier {
+    root: PathBuf,
+    check_ownership: bool,
+}
+
+impl Verifier {
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            check_ownership: false,
+        }
+    }
+
+    pub fn get_root_ref(&self) -> &PathBuf {
+        &self.root
+    }
+
+    pub fn is_checking_ownership(&self) -> bool {
+        self.check_ownership
+    }
+
+    /// Enables checking of owner and group, proto directories are usually owned by builder
+    pub fn set_check_ownership(&mut self, check_ownership: bool) {
+        self.check_ownership = check_ownership
+    }
+
+    /// Returns [`Mismatches`][Mismatch] of every path, which doesn't match its `file` action
+    pub fn verify(&self, manifest: &Manifest) -> BTreeMap<String, Vec<Mismatch>> {
+        let users = match self.check_ownership {
+            true => self.names("etc/passwd"),
+            false => BTreeMap::new(),
+        };
+        let groups = match self.check_ownership {
+            true => self.names("etc/group"),
+            false => BTreeMap::new(),
+        };
+
+        manifest
+            .actions_of_type("file")
+            .filter_map(|action| {
+                let path = action.get_attribute("path")?;
+                let mismatches = self.verify_file(action, path, &users, &groups);
+                match mismatches.is_empty() {
+                    true => None,
+                    false => Some((path.clone(), mismatches)),
+                }
+            })
+            .collect()
+    }
+
+    fn verify_file(
+        &self,
+        action: &Action,
+        path: &str,
+        users: &BTreeMap<u32, String>,
+        groups: &BTreeMap<u32, String>,
+    ) -> Vec<Mismatch> {
+        let file = self.root.join(path.trim_start_matches('/'));
+        let metadata = match fs::symlink_metadata(&file) {
+            Err(_) => return vec![Mismatch::Missing],
+            Ok(metadata) if !metadata.is_file() => return vec![Mismatch::NotFile],
+            Ok(metadata) => metadata,
+        };
+        let mut mismatches = vec![];
+
+        if let Some(expected) = action
+            .get_attribute("pkg.size")
+            .and_then(|size| size.parse::<u64>().ok())
+        {
+            if expected != metadata.len() {
+                mismatches.push(Mismatch::Size {
+                    expected,
+                    actual: metadata.len(),
+                });
+            }
+        }
+
+        match fs::read(&file) {
+            Err(e) => mismatches.push(Mismatch::Unreadable(e.to_string())),
+            Ok(content) => mismatches.extend(verify_hash(action, &content)),
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+
+            if let Some(expected) = action
+                .get_attribute("mode")
+                .and_then(|mode| u32::from_str_radix(mode, 8).ok())
+            {
+                let actual = metadata.mode() & 0o7777;
+                if expected != actual {
+                    mismatches.push(Mismatch::Mode { expected, actual });
+                }
+            }
+
+            if self.check_ownership {
+                let name = |names: &BTreeMap<u32, String>, id: u32| {
+                    names.get(&id).cloned().unwrap_or(id.to_string())
+                };
+                if let Some(expected) = action.get_attribute("owner") {
+                    let actual = name(users, metadata.uid());
+                    if *expected != actual {
+                        mismatches.push(Mismatch::Owner {
+                            expected: expected.clone(),
+                            actual,
+                        });
+                    }
+                }
+                if let Some(expected) = action.get_attribute("group") {
+                    let actual = name(groups, metadata.gid());
+                    if *expected != actual {
+                        mismatches.push(Mismatch::Group {
+                            expected: expected.clone(),
+                            actual,
+                        });
+                    }
+                }
+            }
+        }
+
+        mismatches
+    }
+
+    /// Reads names and ids from passwd or group file of root, or of system
+    fn names(&self, file: &str) -> BTreeMap<u32, String> {
+        let content = fs::read_to_string(self.root.join(file))
+            .or_else(|_| fs::read_to_string(Path::new("/").join(file)))
+            .unwrap_or_default();
+
+        content
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split(':');
+                let name = fields.next()?;
+                let id = fields.nth(1)?.parse::<u32>().ok()?;
+                Some((id, name.to_owned()))
+            })
+            .collect()
+    }
+}
+
+/// Compares the strongest available hash, ELF hash is preferred for ELF objects
+fn verify_hash(action: &Action, content: &[u8]) -> Option<Mismatch> {
+    let content_hashes = action.get_attribute_values("pkg.content-hash");
+    let find = |prefix: &str| {
+        content_hashes
+            .iter()
+            .find(|hash| hash.starts_with(prefix))
+            .map(|hash| (*hash).clone())
+    };
+
+    let (expected, actual) = match (find("gelf:"), find("file:")) {
+        (Some(expected), _) if content.starts_with(b"\x7fELF") => {
+            let actual = match elf_hash(content) {
+                Ok(hash) => hash.unwrap_or_default(),
+                Err(e) => e,
+            };
+            (expected, actual)
+        }
+        (_, Some(expected)) => (expected, content_hash(content)),
+        (Some(expected), None) => (expected, content_hash(content)),
+        // payload of unpublished manifests can be path or "NOHASH"
+        (None, None) => (
+            action
+                .get_payload_ref()
+                .filter(|payload| {
+                    payload.len() == 40 && payload.chars().all(|c| c.is_ascii_hexdigit())
+                })?
+                .clone(),
+            sha1_hash(content),
+        ),
+    };
+
+    match expected == actual {
+        true => None,
+        false => Some(Mismatch::Hash { expected, actual }),
+    }
+}
@@ -0,0 +1,489 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    manifest::{action::Action, Manifest},
+    FMRI,
+};
+
+mod elf;
+mod script;
+mod smf;
+
+/// Placeholder [`FMRI`] of generated dependencies, which aren't resolved yet
+pub const TBD: &str = "__TBD";
+
+/// [`Generator`] generates dependencies of files in [`Manifest`] like `pkgdepend generate`
+///
+/// Files are read from proto directories. Dependencies are found in ELF `DT_NEEDED` entries,
+/// `#!` interpreter lines, Python imports, Perl `use`/`require` statements and SMF manifest
+/// dependencies. Every dependency is `depend fmri=__TBD` action with candidate files
+/// (`pkg.debug.depend.file`) and directories (`pkg.debug.depend.path`).
+///
+/// Generated dependencies matching `pkg.depend.bypass-generate` of the file are skipped and
+/// `pkg.depend.runpath` replaces ELF search path (`$PKGDEPEND_RUNPATH` is the original one).
+///
+/// # Examples
+///
+/// ```plain
+/// depend fmri=__TBD pkg.debug.depend.file=libc.so.1 pkg.debug.depend.path=lib/64 \
+///     pkg.debug.depend.path=usr/lib/64 pkg.debug.depend.reason=usr/bin/bash \
+///     pkg.debug.depend.type=elf type=require
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Generator {
+    proto_dirs: Vec<PathBuf>,
+    /// Paths of SMF manifests delivering services ("svc:/system/filesystem/local")
+    smf_services: BTreeMap<String, String>,
+}
+
+impl Generator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds directory with files delivered by manifest, like `-d directory`
+    pub fn add_proto_dir(&mut self, directory: PathBuf) {
+        self.proto_dirs.push(directory)
+    }
+
+    /// Adds services delivered by SMF manifest, path is relative to image root
+    ///
+    /// Services of manifests delivered by generated package are added automatically, this is
+    /// needed for services delivered by other packages.
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if manifest isn't valid XML
+    pub fn add_smf_manifest(&mut self, path: String, xml: &str) -> Result<(), String> {
+        for service in smf::parse(xml)
+            .map_err(|e| format!("{}: {}", path, e))?
+            .services
+        {
+            self.smf_services.entry(service).or_insert(path.clone());
+        }
+        Ok(())
+    }
+
+    /// Adds SMF manifests from `lib/svc/manifest` and `var/svc/manifest` of image root
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if some manifest can't be read or is invalid
+    pub fn add_smf_manifest_dir(&mut self, root: &Path) -> Result<(), String> {
+        for directory in smf::MANIFEST_DIRS {
+            let mut directories = vec![root.join(directory)];
+            while let Some(directory) = directories.pop() {
+                let Ok(entries) = fs::read_dir(&directory) else {
+                    continue;
+                };
+                for entry in entries {
+                    let path = entry
+                        .map_err(|e| format!("can't read {}: {}", directory.display(), e))?
+                        .path();
+                    if path.is_dir() {
+                        directories.push(path);
+                    } else if path.extension().is_some_and(|extension| extension == "xml") {
+                        let xml = fs::read_to_string(&path)
+                            .map_err(|e| format!("can't read {}: {}", path.display(), e))?;
+                        let relative = path.strip_prefix(root).unwrap_or(&path);
+                        self.add_smf_manifest(relative.to_string_lossy().into_owned(), &xml)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Generates dependencies of files delivered by [`Manifest`]
+    ///
+    /// Files with dependency, which can't be expressed (like `#!/usr/bin/env` interpreter or
+    /// unknown SMF service), are reported in [`Generated`] errors, other files are still used.
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if some file can't be read or its action is invalid
+    pub fn generate(&self, manifest: &Manifest) -> Result<Generated, String> {
+        let mut files: Vec<(&Action, &String, Vec<u8>)> = vec![];
+        for action in manifest.actions_of_type("file") {
+            let path = action
+                .get_attribute("path")
+                .ok_or(format!("file action without path: {}", action))?;
+            files.push((action, path, self.read(action, path)?));
+        }
+
+        let mut generator = self.clone();
+        for (_, path, content) in &files {
+            if smf::is_manifest(path) {
+                generator.add_smf_manifest((*path).clone(), &String::from_utf8_lossy(content))?;
+            }
+        }
+
+        let mut generated = Generated::default();
+        for (action, path, content) in files {
+            let bypass = bypass_patterns(action)?;
+            match generator.generate_file(action, path, &content) {
+                Ok(dependencies) => generated.dependencies.get_ref_mut().extend(
+                    dependencies
+                        .into_iter()
+                        .filter(|dependency| !is_bypassed(dependency, &bypass)),
+                ),
+                Err(e) => generated.errors.push(format!("{}: {}", path, e)),
+            }
+        }
+
+        Ok(generated)
+    }
+
+    fn generate_file(
+        &self,
+        action: &Action,
+        path: &str,
+        content: &[u8],
+    ) -> Result<Vec<Action>, String> {
+        if let Some(dependencies) = elf::dependencies(content, path, action)? {
+            return Ok(dependencies);
+        }
+
+        let mut dependencies = vec![];
+        let mut interpreter: Option<String> = None;
+        if is_executable(action) {
+            if let Some((dependency, path_of_interpreter)) = script::interpreter(content, path)? {
+                dependencies.push(dependency);
+                interpreter = Some(path_of_interpreter);
+            }
+        }
+
+        let text = String::from_utf8_lossy(content);
+        if let Some(version) = script::python_version(path, interpreter.as_deref()) {
+            dependencies.append(&mut script::python_dependencies(&text, path, &version));
+        }
+        if let Some(version) = script::perl_version(path, interpreter.as_deref()) {
+            dependencies.append(&mut script::perl_dependencies(&text, path, &version));
+        }
+        if smf::is_manifest(path) {
+            dependencies.append(&mut smf::dependencies(&text, path, &self.smf_services)?);
+        }
+
+        Ok(dependencies)
+    }
+
+    /// Reads file from the first proto directory containing it
+    fn read(&self, action: &Action, path: &str) -> Result<Vec<u8>, String> {
+        let source = match action.get_payload_ref() {
+            Some(payload) if payload != "NOHASH" => payload,
+            _ => path,
+        };
+        let file = self
+            .proto_dirs
+            .iter()
+            .map(|directory| directory.join(source))
+            .find(|file| file.is_file())
+            .ok_or(format!("{} not found in proto directories", source))?;
+        fs::read(&file).map_err(|e| format!("can't read {}: {}", file.display(), e))
+    }
+}
+
+/// [`Generated`] dependencies of one package
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Generated {
+    dependencies: Manifest,
+    errors: Vec<String>,
+}
+
+impl Generated {
+    /// Returns `depend fmri=__TBD` actions of files
+    pub fn get_dependencies_ref(&self) -> &Manifest {
+        &self.dependencies
+    }
+
+    /// Returns error messages of files, whose dependencies couldn't be generated
+    pub fn get_errors_ref(&self) -> &Vec<String> {
+        &self.errors
+    }
+}
+
+/// Returns `depend fmri=__TBD` action
+pub(crate) fn tbd(files: Vec<String>, paths: Vec<String>, reason: &str, kind: &str) -> Action {
+    let mut action = Action::new("depend".to_owned());
+    action.add_attribute("fmri".to_owned(), TBD.to_owned());
+    for file in files {
+        action.add_attribute("pkg.debug.depend.file".to_owned(), file);
+    }
+    for path in paths {
+        action.add_attribute("pkg.debug.depend.path".to_owned(), path);
+    }
+    action.add_attribute("pkg.debug.depend.reason".to_owned(), reason.to_owned());
+    action.add_attribute("pkg.debug.depend.type".to_owned(), kind.to_owned());
+    action.add_attribute("type".to_owned(), "require".to_owned());
+    action
+}
+
+/// Returns parent directory of package relative path ("" for top level files)
+pub(crate) fn parent(path: &str) -> &str {
+    path.rsplit_once('/')
+        .map(|(parent, _)| parent)
+        .unwrap_or("")
+}
+
+fn is_executable(action: &Action) -> bool {
+    action
+        .get_attribute("mode")
+        .and_then(|mode| u32::from_str_radix(mode, 8).ok())
+        .is_some_and(|mode| mode & 0o111 != 0)
+}
+
+fn bypass_patterns(action: &Action) -> Result<Vec<regex::Regex>, String> {
+    action
+        .get_attribute_values("pkg.depend.bypass-generate")
+        .into_iter()
+        .map(|pattern| {
+            regex::Regex::new(&format!("^(?:{})$", pattern))
+                .map_err(|e| format!("invalid pkg.depend.bypass-generate \"{}\": {}", pattern, e))
+        })
+        .collect()
+}
+
+/// Checks if any candidate path (or file name) of dependency matches bypass pattern
+fn is_bypassed(dependency: &Action, bypass: &[regex::Regex]) -> bool {
+    candidates(dependency).iter().any(|candidate| {
+        let name = candidate.rsplit('/').next().unwrap_or(candidate);
+        bypass
+            .iter()
+            .any(|pattern| pattern.is_match(candidate) || pattern.is_match(name))
+    })
+}
+
+/// Returns all paths, which can satisfy generated dependency
+fn candidates(dependency: &Action) -> Vec<String> {
+    let files = dependency.get_attribute_values("pkg.debug.depend.file");
+    let paths = dependency.get_attribute_values("pkg.debug.depend.path");
+    if paths.is_empty() {
+        return files.into_iter().cloned().collect();
+    }
+    paths
+        .iter()
+        .flat_map(|path| {
+            files.iter().map(move |file| match path.is_empty() {
+                true => (*file).clone(),
+                false => format!("{}/{}", path, file),
+            })
+        })
+        .collect()
+}
+
+/// Normalizes package relative path, removes "." and resolves ".."
+fn normalize(path: &str) -> String {
+    let mut components: Vec<&str> = vec![];
+    for component in path.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop();
+            }
+            component => components.push(component),
+        }
+    }
+    components.join("/")
+}
+
+/// [`Resolved`] dependencies of one package
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Resolved {
+    dependencies: Manifest,
+    unresolved: Vec<Action>,
+}
+
+impl Resolved {
+    /// Returns `depend type=require` actions on packages delivering needed files
+    pub fn get_dependencies_ref(&self) -> &Manifest {
+        &self.dependencies
+    }
+
+    /// Returns generated dependencies, which aren't delivered by any known package
+    pub fn get_unresolved_ref(&self) -> &Vec<Action> {
+        &self.unresolved
+    }
+}
+
+/// What is delivered at a path
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Delivery {
+    File,
+    /// Symbolic link with target relative to image root
+    Link(String),
+}
+
+/// [`Resolver`] maps generated dependencies to [`FMRIs`][FMRI] like `pkgdepend resolve`
+///
+/// Paths delivered by `file`, `link` and `hardlink` actions of added [`Manifests`][Manifest]
+/// are known, symbolic links are followed and packages delivering them are required too.
+///
+/// # Examples
+///
+/// ```
+/// use fmri::{manifest::Manifest, pkgdepend::Resolver};
+/// let mut resolver = Resolver::new();
+/// resolver.add_manifest(&Manifest::parse("\
+/// set name=pkg.fmri value=pkg://openindiana.org/system/library@0.5.11-2023.0.0.1:20230101T000000Z
+/// file path=lib/64/libc.so.1
+/// ").unwrap()).unwrap();
+///
+/// let resolved = resolver.resolve(&Manifest::parse("\
+/// set name=pkg.fmri value=pkg://openindiana.org/shell/bash@5.2
+/// depend fmri=__TBD pkg.debug.depend.file=libc.so.1 pkg.debug.depend.path=lib/64 \\
+///     pkg.debug.depend.reason=usr/bin/bash pkg.debug.depend.type=elf type=require
+/// ").unwrap()).unwrap();
+///
+/// assert_eq!(
+///     resolved.get_dependencies_ref().to_string(),
+///     "depend fmri=pkg:/system/library@0.5.11-2023.0.0.1 type=require \
+///      pkg.debug.depend.file=lib/64/libc.so.1 pkg.debug.depend.reason=usr/bin/bash\n"
+/// );
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Resolver {
+    paths: BTreeMap<String, (FMRI, Delivery)>,
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds paths delivered by package [`Manifest`], it must contain `pkg.fmri`
+    ///
+    /// If more packages deliver the same path, the first one is used.
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if [`FMRI`] is missing or invalid
+    pub fn add_manifest(&mut self, manifest: &Manifest) -> Result<(), String> {
+        let fmri = manifest
+            .get_fmri()?
+            .ok_or("manifest doesn't contain pkg.fmri")?;
+        for (path, delivery) in deliveries(manifest) {
+            self.paths.entry(path).or_insert((fmri.clone(), delivery));
+        }
+        Ok(())
+    }
+
+    /// Resolves `depend fmri=__TBD` actions of [`Manifest`]
+    ///
+    /// Dependencies satisfied by the package itself are dropped, dependencies on the same
+    /// package are merged. Resolved [`FMRIs`][FMRI] don't have publisher nor timestamp.
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if package [`FMRI`] is invalid
+    pub fn resolve(&self, manifest: &Manifest) -> Result<Resolved, String> {
+        let own = manifest.get_fmri()?;
+        let own_paths: BTreeMap<String, Delivery> = deliveries(manifest).collect();
+        let mut resolved = Resolved::default();
+        let mut required: BTreeMap<String, Action> = BTreeMap::new();
+
+        for dependency in manifest
+            .actions_of_type("depend")
+            .filter(|action| action.get_attribute("fmri").is_some_and(|fmri| fmri == TBD))
+        {
+            let found = candidates(dependency)
+                .into_iter()
+                .find_map(|candidate| self.follow(&normalize(&candidate), &own_paths));
+            let Some((path, packages)) = found else {
+                resolved.unresolved.push(dependency.clone());
+                continue;
+            };
+
+            for package in packages {
+                if own
+                    .as_ref()
+                    .is_some_and(|own| own.package_name_eq(&package))
+                {
+                    continue;
+                }
+                let fmri = without_publisher_and_timestamp(package);
+                let action = required.entry(fmri.to_string()).or_insert_with(|| {
+                    let mut action = Action::new("depend".to_owned());
+                    action.add_attribute("fmri".to_owned(), fmri.to_string());
+                    action.add_attribute("type".to_owned(), "require".to_owned());
+                    action
+                });
+                add_unique(action, "pkg.debug.depend.file", &path);
+                for reason in dependency.get_attribute_values("pkg.debug.depend.reason") {
+                    add_unique(action, "pkg.debug.depend.reason", reason);
+                }
+            }
+        }
+
+        resolved.dependencies = required.into_values().collect();
+        Ok(resolved)
+    }
+
+    /// Follows symbolic links from path, returns found path and packages on the way
+    ///
+    /// Paths delivered by the package itself have no package.
+    fn follow(
+        &self,
+        path: &str,
+        own_paths: &BTreeMap<String, Delivery>,
+    ) -> Option<(String, Vec<FMRI>)> {
+        let mut packages = vec![];
+        let mut current = path.to_owned();
+
+        // limits length of link chains, also stops on link cycles
+        for _ in 0..32 {
+            let delivery = match own_paths.get(&current) {
+                Some(delivery) => delivery,
+                None => {
+                    let (fmri, delivery) = self.paths.get(&current)?;
+                    packages.push(fmri.clone());
+                    delivery
+                }
+            };
+            match delivery {
+                Delivery::File => return Some((path.to_owned(), packages)),
+                Delivery::Link(target) => current = target.clone(),
+            }
+        }
+        None
+    }
+}
+
+/// Returns paths delivered by [`Manifest`]
+fn deliveries(manifest: &Manifest) -> impl Iterator<Item = (String, Delivery)> + '_ {
+    manifest.iter().filter_map(|action| {
+        let path = normalize(action.get_attribute("path")?);
+        let delivery = match action.get_action_type_ref().as_str() {
+            "file" | "hardlink" => Delivery::File,
+            "link" => {
+                let target = action.get_attribute("target")?;
+                let target = match target.starts_with('/') {
+                    true => normalize(target),
+                    false => normalize(&format!("{}/{}", parent(&path), target)),
+                };
+                Delivery::Link(target)
+            }
+            _ => return None,
+        };
+        Some((path, delivery))
+    })
+}
+
+fn without_publisher_and_timestamp(mut fmri: FMRI) -> FMRI {
+    fmri.remove_publisher();
+    if let Some(version) = fmri.get_version_ref_mut() {
+        version.remove_timestamp();
+    }
+    fmri
+}
+
+fn add_unique(action: &mut Action, key: &str, value: &str) {
+    if !action
+        .get_attribute_values(key)
+        .iter()
+        .any(|existing| *existing == value)
+    {
+        action.add_attribute(key.to_owned(), value.to_owned());
+    }
+}
@@ -0,0 +1,76 @@
+use goblin::elf::{header::ET_REL, Elf};
+
+use crate::{
+    manifest::action::Action,
+    pkgdepend::{parent, tbd},
+};
+
+/// Default search path of 32-bit objects
+const DEFAULT_RUNPATH_32: [&str; 2] = ["lib", "usr/lib"];
+/// Default search path of 64-bit objects
+const DEFAULT_RUNPATH_64: [&str; 2] = ["lib/64", "usr/lib/64"];
+/// Token of `pkg.depend.runpath`, which is replaced with search path of the object
+const RUNPATH_TOKEN: &str = "$PKGDEPEND_RUNPATH";
+
+/// Returns dependencies on `DT_NEEDED` libraries, [`None`] if content isn't ELF object
+///
+/// Relocatable objects (like kernel modules) have no dependencies.
+pub(crate) fn dependencies(
+    content: &[u8],
+    path: &str,
+    action: &Action,
+) -> Result<Option<Vec<Action>>, String> {
+    if !content.starts_with(b"\x7fELF") {
+        return Ok(None);
+    }
+    let elf = Elf::parse(content).map_err(|e| format!("invalid ELF object: {}", e))?;
+    if elf.header.e_type == ET_REL {
+        return Ok(Some(vec![]));
+    }
+
+    let origin = parent(path);
+    let mut runpath: Vec<String> = vec![];
+    for entry in elf
+        .runpaths
+        .iter()
+        .chain(elf.rpaths.iter())
+        .flat_map(|paths| paths.split(':'))
+        .chain(match elf.is_64 {
+            true => DEFAULT_RUNPATH_64,
+            false => DEFAULT_RUNPATH_32,
+        })
+    {
+        let entry = entry
+            .replace("$ORIGIN", &format!("/{}", origin))
+            .trim_matches('/')
+            .to_owned();
+        if !entry.is_empty() && !runpath.contains(&entry) {
+            runpath.push(entry);
+        }
+    }
+
+    if let Some(custom) = action.get_attribute("pkg.depend.runpath") {
+        runpath = custom
+            .split(':')
+            .flat_map(|entry| match entry {
+                RUNPATH_TOKEN => runpath.clone(),
+                entry => vec![entry.trim_matches('/').to_owned()],
+            })
+            .collect();
+    }
+
+    Ok(Some(
+        elf.libraries
+            .iter()
+            .map(|library| match library.rsplit_once('/') {
+                Some((directory, file)) => tbd(
+                    vec![file.to_owned()],
+                    vec![directory.trim_start_matches('/').to_owned()],
+                    path,
+                    "elf",
+                ),
+                None => tbd(vec![library.to_string()], runpath.clone(), path, "elf"),
+            })
+            .collect(),
+    ))
+}
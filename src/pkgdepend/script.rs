@@ -0,0 +1,196 @@
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use crate::{
+    manifest::action::Action,
+    pkgdepend::{parent, tbd},
+};
+
+/// Python modules built into the interpreter, they aren't delivered as files
+const PYTHON_BUILTINS: [&str; 12] = [
+    "_abc",
+    "_codecs",
+    "_imp",
+    "_io",
+    "_thread",
+    "_warnings",
+    "_weakref",
+    "builtins",
+    "marshal",
+    "posix",
+    "sys",
+    "time",
+];
+
+static PYTHON_MODULE: OnceLock<Regex> = OnceLock::new();
+static PYTHON_INTERPRETER: OnceLock<Regex> = OnceLock::new();
+static PYTHON_IMPORT: OnceLock<Regex> = OnceLock::new();
+static PYTHON_FROM: OnceLock<Regex> = OnceLock::new();
+static PERL_MODULE: OnceLock<Regex> = OnceLock::new();
+static PERL_INTERPRETER: OnceLock<Regex> = OnceLock::new();
+static PERL_USAGE: OnceLock<Regex> = OnceLock::new();
+
+/// Returns regex compiled on the first use, patterns are constant and valid
+fn regex(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).unwrap())
+}
+
+/// Returns dependency on `#!` interpreter and its path, [`None`] if content isn't script
+pub(crate) fn interpreter(content: &[u8], path: &str) -> Result<Option<(Action, String)>, String> {
+    let Some(line) = content.strip_prefix(b"#!") else {
+        return Ok(None);
+    };
+    let line = String::from_utf8_lossy(line.split(|c| *c == b'\n').next().unwrap_or_default());
+    let Some(interpreter) = line.split_whitespace().next() else {
+        return Ok(None);
+    };
+
+    if !interpreter.starts_with('/') {
+        return Err(format!(
+            "interpreter \"{}\" must be absolute path",
+            interpreter
+        ));
+    }
+    let (directory, name) = interpreter.rsplit_once('/').unwrap_or_default();
+    if name == "env" {
+        return Err(format!(
+            "interpreter \"{}\" hides real interpreter, use its absolute path",
+            line.trim()
+        ));
+    }
+
+    Ok(Some((
+        tbd(
+            vec![name.to_owned()],
+            vec![directory.trim_start_matches('/').to_owned()],
+            path,
+            "script",
+        ),
+        interpreter.to_owned(),
+    )))
+}
+
+/// Returns Python version ("3.9") of module (from path) or script (from interpreter)
+pub(crate) fn python_version(path: &str, interpreter: Option<&str>) -> Option<String> {
+    let from_path = regex(&PYTHON_MODULE, r"^usr/lib/python(\d+\.\d+)/.*\.py$");
+    let from_interpreter = regex(&PYTHON_INTERPRETER, r"/python(\d+\.\d+)$");
+
+    from_path
+        .captures(path)
+        .or_else(|| from_interpreter.captures(interpreter?))
+        .map(|captures| captures[1].to_owned())
+}
+
+/// Returns dependencies on modules imported by Python code
+///
+/// Relative imports and builtin modules are skipped.
+pub(crate) fn python_dependencies(text: &str, path: &str, version: &str) -> Vec<Action> {
+    let import = regex(&PYTHON_IMPORT, r"^\s*import\s+([\w., ]+)");
+    let from = regex(&PYTHON_FROM, r"^\s*from\s+([\w.]+)\s+import\b");
+    let library = format!("usr/lib/python{}", version);
+    let paths = vec![
+        parent(path).to_owned(),
+        library.clone(),
+        format!("{}/lib-dynload", library),
+        format!("{}/vendor-packages", library),
+        format!("{}/site-packages", library),
+    ];
+
+    let mut modules: Vec<String> = vec![];
+    for line in text.lines() {
+        let imported: Vec<&str> = match (import.captures(line), from.captures(line)) {
+            (Some(captures), _) => captures
+                .get(1)
+                .map(|names| names.as_str())
+                .unwrap_or_default()
+                .split(',')
+                .filter_map(|name| name.split_whitespace().next())
+                .collect(),
+            (None, Some(captures)) => vec![captures.get(1).map(|m| m.as_str()).unwrap_or("")],
+            (None, None) => continue,
+        };
+        for module in imported {
+            if module.is_empty()
+                || module.starts_with('.')
+                || PYTHON_BUILTINS.contains(&module)
+                || modules.iter().any(|known| known == module)
+            {
+                continue;
+            }
+            modules.push(module.to_owned());
+        }
+    }
+
+    modules
+        .iter()
+        .map(|module| {
+            let module = module.replace('.', "/");
+            let files = vec![
+                format!("{}.py", module),
+                format!("{}.pyc", module),
+                format!("{}/__init__.py", module),
+                format!("{}.so", module),
+                format!("{}module.so", module),
+            ];
+            tbd(files, paths.clone(), path, "python")
+        })
+        .collect()
+}
+
+/// Returns Perl version ("5.36") of module (from path) or script (from interpreter path)
+pub(crate) fn perl_version(path: &str, interpreter: Option<&str>) -> Option<String> {
+    let from_path = regex(
+        &PERL_MODULE,
+        r"^usr/perl5/(?:vendor_perl/|site_perl/)?(\d+\.\d+)/.*\.p[lm]$",
+    );
+    if let Some(captures) = from_path.captures(path) {
+        return Some(captures[1].to_owned());
+    }
+    let from_interpreter = regex(
+        &PERL_INTERPRETER,
+        r"^/usr/perl5/(\d+\.\d+)/bin/perl$|/perl(\d+\.\d+)$",
+    );
+    let captures = from_interpreter.captures(interpreter?)?;
+    captures
+        .get(1)
+        .or(captures.get(2))
+        .map(|version| version.as_str().to_owned())
+}
+
+/// Returns dependencies on modules loaded by Perl `use` and `require`
+///
+/// Pragmas (lowercase modules like "strict") and version requirements are skipped.
+pub(crate) fn perl_dependencies(text: &str, path: &str, version: &str) -> Vec<Action> {
+    let usage = regex(&PERL_USAGE, r"^\s*(?:use|require)\s+([A-Z][\w]*(?:::\w+)*)");
+    let paths = vec![
+        format!("usr/perl5/{}/lib", version),
+        format!("usr/perl5/vendor_perl/{}", version),
+        format!("usr/perl5/site_perl/{}", version),
+    ];
+
+    let mut modules: Vec<&str> = vec![];
+    for line in text.lines() {
+        // code after __END__ isn't executed
+        if line.starts_with("__END__") || line.starts_with("__DATA__") {
+            break;
+        }
+        if let Some(module) = usage.captures(line).and_then(|captures| captures.get(1)) {
+            if !modules.contains(&module.as_str()) {
+                modules.push(module.as_str());
+            }
+        }
+    }
+
+    modules
+        .iter()
+        .map(|module| {
+            tbd(
+                vec![format!("{}.pm", module.replace("::", "/"))],
+                paths.clone(),
+                path,
+                "perl",
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,106 @@
+use std::collections::BTreeMap;
+
+use roxmltree::{Document, ParsingOptions};
+
+use crate::{
+    manifest::action::Action,
+    pkgdepend::{parent, tbd},
+};
+
+/// Directories with SMF manifests, relative to image root
+pub(crate) const MANIFEST_DIRS: [&str; 2] = ["lib/svc/manifest", "var/svc/manifest"];
+
+/// Services delivered by SMF manifest and services they require
+pub(crate) struct SmfManifest {
+    pub(crate) services: Vec<String>,
+    pub(crate) requires: Vec<String>,
+}
+
+pub(crate) fn is_manifest(path: &str) -> bool {
+    path.ends_with(".xml")
+        && MANIFEST_DIRS
+            .iter()
+            .any(|directory| path.starts_with(&format!("{}/", directory)))
+}
+
+/// Parses services and `require_all`/`require_any` dependencies (without instances)
+pub(crate) fn parse(xml: &str) -> Result<SmfManifest, String> {
+    let options = ParsingOptions {
+        allow_dtd: true,
+        ..ParsingOptions::default()
+    };
+    let document =
+        Document::parse_with_options(xml, options).map_err(|e| format!("invalid XML: {}", e))?;
+
+    let mut manifest = SmfManifest {
+        services: vec![],
+        requires: vec![],
+    };
+    for node in document.descendants() {
+        match node.tag_name().name() {
+            "service" => {
+                if let Some(name) = node.attribute("name") {
+                    manifest.services.push(format!("svc:/{}", name));
+                }
+            }
+            "dependency"
+                if matches!(
+                    node.attribute("grouping"),
+                    Some("require_all" | "require_any")
+                ) =>
+            {
+                for service in node
+                    .children()
+                    .filter(|child| child.has_tag_name("service_fmri"))
+                    .filter_map(|child| child.attribute("value"))
+                    .filter_map(without_instance)
+                {
+                    if !manifest.requires.contains(&service) {
+                        manifest.requires.push(service);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(manifest)
+}
+
+/// Returns dependencies on SMF manifests delivering required services
+pub(crate) fn dependencies(
+    xml: &str,
+    path: &str,
+    services: &BTreeMap<String, String>,
+) -> Result<Vec<Action>, String> {
+    let manifest = parse(xml)?;
+    let mut dependencies = vec![];
+
+    for service in &manifest.requires {
+        if manifest.services.contains(service) {
+            continue;
+        }
+        let delivered_by = services.get(service).ok_or(format!(
+            "service {} isn't delivered by any known SMF manifest",
+            service
+        ))?;
+        let file = delivered_by.rsplit('/').next().unwrap_or(delivered_by);
+        dependencies.push(tbd(
+            vec![file.to_owned()],
+            vec![parent(delivered_by).to_owned()],
+            path,
+            "smf",
+        ));
+    }
+
+    Ok(dependencies)
+}
+
+/// Returns "svc:/system/filesystem/local" from "svc:/system/filesystem/local:default"
+///
+/// Dependencies on files ("file://localhost/etc/hosts") are skipped.
+fn without_instance(service: &str) -> Option<String> {
+    let name = service.strip_prefix("svc:/")?;
+    let name = name.split_once(':').map(|(name, _)| name).unwrap_or(name);
+    Some(format!("svc:/{}", name.trim_start_matches('/')))
+}
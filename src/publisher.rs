@@ -1,11 +1,12 @@
-use std::fmt::{Debug, Display, Formatter};
+use alloc::{borrow::ToOwned, format, string::String};
+use core::fmt::{Debug, Display, Formatter};
 
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::{
-    FMRI,
-    helpers::{check_character_collision, remove_first_and_last_characters},
-};
+use crate::helpers::{check_character_collision, remove_first_and_last_characters};
+#[cfg(doc)]
+use crate::FMRI;
 
 /// [`Publisher`] is a part of [`FMRI`]
 ///
@@ -22,7 +23,8 @@ use crate::{
 /// ```
 ///
 /// `* = continues package name`
-#[derive(PartialEq, Serialize, Deserialize, Clone, Ord, Eq, PartialOrd, Hash)]
+#[derive(PartialEq, Clone, Ord, Eq, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Publisher(String);
 
 impl Publisher {
@@ -50,6 +52,11 @@ impl Publisher {
     /// assert_eq!(publisher, None);
     /// ```
     ///
+    /// # Error
+    ///
+    /// Returns a string with error message if "pkg://" isn't at the start, package name is missing
+    /// or publisher is invalid
+    #[allow(clippy::needless_return)]
     pub fn parse_publisher_from_raw_fmri(raw_fmri: String) -> Result<Option<Self>, String> {
         // remove "fmri=" if present
         let raw_fmri = raw_fmri.trim_start_matches("fmri=").to_owned();
@@ -59,16 +66,16 @@ impl Publisher {
             None => Ok(None),
             Some(position) => {
                 if position != 0 {
-                    panic!(
+                    return Err(format!(
                         "wrong position of starting \"pkg://\" pattern ({})",
                         position
-                    )
+                    ));
                 }
 
                 let (publisher, _) = raw_fmri
                     .trim_start_matches("pkg://")
                     .split_once('/')
-                    .expect("Fmri must contain \"/package_name\"");
+                    .ok_or("Fmri must contain \"/package_name\"")?;
                 Ok(Some(Self::new(publisher.to_owned())?))
             }
         };
@@ -88,13 +95,13 @@ impl Publisher {
 }
 
 impl Display for Publisher {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "pkg://{}/", self.get_as_ref_string())
     }
 }
 
 impl Debug for Publisher {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self)
     }
 }
@@ -0,0 +1,181 @@
+use alloc::{borrow::ToOwned, format, string::String, vec, vec::Vec};
+
+use crate::{
+    helpers::{check_package_name, check_publisher_name, percent_decode, percent_encode},
+    publisher::Publisher,
+    version::{segment::Segment, segments::Segments, Version},
+    FMRI,
+};
+
+/// Package URL type of [`FMRIs`][FMRI]
+pub const PURL_TYPE: &str = "ips";
+
+impl FMRI {
+    /// Returns package URL (purl) of [`FMRI`]
+    ///
+    /// The mapping is reversible by [`FMRI::from_purl`]:
+    /// - type is "ips"
+    /// - publisher is namespace
+    /// - package name is name, '/' is percent-encoded
+    /// - component version is version
+    /// - build version, branch version and timestamp are qualifiers "build", "branch" and
+    ///   "timestamp"
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmri::FMRI;
+    /// let fmri = FMRI::parse_raw("pkg://openindiana.org/shell/bash@5.2.21,5.11-2023.0.0.1:20230901T080000Z").unwrap();
+    /// assert_eq!(
+    ///     fmri.to_purl(),
+    ///     "pkg:ips/openindiana.org/shell%2Fbash@5.2.21?branch=2023.0.0.1&build=5.11&timestamp=20230901T080000Z"
+    /// );
+    /// assert_eq!(FMRI::parse_raw("shell/bash").unwrap().to_purl(), "pkg:ips/shell%2Fbash");
+    /// ```
+    pub fn to_purl(&self) -> String {
+        let mut purl = format!("pkg:{}/", PURL_TYPE);
+        if let Some(publisher) = self.get_publisher_as_ref_string() {
+            purl.push_str(&percent_encode(publisher));
+            purl.push('/');
+        }
+        purl.push_str(&percent_encode(self.get_package_name_as_ref_string()));
+
+        let Some(version) = self.get_version_ref() else {
+            return purl;
+        };
+        if let Some(component) = version.get_component_version_ref().get_segment_ref() {
+            purl.push('@');
+            purl.push_str(&component.as_string());
+        }
+
+        // qualifiers are sorted by key
+        let mut qualifiers = vec![];
+        if let Some(branch) = version.get_branch_version_ref().get_segment_ref() {
+            qualifiers.push(format!("branch={}", branch.as_string()));
+        }
+        if let Some(build) = version.get_build_version_ref().get_segment_ref() {
+            qualifiers.push(format!("build={}", build.as_string()));
+        }
+        if let Segments::Timestamp(timestamp) = version.get_timestamp_ref() {
+            qualifiers.push(format!("timestamp={}", percent_encode(timestamp)));
+        }
+        if !qualifiers.is_empty() {
+            purl.push('?');
+            purl.push_str(&qualifiers.join("&"));
+        }
+        purl
+    }
+
+    /// Parses [`FMRI`] from package URL (purl) created by [`FMRI::to_purl`]
+    ///
+    /// Unknown qualifiers and subpath are ignored. Timestamp isn't checked like in
+    /// [`FMRI::parse_raw`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmri::FMRI;
+    /// let fmri = FMRI::from_purl("pkg:ips/openindiana.org/shell%2Fbash@5.2.21?branch=2023.0.0.1").unwrap();
+    /// assert_eq!(fmri, FMRI::parse_raw("pkg://openindiana.org/shell/bash@5.2.21-2023.0.0.1").unwrap());
+    /// assert!(FMRI::from_purl("pkg:deb/debian/bash@5.2").is_err());
+    /// ```
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if purl isn't valid "ips" purl
+    pub fn from_purl(purl: &str) -> Result<Self, String> {
+        let rest = purl
+            .strip_prefix("pkg:")
+            .ok_or(format!("purl \"{}\" doesn't start with \"pkg:\"", purl))?;
+        let rest = rest.split_once('#').map_or(rest, |(rest, _)| rest);
+        let (rest, qualifiers) = match rest.split_once('?') {
+            Some((rest, qualifiers)) => (rest, Some(qualifiers)),
+            None => (rest, None),
+        };
+        let (rest, component) = match rest.rsplit_once('@') {
+            Some((rest, component)) => (rest, Some(percent_decode(component)?)),
+            None => (rest, None),
+        };
+
+        let mut parts = rest.trim_matches('/').split('/');
+        let purl_type = parts.next().unwrap_or_default();
+        if !purl_type.eq_ignore_ascii_case(PURL_TYPE) {
+            return Err(format!(
+                "purl type \"{}\" isn't \"{}\"",
+                purl_type, PURL_TYPE
+            ));
+        }
+        let parts = parts
+            .map(percent_decode)
+            .collect::<Result<Vec<String>, String>>()?;
+        let (publisher, package_name) = match parts.as_slice() {
+            [package_name] => (None, package_name),
+            [publisher, package_name] => (Some(publisher), package_name),
+            _ => return Err(format!("invalid namespace and name in purl \"{}\"", purl)),
+        };
+
+        check_package_name(package_name).map_err(|(index, reason)| {
+            format!(
+                "invalid package name \"{}\": {} at index {}",
+                package_name, reason, index
+            )
+        })?;
+        let mut fmri = FMRI::new_from_package_name(package_name.clone())?;
+        if let Some(publisher) = publisher {
+            check_publisher_name(publisher).map_err(|(index, reason)| {
+                format!(
+                    "invalid publisher \"{}\": {} at index {}",
+                    publisher, reason, index
+                )
+            })?;
+            fmri.change_publisher(Publisher::new(publisher.clone())?);
+        }
+
+        let mut builder = Version::builder();
+        let mut timestamp = None;
+        let mut has_qualifiers = false;
+        for qualifier in qualifiers.into_iter().flat_map(|q| q.split('&')) {
+            let Some((key, value)) = qualifier.split_once('=') else {
+                continue;
+            };
+            let value = percent_decode(value)?;
+            let segment = || {
+                Segment::try_from(value.as_str())
+                    .map_err(|e| format!("invalid {} \"{}\": {}", key, value, e))
+            };
+            builder = match key.to_ascii_lowercase().as_str() {
+                "build" => builder.build_version(segment()?),
+                "branch" => builder.branch_version(segment()?),
+                // timestamp isn't checked, like in lenient parsing, so that purl of any
+                // parsed FMRI round-trips
+                "timestamp" if value.is_empty() => return Err("timestamp is empty".to_owned()),
+                "timestamp" => {
+                    timestamp = Some(value.clone());
+                    builder
+                }
+                _ => continue,
+            };
+            has_qualifiers = true;
+        }
+
+        match component {
+            Some(component) => {
+                let segment = Segment::try_from(component.as_str())
+                    .map_err(|e| format!("invalid version \"{}\": {}", component, e))?;
+                let mut version = builder.component_version(segment).build()?;
+                if let Some(timestamp) = timestamp {
+                    version.set_timestamp_unchecked(timestamp);
+                }
+                fmri.change_version(version);
+            }
+            None if has_qualifiers => {
+                return Err(format!(
+                    "purl \"{}\" has version qualifiers without version",
+                    purl
+                ))
+            }
+            None => {}
+        }
+        Ok(fmri)
+    }
+}
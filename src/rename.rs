@@ -0,0 +1,291 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::Value;
+
+use crate::{
+    fmri_list::{diff::FMRIChange, index::cmp_newest},
+    manifest::{action::Action, Manifest},
+    FMRIList, FMRI,
+};
+
+/// [`PackageStatus`] of one package version, taken from `pkg.renamed` and `pkg.obsolete`
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum PackageStatus {
+    Normal,
+    /// Package is renamed to [`FMRI`] from its `depend type=require` action, incorporations which
+    /// renamed packages often require too are skipped
+    Renamed(FMRI),
+    Obsolete,
+}
+
+/// [`Resolution`] is result of following rename chain of one [`FMRI`]
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum Resolution {
+    /// Package isn't renamed nor obsoleted (or it is unknown)
+    Current(FMRI),
+    /// Package was renamed, chain contains all renamed [`FMRIs`][FMRI] starting with the resolved one
+    Renamed { chain: Vec<FMRI>, current: FMRI },
+    /// Package (or package it was renamed to) is obsoleted, last [`FMRI`] of chain is obsoleted
+    Obsolete { chain: Vec<FMRI> },
+}
+
+/// [`RewrittenList`] is [`FMRIList`] with all renames applied
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct RewrittenList {
+    list: FMRIList,
+    renamed: Vec<FMRIChange>,
+    obsolete: FMRIList,
+}
+
+impl RewrittenList {
+    /// Returns list with renamed packages replaced and obsoleted removed
+    pub fn get_list_ref(&self) -> &FMRIList {
+        &self.list
+    }
+
+    /// Returns replaced [`FMRIs`][FMRI] with their current packages
+    pub fn get_renamed_ref(&self) -> &Vec<FMRIChange> {
+        &self.renamed
+    }
+
+    /// Returns removed [`FMRIs`][FMRI], which are obsoleted
+    pub fn get_obsolete_ref(&self) -> &FMRIList {
+        &self.obsolete
+    }
+}
+
+/// [`RenameResolver`] follows pkg(5) rename chains and reports obsoleted packages
+///
+/// Packages are added from [`Manifests`][Manifest] (`set name=pkg.renamed value=true` plus
+/// `depend type=require` on the new package, or `set name=pkg.obsolete value=true`) or from
+/// catalog `catalog.dependency.C` parts.
+///
+/// # Examples
+///
+/// ```
+/// use fmri::{FMRI, manifest::Manifest, rename::{RenameResolver, Resolution}};
+/// let mut resolver = RenameResolver::new();
+/// resolver.add_manifest(&Manifest::parse("\
+/// set name=pkg.fmri value=pkg://openindiana.org/library/python/six-39@1.16-2023.0.0.1
+/// set name=pkg.renamed value=true
+/// depend fmri=library/python/six-311@1.16-2023.0.0.1 type=require
+/// ").unwrap()).unwrap();
+///
+/// let resolution = resolver.resolve(&FMRI::parse_raw("library/python/six-39").unwrap()).unwrap();
+/// let Resolution::Renamed { current, .. } = resolution else { panic!() };
+/// assert_eq!(current.get_package_name_as_ref_string(), "library/python/six-311");
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct RenameResolver {
+    /// Versions of every package name sorted from the oldest
+    packages: BTreeMap<String, Vec<(FMRI, PackageStatus)>>,
+}
+
+impl RenameResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds package from [`Manifest`], it must contain `pkg.fmri`
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if [`FMRI`] is missing or invalid, or renamed
+    /// package doesn't depend on exactly one package besides incorporations
+    pub fn add_manifest(&mut self, manifest: &Manifest) -> Result<(), String> {
+        let fmri = manifest
+            .get_fmri()?
+            .ok_or("manifest doesn't contain pkg.fmri")?;
+
+        let status = if manifest.is_set_true("pkg.obsolete") {
+            PackageStatus::Obsolete
+        } else if manifest.is_set_true("pkg.renamed") {
+            PackageStatus::Renamed(rename_target(manifest, &fmri)?)
+        } else {
+            PackageStatus::Normal
+        };
+
+        self.add(fmri, status);
+        Ok(())
+    }
+
+    /// Adds packages from pkg(5) `catalog.dependency.C` catalog part
+    ///
+    /// ```plain
+    /// {"openindiana.org": {"library/foo": [{"version": "1.0-2023.0.0.0:20230101T000000Z",
+    ///     "actions": ["set name=pkg.renamed value=true", "depend fmri=library/bar type=require"]}]}}
+    /// ```
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if catalog part or some package in it is invalid
+    pub fn add_catalog_part(&mut self, catalog_part: &str) -> Result<(), String> {
+        let catalog: Value =
+            serde_json::from_str(catalog_part).map_err(|e| format!("invalid catalog: {}", e))?;
+        let publishers = catalog.as_object().ok_or("catalog must be an object")?;
+
+        for (publisher, packages) in publishers {
+            // "_SIGNATURE" and similar keys aren't publishers
+            if publisher.starts_with('_') {
+                continue;
+            }
+            let packages = packages
+                .as_object()
+                .ok_or(format!("packages of {} must be an object", publisher))?;
+
+            for (package_name, versions) in packages {
+                for entry in versions.as_array().into_iter().flatten() {
+                    let version = entry.get("version").and_then(Value::as_str).ok_or(format!(
+                        "missing version of {} from {}",
+                        package_name, publisher
+                    ))?;
+
+                    let mut manifest = Manifest::new();
+                    let mut set_fmri = Action::new("set".to_owned());
+                    set_fmri.add_attribute("name".to_owned(), "pkg.fmri".to_owned());
+                    set_fmri.add_attribute(
+                        "value".to_owned(),
+                        format!("pkg://{}/{}@{}", publisher, package_name, version),
+                    );
+                    manifest.add(set_fmri);
+
+                    for action in entry
+                        .get("actions")
+                        .and_then(Value::as_array)
+                        .into_iter()
+                        .flatten()
+                    {
+                        let action = action.as_str().ok_or("actions must be strings")?;
+                        manifest.add(Action::parse(action)?);
+                    }
+                    self.add_manifest(&manifest)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn add(&mut self, fmri: FMRI, status: PackageStatus) {
+        let versions = self
+            .packages
+            .entry(fmri.get_package_name_as_ref_string().clone())
+            .or_default();
+        let position = versions.partition_point(|(other, _)| cmp_newest(other, &fmri).is_le());
+        versions.insert(position, (fmri, status));
+    }
+
+    /// Returns known package version which applies to [`FMRI`]
+    ///
+    /// It is the newest version, which isn't older than version of [`FMRI`] (if it has one).
+    pub fn get(&self, fmri: &FMRI) -> Option<&(FMRI, PackageStatus)> {
+        let versions = self.packages.get(fmri.get_package_name_as_ref_string())?;
+        let newest = versions.last()?;
+        match fmri.get_version_ref() {
+            Some(version) if newest.0.get_version_ref().as_ref() < Some(version) => None,
+            _ => Some(newest),
+        }
+    }
+
+    /// Follows rename chain of [`FMRI`]
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if rename chain contains cycle
+    pub fn resolve(&self, fmri: &FMRI) -> Result<Resolution, String> {
+        let mut chain: Vec<FMRI> = vec![];
+        let mut visited: BTreeSet<&String> = BTreeSet::new();
+        let mut current = fmri.clone();
+
+        while let Some((known, status)) = self.get(&current) {
+            if !visited.insert(known.get_package_name_as_ref_string()) {
+                chain.push(known.clone());
+                return Err(format!(
+                    "rename cycle: {}",
+                    chain
+                        .iter()
+                        .map(|fmri| fmri.get_package_name_as_ref_string().as_str())
+                        .collect::<Vec<&str>>()
+                        .join(" -> ")
+                ));
+            }
+
+            match status {
+                PackageStatus::Normal => break,
+                PackageStatus::Obsolete => {
+                    chain.push(known.clone());
+                    return Ok(Resolution::Obsolete { chain });
+                }
+                PackageStatus::Renamed(target) => {
+                    chain.push(known.clone());
+                    current = target.clone();
+                }
+            }
+        }
+
+        if chain.is_empty() {
+            return Ok(Resolution::Current(current));
+        }
+        Ok(Resolution::Renamed { chain, current })
+    }
+
+    /// Replaces renamed packages in [`FMRIList`] with current ones and removes obsoleted
+    ///
+    /// Current package keeps version from dependency only if the replaced [`FMRI`] had version.
+    /// If current package is already in the list, only the newest one is kept.
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if some rename chain contains cycle
+    pub fn rewrite(&self, list: &FMRIList) -> Result<RewrittenList, String> {
+        let mut rewritten = RewrittenList::default();
+
+        for fmri in list {
+            match self.resolve(fmri)? {
+                Resolution::Current(current) => rewritten.list.add(current),
+                Resolution::Obsolete { .. } => rewritten.obsolete.add(fmri.clone()),
+                Resolution::Renamed { mut current, .. } => {
+                    if !fmri.has_version() {
+                        current.remove_version();
+                    }
+                    rewritten
+                        .renamed
+                        .push(FMRIChange::new(fmri.clone(), current.clone()));
+                    rewritten.list.add(current);
+                }
+            }
+        }
+
+        rewritten.list.dedup_keep_newest();
+        Ok(rewritten)
+    }
+}
+
+/// Returns [`FMRI`] renamed package is renamed to, it is the only required package which isn't an
+/// incorporation ("consolidation/userland/userland-incorporation", "entire")
+fn rename_target(manifest: &Manifest, fmri: &FMRI) -> Result<FMRI, String> {
+    let required = manifest
+        .actions_of_type("depend")
+        .filter(|action| action.get_attribute("type").is_some_and(|t| t == "require"))
+        .filter_map(|action| action.get_attribute("fmri"))
+        .map(|target| FMRI::parse_raw(target))
+        .collect::<Result<Vec<FMRI>, String>>()?;
+
+    let mut targets = required.iter().filter(|target| {
+        let package_name = target.get_package_name_as_ref_string();
+        let last = package_name.rsplit('/').next().unwrap_or_default();
+        !(last.ends_with("incorporation") || package_name == "entire")
+    });
+    match (targets.next(), targets.next()) {
+        (Some(target), None) => Ok(target.clone()),
+        (None, _) if required.len() == 1 => Ok(required[0].clone()),
+        (None, _) => Err(format!(
+            "renamed package {} doesn't have require dependency",
+            fmri
+        )),
+        (Some(_), Some(_)) => Err(format!(
+            "renamed package {} requires more packages, rename target is ambiguous",
+            fmri
+        )),
+    }
+}
@@ -0,0 +1,547 @@
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+
+use crate::{manifest::Manifest, payload::sha1_hash, FMRIList, FMRI};
+
+/// `depend` types, which make dependency relationship
+const DEPENDENCY_TYPES: [&str; 6] = [
+    "require",
+    "require-any",
+    "conditional",
+    "group",
+    "group-any",
+    "optional",
+];
+
+/// [`Component`] is one package of [`Sbom`] with metadata from its [`Manifest`]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Component {
+    fmri: FMRI,
+    summary: Option<String>,
+    licenses: Vec<String>,
+    upstream_url: Option<String>,
+    source_url: Option<String>,
+    /// Package names of dependencies and if they are optional
+    dependencies: BTreeMap<String, bool>,
+}
+
+impl Component {
+    pub fn new(fmri: FMRI) -> Self {
+        Self {
+            fmri,
+            summary: None,
+            licenses: vec![],
+            upstream_url: None,
+            source_url: None,
+            dependencies: BTreeMap::new(),
+        }
+    }
+
+    pub fn get_fmri_ref(&self) -> &FMRI {
+        &self.fmri
+    }
+
+    /// Returns `pkg.summary`
+    pub fn get_summary_ref(&self) -> &Option<String> {
+        &self.summary
+    }
+
+    /// Returns `license` attributes of license actions
+    pub fn get_licenses_ref(&self) -> &Vec<String> {
+        &self.licenses
+    }
+
+    /// Returns `info.upstream-url`
+    pub fn get_upstream_url_ref(&self) -> &Option<String> {
+        &self.upstream_url
+    }
+
+    /// Returns `info.source-url`
+    pub fn get_source_url_ref(&self) -> &Option<String> {
+        &self.source_url
+    }
+
+    /// Returns CycloneDX `bom-ref`, which is package URL of the [`FMRI`]
+    pub fn bom_ref(&self) -> String {
+        self.fmri.to_purl()
+    }
+
+    /// Returns SPDX identifier derived from package name and package URL of the [`FMRI`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmri::{FMRI, sbom::Component};
+    /// let component = Component::new(FMRI::parse_raw("pkg://oi/library/c++/sigcpp@2.10").unwrap());
+    /// assert_eq!(component.spdx_id(), "SPDXRef-Package-library-c---sigcpp-2.10-1c7639cf");
+    /// ```
+    pub fn spdx_id(&self) -> String {
+        let name = format!(
+            "{}-{}",
+            self.fmri.get_package_name_as_ref_string(),
+            self.version().unwrap_or_default()
+        );
+        let name: String = name
+            .chars()
+            .map(
+                |c| match c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                    true => c,
+                    false => '-',
+                },
+            )
+            .collect();
+        // hash keeps identifiers unique after replacing characters
+        format!(
+            "SPDXRef-Package-{}-{}",
+            name.trim_end_matches('-'),
+            &sha1_hash(self.bom_ref().as_bytes())[..8]
+        )
+    }
+
+    /// Returns version without '@'
+    fn version(&self) -> Option<String> {
+        self.fmri
+            .get_version_ref()
+            .as_ref()
+            .map(|version| version.to_string().trim_start_matches('@').to_owned())
+    }
+}
+
+/// [`Sbom`] is software bill of materials of installed packages
+///
+/// Components are sorted by package name, dependencies are relationships between components
+/// made by `depend` actions (`require`, `require-any`, `conditional`, `group`, `group-any` and
+/// `optional`). Dependencies on packages outside of the SBOM are skipped.
+///
+/// # Examples
+///
+/// ```
+/// use fmri::{FMRI, FMRIList, manifest::Manifest, sbom::Sbom};
+/// let list = FMRIList::from(vec![
+///     FMRI::parse_raw("pkg://openindiana.org/shell/bash@5.2.21").unwrap(),
+///     FMRI::parse_raw("pkg://openindiana.org/library/ncurses@6.4").unwrap(),
+/// ]);
+/// let mut sbom = Sbom::from_fmri_list("oi-image".to_owned(), &list);
+/// sbom.add_manifest(&Manifest::parse("\
+/// set name=pkg.fmri value=pkg://openindiana.org/shell/bash@5.2.21
+/// set name=pkg.summary value=\"GNU Bourne Again shell\"
+/// license bash.license license=GPLv3
+/// depend fmri=library/ncurses type=require
+/// ").unwrap()).unwrap();
+/// sbom.set_created("2023-09-05T10:15:30Z".to_owned());
+///
+/// let cyclonedx = sbom.to_cyclonedx_json();
+/// assert!(cyclonedx.contains("\"purl\": \"pkg:ips/openindiana.org/shell%2Fbash@5.2.21\""));
+/// let spdx = sbom.to_spdx_tag_value();
+/// assert!(spdx.contains("PackageSummary: <text>GNU Bourne Again shell</text>"));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Sbom {
+    name: String,
+    /// Time in format "2023-09-05T10:15:30Z", current time is used if not set
+    created: Option<String>,
+    namespace: Option<String>,
+    components: BTreeMap<String, Component>,
+}
+
+impl Sbom {
+    /// Returns empty [`Sbom`] with document name
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            created: None,
+            namespace: None,
+            components: BTreeMap::new(),
+        }
+    }
+
+    /// Returns [`Sbom`] with components without metadata
+    pub fn from_fmri_list(name: String, fmri_list: &FMRIList) -> Self {
+        let mut sbom = Self::new(name);
+        for fmri in fmri_list {
+            sbom.add(fmri.clone());
+        }
+        sbom
+    }
+
+    /// Adds component without metadata, component with the same package name is replaced
+    pub fn add(&mut self, fmri: FMRI) {
+        self.components.insert(
+            fmri.get_package_name_as_ref_string().clone(),
+            Component::new(fmri),
+        );
+    }
+
+    /// Adds metadata and dependencies from [`Manifest`] to component with the same package name,
+    /// component is added if it isn't in [`Sbom`]
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if manifest or some dependency doesn't have valid
+    /// [`FMRI`]
+    pub fn add_manifest(&mut self, manifest: &Manifest) -> Result<(), String> {
+        let fmri = manifest
+            .get_fmri()?
+            .ok_or("manifest doesn't have pkg.fmri")?;
+        let component = self
+            .components
+            .entry(fmri.get_package_name_as_ref_string().clone())
+            .or_insert_with(|| Component::new(fmri));
+
+        component.summary = manifest.get_set_value("pkg.summary").cloned();
+        component.upstream_url = manifest.get_set_value("info.upstream-url").cloned();
+        component.source_url = manifest.get_set_value("info.source-url").cloned();
+        component.licenses = manifest
+            .actions_of_type("license")
+            .filter_map(|action| action.get_attribute("license").cloned())
+            .collect();
+
+        component.dependencies.clear();
+        for action in manifest.actions_of_type("depend") {
+            let Some(dependency_type) = action.get_attribute("type") else {
+                continue;
+            };
+            if !DEPENDENCY_TYPES.contains(&dependency_type.as_str()) {
+                continue;
+            }
+            let optional = matches!(dependency_type.as_str(), "optional" | "group" | "group-any");
+            for fmri in action.get_attribute_values("fmri") {
+                let name = FMRI::parse_raw(fmri)?.get_package_name_as_string();
+                let entry = component.dependencies.entry(name).or_insert(optional);
+                *entry &= optional;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_name_ref(&self) -> &String {
+        &self.name
+    }
+
+    /// Sets creation time in format "2023-09-05T10:15:30Z"
+    pub fn set_created(&mut self, created: String) {
+        self.created = Some(created)
+    }
+
+    /// Sets SPDX document namespace, default is derived from name and components
+    pub fn set_namespace(&mut self, namespace: String) {
+        self.namespace = Some(namespace)
+    }
+
+    /// Returns components sorted by package name
+    pub fn components(&self) -> impl Iterator<Item = &Component> {
+        self.components.values()
+    }
+
+    /// Returns component by package name
+    pub fn get(&self, package_name: &str) -> Option<&Component> {
+        self.components.get(package_name)
+    }
+
+    /// Returns CycloneDX 1.5 JSON document
+    pub fn to_cyclonedx_json(&self) -> String {
+        let components: Vec<Value> = self
+            .components()
+            .map(|component| {
+                let mut value = json!({
+                    "type": "library",
+                    "bom-ref": component.bom_ref(),
+                    "name": component.fmri.get_package_name_as_ref_string(),
+                    "purl": component.bom_ref(),
+                });
+                if let Some(version) = component.version() {
+                    value["version"] = json!(version);
+                }
+                if let Some(publisher) = component.fmri.get_publisher_as_ref_string() {
+                    value["publisher"] = json!(publisher);
+                }
+                if let Some(summary) = &component.summary {
+                    value["description"] = json!(summary);
+                }
+                if !component.licenses.is_empty() {
+                    value["licenses"] = component
+                        .licenses
+                        .iter()
+                        .map(|license| json!({"license": {"name": license}}))
+                        .collect();
+                }
+                let references: Vec<Value> = [
+                    ("website", &component.upstream_url),
+                    ("source-distribution", &component.source_url),
+                ]
+                .into_iter()
+                .filter_map(|(reference_type, url)| {
+                    Some(json!({"type": reference_type, "url": url.as_ref()?}))
+                })
+                .collect();
+                if !references.is_empty() {
+                    value["externalReferences"] = json!(references);
+                }
+                value
+            })
+            .collect();
+
+        let dependencies: Vec<Value> = self
+            .components()
+            .map(|component| {
+                json!({
+                    "ref": component.bom_ref(),
+                    "dependsOn": self
+                        .dependencies_of(component)
+                        .map(|(dependency, _)| dependency.bom_ref())
+                        .collect::<Vec<String>>(),
+                })
+            })
+            .collect();
+
+        let document = json!({
+            "bomFormat": "CycloneDX",
+            "specVersion": "1.5",
+            "version": 1,
+            "metadata": {
+                "timestamp": self.created(),
+                "tools": [{"name": env!("CARGO_PKG_NAME"), "version": env!("CARGO_PKG_VERSION")}],
+                "component": {"type": "operating-system", "name": self.name},
+            },
+            "components": components,
+            "dependencies": dependencies,
+        });
+        format!("{:#}", document)
+    }
+
+    /// Returns SPDX 2.3 JSON document
+    pub fn to_spdx_json(&self) -> String {
+        let packages: Vec<Value> = self
+            .components()
+            .map(|component| {
+                let mut value = json!({
+                    "name": component.fmri.get_package_name_as_ref_string(),
+                    "SPDXID": component.spdx_id(),
+                    "supplier": supplier(component),
+                    "downloadLocation": download_location(component),
+                    "filesAnalyzed": false,
+                    "licenseConcluded": "NOASSERTION",
+                    "licenseDeclared": "NOASSERTION",
+                    "copyrightText": "NOASSERTION",
+                    "externalRefs": [{
+                        "referenceCategory": "PACKAGE-MANAGER",
+                        "referenceType": "purl",
+                        "referenceLocator": component.bom_ref(),
+                    }],
+                });
+                if let Some(version) = component.version() {
+                    value["versionInfo"] = json!(version);
+                }
+                if let Some(homepage) = &component.upstream_url {
+                    value["homepage"] = json!(homepage);
+                }
+                if let Some(comments) = license_comments(component) {
+                    value["licenseComments"] = json!(comments);
+                }
+                if let Some(summary) = &component.summary {
+                    value["summary"] = json!(summary);
+                }
+                value
+            })
+            .collect();
+
+        let relationships: Vec<Value> = self
+            .relationships()
+            .into_iter()
+            .map(|(element, relationship, related)| {
+                json!({
+                    "spdxElementId": element,
+                    "relationshipType": relationship,
+                    "relatedSpdxElement": related,
+                })
+            })
+            .collect();
+
+        let document = json!({
+            "spdxVersion": "SPDX-2.3",
+            "dataLicense": "CC0-1.0",
+            "SPDXID": "SPDXRef-DOCUMENT",
+            "name": self.name,
+            "documentNamespace": self.namespace(),
+            "creationInfo": {
+                "created": self.created(),
+                "creators": [creator()],
+            },
+            "packages": packages,
+            "relationships": relationships,
+        });
+        format!("{:#}", document)
+    }
+
+    /// Returns SPDX 2.3 tag-value document
+    pub fn to_spdx_tag_value(&self) -> String {
+        let mut lines = vec![
+            "SPDXVersion: SPDX-2.3".to_owned(),
+            "DataLicense: CC0-1.0".to_owned(),
+            "SPDXID: SPDXRef-DOCUMENT".to_owned(),
+            format!("DocumentName: {}", self.name),
+            format!("DocumentNamespace: {}", self.namespace()),
+            format!("Creator: {}", creator()),
+            format!("Created: {}", self.created()),
+        ];
+
+        for component in self.components() {
+            lines.push(String::new());
+            lines.push(format!(
+                "PackageName: {}",
+                component.fmri.get_package_name_as_ref_string()
+            ));
+            lines.push(format!("SPDXID: {}", component.spdx_id()));
+            if let Some(version) = component.version() {
+                lines.push(format!("PackageVersion: {}", version));
+            }
+            lines.push(format!("PackageSupplier: {}", supplier(component)));
+            lines.push(format!(
+                "PackageDownloadLocation: {}",
+                download_location(component)
+            ));
+            lines.push("FilesAnalyzed: false".to_owned());
+            if let Some(homepage) = &component.upstream_url {
+                lines.push(format!("PackageHomePage: {}", homepage));
+            }
+            lines.push("PackageLicenseConcluded: NOASSERTION".to_owned());
+            lines.push("PackageLicenseDeclared: NOASSERTION".to_owned());
+            if let Some(comments) = license_comments(component) {
+                lines.push(format!("PackageLicenseComments: <text>{}</text>", comments));
+            }
+            lines.push("PackageCopyrightText: NOASSERTION".to_owned());
+            if let Some(summary) = &component.summary {
+                lines.push(format!("PackageSummary: <text>{}</text>", summary));
+            }
+            lines.push(format!(
+                "ExternalRef: PACKAGE-MANAGER purl {}",
+                component.bom_ref()
+            ));
+        }
+
+        lines.push(String::new());
+        for (element, relationship, related) in self.relationships() {
+            lines.push(format!(
+                "Relationship: {} {} {}",
+                element, relationship, related
+            ));
+        }
+        lines.join("\n") + "\n"
+    }
+
+    /// Returns components, which component depends on, and if the dependencies are optional
+    fn dependencies_of<'a>(
+        &'a self,
+        component: &'a Component,
+    ) -> impl Iterator<Item = (&'a Component, bool)> {
+        component
+            .dependencies
+            .iter()
+            .filter_map(|(name, optional)| Some((self.components.get(name)?, *optional)))
+    }
+
+    /// Returns SPDX relationships, the document describes all components
+    fn relationships(&self) -> Vec<(String, &'static str, String)> {
+        let mut relationships: Vec<(String, &'static str, String)> = self
+            .components()
+            .map(|component| {
+                (
+                    "SPDXRef-DOCUMENT".to_owned(),
+                    "DESCRIBES",
+                    component.spdx_id(),
+                )
+            })
+            .collect();
+        for component in self.components() {
+            for (dependency, optional) in self.dependencies_of(component) {
+                relationships.push(match optional {
+                    true => (
+                        dependency.spdx_id(),
+                        "OPTIONAL_DEPENDENCY_OF",
+                        component.spdx_id(),
+                    ),
+                    false => (component.spdx_id(), "DEPENDS_ON", dependency.spdx_id()),
+                });
+            }
+        }
+        relationships
+    }
+
+    fn created(&self) -> String {
+        self.created.clone().unwrap_or_else(|| {
+            let seconds = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default();
+            format_time(seconds)
+        })
+    }
+
+    fn namespace(&self) -> String {
+        self.namespace.clone().unwrap_or_else(|| {
+            let purls: Vec<String> = self.components().map(Component::bom_ref).collect();
+            format!(
+                "https://spdx.org/spdxdocs/{}-{}",
+                self.name,
+                sha1_hash(purls.join("\n").as_bytes())
+            )
+        })
+    }
+}
+
+fn creator() -> String {
+    format!(
+        "Tool: {}-{}",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION")
+    )
+}
+
+fn supplier(component: &Component) -> String {
+    match component.fmri.get_publisher_as_ref_string() {
+        Some(publisher) => format!("Organization: {}", publisher),
+        None => "NOASSERTION".to_owned(),
+    }
+}
+
+fn download_location(component: &Component) -> String {
+    component
+        .source_url
+        .clone()
+        .unwrap_or("NOASSERTION".to_owned())
+}
+
+/// License names of packages aren't SPDX expressions, so they are only comments
+fn license_comments(component: &Component) -> Option<String> {
+    match component.licenses.is_empty() {
+        true => None,
+        false => Some(format!("Licenses: {}", component.licenses.join(", "))),
+    }
+}
+
+/// Formats seconds since Unix epoch as "2023-09-05T10:15:30Z"
+fn format_time(seconds: u64) -> String {
+    let (days, time) = (seconds / 86400, seconds % 86400);
+    // civil from days, Howard Hinnant's algorithm
+    let z = days + 719468;
+    let era = z / 146097;
+    let day_of_era = z % 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
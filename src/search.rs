@@ -0,0 +1,380 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+
+use crate::{
+    helpers::glob_match,
+    manifest::{action::Action, Manifest},
+    search::query::{Expression, Query, Term},
+    FMRI,
+};
+
+pub mod query;
+
+/// [`Match`] is one indexed value of matching [`Action`], like one line of `pkg search` output
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct Match {
+    index: String,
+    value: String,
+    action: Action,
+}
+
+impl Match {
+    /// Returns index name, like "basename", "path", "fmri" or name of set action
+    pub fn get_index_ref(&self) -> &String {
+        &self.index
+    }
+
+    pub fn get_value_ref(&self) -> &String {
+        &self.value
+    }
+
+    pub fn get_action_ref(&self) -> &Action {
+        &self.action
+    }
+}
+
+impl Display for Match {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.index,
+            self.action.get_action_type_ref(),
+            self.value
+        )
+    }
+}
+
+/// [`SearchResult`] is matching package with its matching actions
+///
+/// Matches are empty for package only queries (`<...>`).
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct SearchResult {
+    fmri: FMRI,
+    matches: Vec<Match>,
+}
+
+impl SearchResult {
+    pub fn get_fmri_ref(&self) -> &FMRI {
+        &self.fmri
+    }
+
+    pub fn get_matches_ref(&self) -> &Vec<Match> {
+        &self.matches
+    }
+}
+
+/// One indexed value, pkg(5) index entry `(action type, index, value)` with its tokens
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct Entry {
+    package: usize,
+    action: usize,
+    index: String,
+    value: String,
+    tokens: Vec<String>,
+}
+
+/// [`SearchIndex`] is in-process pkg(5) search index of package [`Manifests`][Manifest]
+///
+/// Actions are indexed like `pkg search` does:
+/// - `file`, `dir`, `link` and `hardlink` by "path" and "basename", `file` also by "content"
+///   (payload and `pkg.content-hash`)
+/// - `depend` by dependency type, tokens are the [`FMRI`], its package name and the last part of
+///   package name
+/// - `set name=pkg.fmri` by "fmri", other `set` actions by their name, tokens are words of values
+/// - other actions by their key attribute
+///
+/// Tokens are matched case-insensitively unless [`Query`] is case-sensitive.
+///
+/// # Examples
+///
+/// ```
+/// use fmri::{manifest::Manifest, search::{SearchIndex, query::Query}};
+/// let mut index = SearchIndex::new();
+/// index.add_manifest(&Manifest::parse("\
+/// set name=pkg.fmri value=pkg://openindiana.org/shell/bash@5.2
+/// set name=pkg.description value=\"GNU Bourne Again shell\"
+/// file path=usr/bin/bash mode=0555
+/// ").unwrap()).unwrap();
+/// index.add_manifest(&Manifest::parse("\
+/// set name=pkg.fmri value=pkg://openindiana.org/shell/zsh@5.9
+/// file path=usr/bin/zsh mode=0555
+/// ").unwrap()).unwrap();
+///
+/// let results = index.search(&Query::parse("basename:bash").unwrap());
+/// assert_eq!(results.len(), 1);
+/// assert_eq!(results[0].get_fmri_ref().get_package_name_as_ref_string(), "shell/bash");
+/// assert_eq!(results[0].get_matches_ref()[0].to_string(), "basename file usr/bin/bash");
+///
+/// assert_eq!(index.search(&Query::parse("<file::usr/bin/*sh>").unwrap()).len(), 2);
+/// assert_eq!(index.search(&Query::parse("bourne AND zsh").unwrap()).len(), 0);
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct SearchIndex {
+    packages: Vec<(FMRI, Vec<Action>)>,
+    /// [`FMRIs`][FMRI] of packages, for quick check of already indexed package
+    indexed: BTreeSet<FMRI>,
+    entries: Vec<Entry>,
+    /// Lowercase tokens with indexes of their entries
+    tokens: BTreeMap<String, BTreeSet<usize>>,
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Indexes [`Manifest`] under its `pkg.fmri`
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if manifest doesn't have valid `pkg.fmri` or the
+    /// package is already indexed
+    pub fn add_manifest(&mut self, manifest: &Manifest) -> Result<(), String> {
+        let fmri = manifest
+            .get_fmri()?
+            .ok_or("manifest doesn't have pkg.fmri")?;
+        self.add(fmri, manifest)
+    }
+
+    /// Indexes [`Manifest`] under given [`FMRI`]
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if the package is already indexed
+    pub fn add(&mut self, fmri: FMRI, manifest: &Manifest) -> Result<(), String> {
+        if !self.indexed.insert(fmri.clone()) {
+            return Err(format!("package {} is already indexed", fmri));
+        }
+
+        let package = self.packages.len();
+        for (action_number, action) in manifest.iter().enumerate() {
+            for (index, value, tokens) in entries(action) {
+                let entry = self.entries.len();
+                for token in &tokens {
+                    self.tokens
+                        .entry(token.to_lowercase())
+                        .or_default()
+                        .insert(entry);
+                }
+                self.entries.push(Entry {
+                    package,
+                    action: action_number,
+                    index,
+                    value,
+                    tokens,
+                });
+            }
+        }
+        self.packages.push((fmri, manifest.get_ref().clone()));
+        Ok(())
+    }
+
+    /// Returns count of indexed packages
+    pub fn len(&self) -> usize {
+        self.packages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty()
+    }
+
+    /// Returns indexed [`FMRIs`][FMRI] in order of addition
+    pub fn fmris(&self) -> impl Iterator<Item = &FMRI> {
+        self.packages.iter().map(|(fmri, _)| fmri)
+    }
+
+    /// Returns matching packages in order of addition with their matching actions
+    pub fn search(&self, query: &Query) -> Vec<SearchResult> {
+        self.evaluate(query.get_expression_ref(), query.is_case_sensitive())
+            .into_iter()
+            .map(|(package, entries)| {
+                let (fmri, actions) = &self.packages[package];
+                SearchResult {
+                    fmri: fmri.clone(),
+                    matches: entries
+                        .into_iter()
+                        .map(|entry| {
+                            let entry = &self.entries[entry];
+                            Match {
+                                index: entry.index.clone(),
+                                value: entry.value.clone(),
+                                action: actions[entry.action].clone(),
+                            }
+                        })
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns matching entries of every matching package
+    fn evaluate(
+        &self,
+        expression: &Expression,
+        case_sensitive: bool,
+    ) -> BTreeMap<usize, BTreeSet<usize>> {
+        match expression {
+            Expression::Term(term) => {
+                let mut packages: BTreeMap<usize, BTreeSet<usize>> = BTreeMap::new();
+                for entry in self.find(term, case_sensitive) {
+                    packages
+                        .entry(self.entries[entry].package)
+                        .or_default()
+                        .insert(entry);
+                }
+                packages
+            }
+            Expression::And(left, right) => {
+                let mut right = self.evaluate(right, case_sensitive);
+                self.evaluate(left, case_sensitive)
+                    .into_iter()
+                    .filter_map(|(package, mut entries)| {
+                        entries.append(&mut right.remove(&package)?);
+                        Some((package, entries))
+                    })
+                    .collect()
+            }
+            Expression::Or(left, right) => {
+                let mut packages = self.evaluate(left, case_sensitive);
+                for (package, mut entries) in self.evaluate(right, case_sensitive) {
+                    packages.entry(package).or_default().append(&mut entries);
+                }
+                packages
+            }
+            Expression::Packages(expression) => self
+                .evaluate(expression, case_sensitive)
+                .into_keys()
+                .map(|package| (package, BTreeSet::new()))
+                .collect(),
+        }
+    }
+
+    /// Returns entries matching query term
+    fn find(&self, term: &Term, case_sensitive: bool) -> BTreeSet<usize> {
+        let token = term.get_token_ref().to_lowercase();
+        let candidates: BTreeSet<usize> = match token.contains(['*', '?']) {
+            true => self
+                .tokens
+                .iter()
+                .filter(|(indexed, _)| glob_match(&token, indexed))
+                .flat_map(|(_, entries)| entries.iter().copied())
+                .collect(),
+            false => self.tokens.get(&token).cloned().unwrap_or_default(),
+        };
+
+        let field_matches = |pattern: &Option<String>, value: &str| match pattern {
+            Some(pattern) => glob_match(pattern, value),
+            None => true,
+        };
+        candidates
+            .into_iter()
+            .filter(|entry| {
+                let entry = &self.entries[*entry];
+                let (fmri, actions) = &self.packages[entry.package];
+                field_matches(term.get_index_ref(), &entry.index)
+                    && field_matches(
+                        term.get_action_type_ref(),
+                        actions[entry.action].get_action_type_ref(),
+                    )
+                    && term
+                        .get_package_ref()
+                        .as_ref()
+                        .map_or(true, |pattern| fmri.matches_pattern(pattern))
+                    && (!case_sensitive
+                        || entry
+                            .tokens
+                            .iter()
+                            .any(|token| glob_match(term.get_token_ref(), token)))
+            })
+            .collect()
+    }
+}
+
+/// Returns index entries `(index, value, tokens)` of [`Action`]
+fn entries(action: &Action) -> Vec<(String, String, Vec<String>)> {
+    match action.get_action_type_ref().as_str() {
+        "set" => {
+            let Some(name) = action.get_attribute("name") else {
+                return vec![];
+            };
+            let values = action.get_attribute_values("value");
+            if name == "pkg.fmri" {
+                return values
+                    .into_iter()
+                    .map(|value| ("fmri".to_owned(), value.clone(), fmri_tokens(value)))
+                    .collect();
+            }
+            let value = values
+                .iter()
+                .map(|value| value.as_str())
+                .collect::<Vec<&str>>()
+                .join(" ");
+            let tokens = words(&value);
+            vec![(name.clone(), value, tokens)]
+        }
+        "depend" => {
+            let dependency_type = action
+                .get_attribute("type")
+                .cloned()
+                .unwrap_or("require".to_owned());
+            action
+                .get_attribute_values("fmri")
+                .into_iter()
+                .map(|fmri| (dependency_type.clone(), fmri.clone(), fmri_tokens(fmri)))
+                .collect()
+        }
+        "file" | "dir" | "link" | "hardlink" => {
+            let Some(path) = action.get_attribute("path") else {
+                return vec![];
+            };
+            let path = path.trim_start_matches('/');
+            let basename = path.rsplit('/').next().unwrap_or(path);
+            let mut entries = vec![
+                (
+                    "basename".to_owned(),
+                    path.to_owned(),
+                    vec![basename.to_owned()],
+                ),
+                ("path".to_owned(), path.to_owned(), vec![path.to_owned()]),
+            ];
+            if action.get_action_type_ref() == "file" {
+                let hashes = action
+                    .get_payload_ref()
+                    .into_iter()
+                    .chain(action.get_attribute_values("pkg.content-hash"));
+                for hash in hashes {
+                    entries.push(("content".to_owned(), hash.clone(), vec![hash.clone()]));
+                }
+            }
+            entries
+        }
+        _ => match (action.get_key_attribute_name(), action.get_key_attribute()) {
+            (Some(key), Some(value)) => vec![(key.to_owned(), value.clone(), vec![value.clone()])],
+            _ => vec![],
+        },
+    }
+}
+
+/// Returns the whole [`FMRI`], its package name and the last part of package name
+fn fmri_tokens(value: &str) -> Vec<String> {
+    let mut tokens = vec![value.to_owned()];
+    if let Ok(fmri) = FMRI::parse_raw(value) {
+        let name = fmri.get_package_name_as_ref_string();
+        tokens.push(name.clone());
+        if let Some((_, last)) = name.rsplit_once('/') {
+            tokens.push(last.to_owned());
+        }
+    }
+    tokens.dedup();
+    tokens
+}
+
+/// Splits text into words without surrounding punctuation
+fn words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|word| !word.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
@@ -0,0 +1,226 @@
+use std::fmt::{Display, Formatter};
+use std::iter::Peekable;
+use std::vec::IntoIter;
+
+/// [`Query`] is parsed pkg(5) search query
+///
+/// Terms are `<package>:<action type>:<index>:<token>`, the leading fields can be omitted and
+/// empty fields match anything. Fields and token can contain `*` and `?` wildcards. Terms are
+/// combined with `AND` (also implicit between terms), `OR` and parentheses, `AND` binds tighter.
+/// Results of expressions in `<...>` are only packages without matching actions.
+///
+/// # Examples
+///
+/// ```
+/// use fmri::search::query::Query;
+/// assert!(Query::parse("bash").is_ok());
+/// assert!(Query::parse("file:basename:bash OR <depend:require:library/ncurses>").is_ok());
+/// assert!(Query::parse("shell/*:set:pkg.description:bourne").is_ok());
+/// assert!(Query::parse("(bash OR").is_err());
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct Query {
+    expression: Expression,
+    case_sensitive: bool,
+}
+
+impl Query {
+    /// Parses case-insensitive query
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if query is empty or invalid
+    pub fn parse(query: &str) -> Result<Self, String> {
+        let mut tokens = tokenize(query).into_iter().peekable();
+        if tokens.peek().is_none() {
+            return Err("query is empty".to_owned());
+        }
+
+        let expression = parse_or(&mut tokens)?;
+        match tokens.next() {
+            None => Ok(Self {
+                expression,
+                case_sensitive: false,
+            }),
+            Some(token) => Err(format!("unexpected \"{}\" in query", token)),
+        }
+    }
+
+    pub fn is_case_sensitive(&self) -> bool {
+        self.case_sensitive
+    }
+
+    /// Enables case-sensitive matching of tokens, like `pkg search -I`
+    pub fn set_case_sensitive(&mut self, case_sensitive: bool) {
+        self.case_sensitive = case_sensitive
+    }
+
+    pub(crate) fn get_expression_ref(&self) -> &Expression {
+        &self.expression
+    }
+}
+
+impl Display for Query {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.expression)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub(crate) enum Expression {
+    Term(Term),
+    And(Box<Expression>, Box<Expression>),
+    Or(Box<Expression>, Box<Expression>),
+    /// `<...>`, only packages are returned
+    Packages(Box<Expression>),
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Term(term) => write!(f, "{}", term),
+            Self::And(left, right) => write!(f, "({} AND {})", left, right),
+            Self::Or(left, right) => write!(f, "({} OR {})", left, right),
+            Self::Packages(expression) => write!(f, "<{}>", expression),
+        }
+    }
+}
+
+/// One query term, [`None`] fields match anything
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub(crate) struct Term {
+    package: Option<String>,
+    action_type: Option<String>,
+    index: Option<String>,
+    token: String,
+}
+
+impl Term {
+    fn parse(term: &str) -> Result<Self, String> {
+        let mut fields: Vec<Option<String>> = term
+            .splitn(4, ':')
+            .map(|field| match field.is_empty() {
+                true => None,
+                false => Some(field.to_owned()),
+            })
+            .collect();
+        let token = fields
+            .pop()
+            .flatten()
+            .ok_or(format!("term \"{}\" doesn't have token", term))?;
+
+        // missing fields are the leading ones
+        while fields.len() < 3 {
+            fields.insert(0, None);
+        }
+        let mut fields = fields.into_iter();
+        Ok(Self {
+            package: fields.next().flatten(),
+            action_type: fields.next().flatten(),
+            index: fields.next().flatten(),
+            token,
+        })
+    }
+
+    pub(crate) fn get_package_ref(&self) -> &Option<String> {
+        &self.package
+    }
+
+    pub(crate) fn get_action_type_ref(&self) -> &Option<String> {
+        &self.action_type
+    }
+
+    pub(crate) fn get_index_ref(&self) -> &Option<String> {
+        &self.index
+    }
+
+    pub(crate) fn get_token_ref(&self) -> &String {
+        &self.token
+    }
+}
+
+impl Display for Term {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let field = |field: &Option<String>| field.clone().unwrap_or_default();
+        write!(
+            f,
+            "{}:{}:{}:{}",
+            field(&self.package),
+            field(&self.action_type),
+            field(&self.index),
+            self.token
+        )
+    }
+}
+
+/// Splits query into words, parentheses and angle brackets
+fn tokenize(query: &str) -> Vec<String> {
+    let mut tokens = vec![];
+    let mut word = String::new();
+    for c in query.chars() {
+        if c.is_whitespace() || matches!(c, '(' | ')' | '<' | '>') {
+            if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+            if !c.is_whitespace() {
+                tokens.push(c.to_string());
+            }
+        } else {
+            word.push(c);
+        }
+    }
+    if !word.is_empty() {
+        tokens.push(word);
+    }
+    tokens
+}
+
+type Tokens = Peekable<IntoIter<String>>;
+
+fn parse_or(tokens: &mut Tokens) -> Result<Expression, String> {
+    let mut expression = parse_and(tokens)?;
+    while tokens.next_if(|token| token == "OR").is_some() {
+        expression = Expression::Or(Box::new(expression), Box::new(parse_and(tokens)?));
+    }
+    Ok(expression)
+}
+
+fn parse_and(tokens: &mut Tokens) -> Result<Expression, String> {
+    let mut expression = parse_unary(tokens)?;
+    loop {
+        if tokens.next_if(|token| token == "AND").is_none()
+            && tokens
+                .peek()
+                .map_or(true, |token| matches!(token.as_str(), "OR" | ")" | ">"))
+        {
+            return Ok(expression);
+        }
+        expression = Expression::And(Box::new(expression), Box::new(parse_unary(tokens)?));
+    }
+}
+
+fn parse_unary(tokens: &mut Tokens) -> Result<Expression, String> {
+    let token = tokens.next().ok_or("query ends unexpectedly")?;
+    match token.as_str() {
+        "(" => {
+            let expression = parse_or(tokens)?;
+            expect(tokens, ")")?;
+            Ok(expression)
+        }
+        "<" => {
+            let expression = parse_or(tokens)?;
+            expect(tokens, ">")?;
+            Ok(Expression::Packages(Box::new(expression)))
+        }
+        ")" | ">" | "AND" | "OR" => Err(format!("unexpected \"{}\" in query", token)),
+        _ => Ok(Expression::Term(Term::parse(&token)?)),
+    }
+}
+
+fn expect(tokens: &mut Tokens, expected: &str) -> Result<(), String> {
+    match tokens.next() {
+        Some(token) if token == expected => Ok(()),
+        Some(token) => Err(format!("expected \"{}\", found \"{}\"", expected, token)),
+        None => Err(format!("expected \"{}\" at the end of query", expected)),
+    }
+}
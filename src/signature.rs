@@ -0,0 +1,304 @@
+use rsa::{
+    pkcs1::DecodeRsaPrivateKey,
+    pkcs8::DecodePrivateKey,
+    sha2::{Digest, Sha256, Sha384, Sha512},
+    Pkcs1v15Sign, RsaPrivateKey,
+};
+
+use crate::{
+    manifest::{action::Action, Manifest},
+    payload::PayloadHashes,
+    signature::certificate::{CertificateStore, ValidatedChain},
+};
+
+pub mod certificate;
+
+/// Version of signed message format, pkg(5) `Action.sig_version`
+pub const SIGNATURE_VERSION: &str = "0";
+
+/// [`SignaturePolicy`] is pkg(5) `signature-policy` image property
+#[derive(Clone, PartialEq, Eq, Debug, Default, Hash)]
+pub enum SignaturePolicy {
+    /// Signatures aren't checked
+    Ignore,
+    /// Present signatures must be valid
+    #[default]
+    Verify,
+    /// At least one valid signature is required
+    RequireSignatures,
+    /// Like [`SignaturePolicy::RequireSignatures`], every name must be common name of some
+    /// certificate in chains of valid signatures
+    RequireNames(Vec<String>),
+}
+
+impl SignaturePolicy {
+    /// Parses policy name, names are used only by "require-names"
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if policy is unknown or "require-names" is without
+    /// names
+    pub fn parse(policy: &str, names: Vec<String>) -> Result<Self, String> {
+        match policy {
+            "ignore" => Ok(Self::Ignore),
+            "verify" => Ok(Self::Verify),
+            "require-signatures" => Ok(Self::RequireSignatures),
+            "require-names" if names.is_empty() => {
+                Err("signature policy require-names needs at least one name".to_owned())
+            }
+            "require-names" => Ok(Self::RequireNames(names)),
+            _ => Err(format!("unknown signature policy \"{}\"", policy)),
+        }
+    }
+}
+
+/// Returns message signed by signature [`Action`], like pkg(5) does
+///
+/// The message is sorted list of actions with sorted attributes, other signature actions are
+/// skipped and value of the signature is empty.
+///
+/// # Examples
+///
+/// ```
+/// use fmri::{manifest::{Manifest, action::Action}, signature::signed_message};
+/// let manifest = Manifest::parse("\
+/// set name=pkg.fmri value=pkg://openindiana.org/shell/bash@5.2
+/// file 1234 path=usr/bin/bash mode=0555 group=bin
+/// signature 5678 algorithm=sha256 value=abcd version=0
+/// ").unwrap();
+/// let signature = manifest.actions_of_type("signature").next().unwrap();
+/// assert_eq!(signed_message(&manifest, signature), "\
+/// file 1234 group=bin mode=0555 path=usr/bin/bash
+/// set name=pkg.fmri value=pkg://openindiana.org/shell/bash@5.2
+/// signature 5678 algorithm=sha256 value=\"\" version=0");
+/// ```
+pub fn signed_message(manifest: &Manifest, signature: &Action) -> String {
+    let mut signing = signature.clone();
+    signing.set_attribute("value".to_owned(), String::new());
+
+    let mut lines: Vec<String> = manifest
+        .iter()
+        .filter(|action| action.get_action_type_ref() != "signature")
+        .map(sig_str)
+        .collect();
+    lines.push(sig_str(&signing));
+    lines.sort();
+    lines.join("\n")
+}
+
+/// Signs [`Manifest`] with RSA key and adds signature action
+///
+/// Key is PEM encoded PKCS#8 or PKCS#1 RSA private key, certificate and chain (from the
+/// signing certificate towards the trust anchor) are PEM encoded. Payload of the signature is
+/// hash of the certificate.
+///
+/// RSA implementation is affected by
+/// [RUSTSEC-2023-0071](https://rustsec.org/advisories/RUSTSEC-2023-0071), timing of signing can
+/// leak the private key, so don't sign where timing can be observed by an attacker.
+///
+/// # Error
+///
+/// Returns a string with error message if key or certificates are invalid, or key doesn't match
+/// the certificate
+pub fn sign(
+    manifest: &mut Manifest,
+    key_pem: &str,
+    certificate_pem: &str,
+    chain_pems: &[&str],
+) -> Result<(), String> {
+    let key = RsaPrivateKey::from_pkcs8_pem(key_pem)
+        .or_else(|_| RsaPrivateKey::from_pkcs1_pem(key_pem))
+        .map_err(|e| format!("invalid private key: {}", e))?;
+    let certificate = certificate::parse_pem(certificate_pem)?;
+    if certificate::rsa_public_key(&certificate)? != key.to_public_key() {
+        return Err("private key doesn't match certificate".to_owned());
+    }
+
+    let mut signature = Action::new("signature".to_owned());
+    PayloadHashes::compute(certificate_pem.as_bytes())?.apply_to(&mut signature);
+    // only legacy hashes are used by signature actions
+    signature.remove_attribute("pkg.content-hash");
+    signature.add_attribute("algorithm".to_owned(), "rsa-sha256".to_owned());
+
+    if !chain_pems.is_empty() {
+        let mut chain: [Vec<String>; 4] = Default::default();
+        for pem in chain_pems {
+            certificate::parse_pem(pem)?;
+            let hashes = PayloadHashes::compute(pem.as_bytes())?;
+            chain[0].push(hashes.get_hash_ref().clone());
+            chain[1].push(hashes.get_chash_ref().clone());
+            chain[2].push(hashes.get_csize().to_string());
+            chain[3].push(hashes.get_size().to_string());
+        }
+        for (key, values) in ["chain", "chain.chashes", "chain.csizes", "chain.sizes"]
+            .iter()
+            .zip(chain)
+        {
+            signature.add_attribute(key.to_string(), values.join(" "));
+        }
+    }
+    signature.add_attribute("version".to_owned(), SIGNATURE_VERSION.to_owned());
+    signature.add_attribute("value".to_owned(), String::new());
+
+    let message = signed_message(manifest, &signature);
+    let value = key
+        .sign(
+            Pkcs1v15Sign::new::<Sha256>(),
+            &Sha256::digest(message.as_bytes()),
+        )
+        .map_err(|e| format!("signing failed: {}", e))?;
+    signature.set_attribute("value".to_owned(), hex::encode(value));
+
+    manifest.add(signature);
+    Ok(())
+}
+
+impl CertificateStore {
+    /// Verifies one signature action of [`Manifest`]
+    ///
+    /// Returns validated chain of trust, which is empty for hash only signatures ("sha256").
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if signature or its chain of trust is invalid
+    pub fn verify_signature(
+        &self,
+        manifest: &Manifest,
+        signature: &Action,
+    ) -> Result<ValidatedChain, String> {
+        let algorithm = signature
+            .get_attribute("algorithm")
+            .ok_or("signature without algorithm")?;
+        let value = signature
+            .get_attribute("value")
+            .ok_or("signature without value")?;
+        if let Some(version) = signature.get_attribute("version") {
+            if version != SIGNATURE_VERSION {
+                return Err(format!("unsupported signature version {}", version));
+            }
+        }
+        let value = hex::decode(value).map_err(|e| format!("invalid signature value: {}", e))?;
+        let message = signed_message(manifest, signature);
+
+        let (key_algorithm, hash) = algorithm.split_once('-').unwrap_or(("", algorithm));
+        let (scheme, digest) = match hash {
+            "sha256" => (
+                Pkcs1v15Sign::new::<Sha256>(),
+                Sha256::digest(message.as_bytes()).to_vec(),
+            ),
+            "sha384" => (
+                Pkcs1v15Sign::new::<Sha384>(),
+                Sha384::digest(message.as_bytes()).to_vec(),
+            ),
+            "sha512" => (
+                Pkcs1v15Sign::new::<Sha512>(),
+                Sha512::digest(message.as_bytes()).to_vec(),
+            ),
+            _ => return Err(format!("unsupported signature algorithm {}", algorithm)),
+        };
+
+        match key_algorithm {
+            // hash only signature proves only integrity
+            "" => match digest == value {
+                true => Ok(ValidatedChain::default()),
+                false => Err("manifest hash doesn't match signature".to_owned()),
+            },
+            "rsa" => {
+                let payload = signature
+                    .get_payload_ref()
+                    .ok_or("signature without certificate")?;
+                let chain: Vec<&str> = signature
+                    .get_attribute("chain")
+                    .map(|chain| chain.split_whitespace().collect())
+                    .unwrap_or_default();
+                let validated = self.validate_chain(payload, &chain)?;
+
+                let key = certificate::rsa_public_key(&validated.get_ref()[0])?;
+                key.verify(scheme, &digest, &value)
+                    .map_err(|_| "signature doesn't match manifest".to_owned())?;
+                Ok(validated)
+            }
+            _ => Err(format!("unsupported signature algorithm {}", algorithm)),
+        }
+    }
+
+    /// Verifies signatures of [`Manifest`] according to [`SignaturePolicy`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmri::{manifest::Manifest, signature::{SignaturePolicy, certificate::CertificateStore}};
+    /// let manifest = Manifest::parse("set name=pkg.fmri value=pkg:/shell/bash@5.2").unwrap();
+    /// let store = CertificateStore::new();
+    /// assert!(store.verify(&manifest, &SignaturePolicy::Verify).is_ok());
+    /// assert!(store.verify(&manifest, &SignaturePolicy::RequireSignatures).is_err());
+    /// ```
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if some signature is invalid or policy isn't met
+    pub fn verify(&self, manifest: &Manifest, policy: &SignaturePolicy) -> Result<(), String> {
+        if *policy == SignaturePolicy::Ignore {
+            return Ok(());
+        }
+
+        let mut signed = false;
+        let mut names: Vec<String> = vec![];
+        for signature in manifest.actions_of_type("signature") {
+            let chain = self.verify_signature(manifest, signature)?;
+            // hash only signatures don't satisfy signature requirement
+            if !chain.get_ref().is_empty() {
+                signed = true;
+                names.append(&mut chain.common_names());
+            }
+        }
+
+        match policy {
+            SignaturePolicy::RequireSignatures | SignaturePolicy::RequireNames(_) if !signed => {
+                Err("manifest isn't signed".to_owned())
+            }
+            SignaturePolicy::RequireNames(required) => {
+                match required.iter().find(|name| !names.contains(name)) {
+                    Some(missing) => Err(format!(
+                        "required name \"{}\" isn't in any chain of trust",
+                        missing
+                    )),
+                    None => Ok(()),
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Returns stable string of [`Action`] used in signed message, pkg(5) `Action.sig_str`
+fn sig_str(action: &Action) -> String {
+    let mut out = action.get_action_type_ref().clone();
+    if let Some(payload) = &action.get_payload_ref() {
+        if action.get_attribute("hash") != Some(payload) {
+            out.push(' ');
+            out.push_str(payload);
+        }
+    }
+
+    let mut attributes: Vec<&(String, String)> = action.get_attributes_ref().iter().collect();
+    attributes.sort();
+    for (key, value) in attributes {
+        out.push_str(&format!(" {}={}", key, sig_quote(value)));
+    }
+    out
+}
+
+/// Quotes value like pkg(5) `sig_str` does, only spaces and quotes are significant
+fn sig_quote(value: &str) -> String {
+    if !value.is_empty() && !value.contains([' ', '\'', '"']) {
+        return value.to_owned();
+    }
+    if !value.contains('"') {
+        return format!("\"{}\"", value);
+    }
+    if !value.contains('\'') {
+        return format!("'{}'", value);
+    }
+    format!("\"{}\"", value.replace('"', "\\\""))
+}
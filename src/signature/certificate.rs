@@ -0,0 +1,325 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+use rsa::{
+    pkcs8::DecodePublicKey,
+    sha2::{Digest, Sha256, Sha384, Sha512},
+    Pkcs1v15Sign, RsaPublicKey,
+};
+use x509_cert::{
+    der::{
+        asn1::{Ia5StringRef, PrintableStringRef, Utf8StringRef},
+        oid::{db::rfc4519::CN, ObjectIdentifier},
+        Decode, DecodePem, Encode,
+    },
+    ext::pkix::{BasicConstraints, KeyUsage},
+    name::Name,
+    Certificate,
+};
+
+use crate::payload::sha1_hash;
+
+const SHA256_WITH_RSA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.11");
+const SHA384_WITH_RSA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.12");
+const SHA512_WITH_RSA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.13");
+/// Maximal length of chain of trust
+const MAX_CHAIN_LENGTH: usize = 16;
+
+/// [`ValidatedChain`] is chain of trust from signing certificate to trust anchor
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct ValidatedChain(Vec<Certificate>);
+
+impl ValidatedChain {
+    pub fn get(self) -> Vec<Certificate> {
+        self.0
+    }
+
+    /// Returns certificates, signing certificate is the first and trust anchor the last
+    pub fn get_ref(&self) -> &Vec<Certificate> {
+        &self.0
+    }
+
+    /// Returns common names of subjects of all certificates
+    pub fn common_names(&self) -> Vec<String> {
+        self.0
+            .iter()
+            .flat_map(|certificate| common_names(&certificate.tbs_certificate.subject))
+            .collect()
+    }
+}
+
+/// [`CertificateStore`] contains trust anchors and certificates used by signatures
+///
+/// Certificates are found by pkg(5) payload hash, SHA-1 of their PEM file.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use fmri::{manifest::Manifest, signature::{SignaturePolicy, certificate::CertificateStore}};
+/// let mut store = CertificateStore::new();
+/// store.add_trust_anchor_dir(Path::new("/etc/certs/CA")).unwrap();
+/// store.add_certificate_dir(Path::new("certificates")).unwrap();
+/// let manifest = Manifest::parse(&std::fs::read_to_string("bash.p5m").unwrap()).unwrap();
+/// store.verify(&manifest, &SignaturePolicy::RequireSignatures).unwrap();
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct CertificateStore {
+    trust_anchors: Vec<Certificate>,
+    certificates: BTreeMap<String, Certificate>,
+}
+
+impl CertificateStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds trusted certificate, chains of trust must end with some trust anchor
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if certificate isn't valid PEM
+    pub fn add_trust_anchor(&mut self, pem: &str) -> Result<(), String> {
+        let certificate = parse_pem(pem)?;
+        self.certificates
+            .insert(sha1_hash(pem.as_bytes()), certificate.clone());
+        self.trust_anchors.push(certificate);
+        Ok(())
+    }
+
+    /// Adds trust anchors from all `.pem` files in directory (like `/etc/certs/CA`)
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if directory or some certificate can't be read
+    pub fn add_trust_anchor_dir(&mut self, directory: &Path) -> Result<(), String> {
+        for pem in read_pem_files(directory)? {
+            self.add_trust_anchor(&pem)?;
+        }
+        Ok(())
+    }
+
+    /// Adds certificate which can be used in chains of trust, returns its payload hash
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if certificate isn't valid PEM
+    pub fn add_certificate(&mut self, pem: &str) -> Result<String, String> {
+        let hash = sha1_hash(pem.as_bytes());
+        self.certificates.insert(hash.clone(), parse_pem(pem)?);
+        Ok(hash)
+    }
+
+    /// Adds certificates from all `.pem` files in directory
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if directory or some certificate can't be read
+    pub fn add_certificate_dir(&mut self, directory: &Path) -> Result<(), String> {
+        for pem in read_pem_files(directory)? {
+            self.add_certificate(&pem)?;
+        }
+        Ok(())
+    }
+
+    /// Returns certificate by its payload hash
+    pub fn get(&self, hash: &str) -> Option<&Certificate> {
+        self.certificates.get(hash)
+    }
+
+    pub fn get_trust_anchors_ref(&self) -> &Vec<Certificate> {
+        &self.trust_anchors
+    }
+
+    /// Validates chain of trust from signing certificate through chain certificates to some
+    /// trust anchor
+    ///
+    /// Every certificate must be valid now and signed by the next one, issuers must be
+    /// certificate authorities allowed to sign certificates and signing certificate must be
+    /// allowed to sign data (if it has key usage extension).
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if some certificate is unknown or chain is invalid
+    pub fn validate_chain(&self, signer: &str, chain: &[&str]) -> Result<ValidatedChain, String> {
+        let certificate = self
+            .get(signer)
+            .ok_or(format!("unknown signing certificate {}", signer))?;
+        let mut candidates: Vec<&Certificate> = chain
+            .iter()
+            .map(|hash| {
+                self.get(hash)
+                    .ok_or(format!("unknown chain certificate {}", hash))
+            })
+            .collect::<Result<Vec<&Certificate>, String>>()?;
+        candidates.extend(self.trust_anchors.iter());
+
+        check_validity(certificate)?;
+        if let Some(key_usage) = extension::<KeyUsage>(certificate)? {
+            if !key_usage.digital_signature() {
+                return Err(format!(
+                    "certificate \"{}\" isn't allowed to sign",
+                    certificate.tbs_certificate.subject
+                ));
+            }
+        }
+
+        let mut validated = vec![certificate.clone()];
+        let mut current = certificate;
+        while !self.trust_anchors.contains(current) {
+            if validated.len() > MAX_CHAIN_LENGTH {
+                return Err("chain of trust is too long".to_owned());
+            }
+            let issuer = candidates
+                .iter()
+                .find(|candidate| {
+                    candidate.tbs_certificate.subject == current.tbs_certificate.issuer
+                        && verify_certificate(current, candidate).is_ok()
+                })
+                .ok_or(format!(
+                    "issuer \"{}\" of \"{}\" isn't trusted",
+                    current.tbs_certificate.issuer, current.tbs_certificate.subject
+                ))?;
+
+            check_validity(issuer)?;
+            check_authority(issuer)?;
+            validated.push((*issuer).clone());
+            current = issuer;
+        }
+
+        Ok(ValidatedChain(validated))
+    }
+}
+
+/// Parses PEM encoded certificate
+///
+/// # Error
+///
+/// Returns a string with error message if certificate is invalid
+pub fn parse_pem(pem: &str) -> Result<Certificate, String> {
+    Certificate::from_pem(pem.as_bytes()).map_err(|e| format!("invalid certificate: {}", e))
+}
+
+/// Returns RSA public key of certificate
+///
+/// # Error
+///
+/// Returns a string with error message if certificate doesn't have RSA key
+pub fn rsa_public_key(certificate: &Certificate) -> Result<RsaPublicKey, String> {
+    let der = certificate
+        .tbs_certificate
+        .subject_public_key_info
+        .to_der()
+        .map_err(|e| format!("invalid public key: {}", e))?;
+    RsaPublicKey::from_public_key_der(&der).map_err(|e| format!("unsupported public key: {}", e))
+}
+
+/// Verifies signature of certificate made by issuer
+fn verify_certificate(certificate: &Certificate, issuer: &Certificate) -> Result<(), String> {
+    let tbs = certificate
+        .tbs_certificate
+        .to_der()
+        .map_err(|e| format!("invalid certificate: {}", e))?;
+    let signature = certificate
+        .signature
+        .as_bytes()
+        .ok_or("invalid certificate signature")?;
+
+    let (scheme, digest) = match certificate.signature_algorithm.oid {
+        SHA256_WITH_RSA => (Pkcs1v15Sign::new::<Sha256>(), Sha256::digest(&tbs).to_vec()),
+        SHA384_WITH_RSA => (Pkcs1v15Sign::new::<Sha384>(), Sha384::digest(&tbs).to_vec()),
+        SHA512_WITH_RSA => (Pkcs1v15Sign::new::<Sha512>(), Sha512::digest(&tbs).to_vec()),
+        oid => {
+            return Err(format!(
+                "unsupported certificate signature algorithm {}",
+                oid
+            ))
+        }
+    };
+    rsa_public_key(issuer)?
+        .verify(scheme, &digest, signature)
+        .map_err(|_| "invalid certificate signature".to_owned())
+}
+
+fn check_validity(certificate: &Certificate) -> Result<(), String> {
+    let validity = &certificate.tbs_certificate.validity;
+    let now = SystemTime::now();
+    if now < validity.not_before.to_system_time() || now > validity.not_after.to_system_time() {
+        return Err(format!(
+            "certificate \"{}\" is valid only from {} to {}",
+            certificate.tbs_certificate.subject, validity.not_before, validity.not_after
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that issuer is certificate authority allowed to sign certificates
+fn check_authority(issuer: &Certificate) -> Result<(), String> {
+    let subject = &issuer.tbs_certificate.subject;
+    if !extension::<BasicConstraints>(issuer)?.is_some_and(|constraints| constraints.ca) {
+        return Err(format!("certificate \"{}\" isn't authority", subject));
+    }
+    if let Some(key_usage) = extension::<KeyUsage>(issuer)? {
+        if !key_usage.key_cert_sign() {
+            return Err(format!(
+                "certificate \"{}\" isn't allowed to sign certificates",
+                subject
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn extension<'a, T>(certificate: &'a Certificate) -> Result<Option<T>, String>
+where
+    T: Decode<'a> + x509_cert::der::oid::AssociatedOid,
+{
+    certificate
+        .tbs_certificate
+        .get::<T>()
+        .map(|extension| extension.map(|(_, value)| value))
+        .map_err(|e| {
+            format!(
+                "invalid extension of \"{}\": {}",
+                certificate.tbs_certificate.subject, e
+            )
+        })
+}
+
+fn common_names(name: &Name) -> Vec<String> {
+    name.0
+        .iter()
+        .flat_map(|rdn| rdn.0.iter())
+        .filter(|attribute| attribute.oid == CN)
+        .filter_map(|attribute| {
+            let value = &attribute.value;
+            value
+                .decode_as::<Utf8StringRef>()
+                .map(|s| s.to_string())
+                .or_else(|_| {
+                    value
+                        .decode_as::<PrintableStringRef>()
+                        .map(|s| s.to_string())
+                })
+                .or_else(|_| value.decode_as::<Ia5StringRef>().map(|s| s.to_string()))
+                .ok()
+        })
+        .collect()
+}
+
+fn read_pem_files(directory: &Path) -> Result<Vec<String>, String> {
+    let mut files: Vec<_> = fs::read_dir(directory)
+        .map_err(|e| format!("can't read {}: {}", directory.display(), e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == "pem"))
+        .collect();
+    files.sort();
+    files
+        .iter()
+        .map(|path| {
+            fs::read_to_string(path).map_err(|e| format!("can't read {}: {}", path.display(), e))
+        })
+        .collect()
+}
@@ -1,4 +1,38 @@
+mod arena_test;
+#[cfg(feature = "capi")]
+mod capi_test;
+#[cfg(feature = "manifest")]
+mod conflict_test;
+mod fmri_list_test;
 mod fmri_test;
+#[cfg(any(feature = "arbitrary", feature = "proptest"))]
+mod fuzz_test;
 mod helpers_test;
+#[cfg(feature = "history")]
+mod history_test;
+#[cfg(feature = "manifest")]
+mod incorporation_test;
+#[cfg(feature = "manifest")]
+mod manifest_test;
+#[cfg(feature = "manifest")]
+mod mediator_test;
+#[cfg(feature = "manifest")]
+mod mogrify_test;
+#[cfg(feature = "rayon")]
+mod parallel_test;
+#[cfg(feature = "payload")]
+mod payload_test;
+#[cfg(feature = "pkgdepend")]
+mod pkgdepend_test;
 mod publisher_test;
+mod purl_test;
+#[cfg(all(feature = "manifest", feature = "json"))]
+mod rename_test;
+#[cfg(feature = "sbom")]
+mod sbom_test;
+#[cfg(feature = "manifest")]
+mod search_test;
+#[cfg(feature = "signature")]
+mod signature_test;
+mod version_convert_test;
 mod version_test;
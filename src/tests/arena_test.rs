@@ -0,0 +1,117 @@
+use crate::{fmri_list::arena::FMRIArena, FMRIList, FMRI};
+
+const FMRIS: [&str; 7] = [
+    "pkg://openindiana.org/shell/bash@5.2.15,5.11-2023.0.0.1:20230801T080000Z",
+    "pkg://openindiana.org/shell/bash@5.2.21,5.11-2023.0.0.0:20230901T080000Z",
+    "pkg://openindiana.org/library/zlib@1.3,5.11-2023.0.0.0:20230901T080000Z",
+    "pkg://on-nightly/system/kernel@0.5.11-2023.0.0.22345",
+    "pkg:/library/zlib@1.3",
+    "text/gnu-grep",
+    "fmri=editor/vim@9.0:2023",
+];
+
+#[test]
+fn arena_round_trip() {
+    let list: FMRIList = FMRIS
+        .iter()
+        .map(|fmri| FMRI::parse_raw(fmri).unwrap())
+        .collect();
+    let arena = FMRIArena::from(&list);
+    assert_eq!(arena.len(), FMRIS.len());
+    assert_eq!(arena.to_fmri_list(), list);
+
+    for (view, fmri) in arena.iter().zip(&list) {
+        assert_eq!(view.to_fmri(), *fmri);
+        assert_eq!(view.to_string(), fmri.to_string());
+        assert_eq!(
+            view.get_publisher_ref(),
+            fmri.get_publisher_as_ref_string().map(String::as_str)
+        );
+        assert_eq!(view.has_version(), fmri.has_version());
+    }
+
+    // lenient timestamp is kept as it is
+    assert_eq!(
+        arena.get(6).unwrap().get_timestamp(),
+        Some("2023".to_owned())
+    );
+    assert!(arena.get(7).is_none());
+}
+
+#[test]
+fn arena_ordering() {
+    let list: FMRIList = FMRIS
+        .iter()
+        .map(|fmri| FMRI::parse_raw(fmri).unwrap())
+        .collect();
+    let arena = FMRIArena::from(&list);
+    for (view, fmri) in arena.iter().zip(&list) {
+        for (other_view, other_fmri) in arena.iter().zip(&list) {
+            assert_eq!(view.cmp(&other_view), fmri.cmp(other_fmri));
+            assert_eq!(view == other_view, fmri == other_fmri);
+        }
+    }
+
+    // views of different arenas
+    let other = FMRIArena::from(&list);
+    assert_eq!(arena.get(0), other.get(0));
+    assert_ne!(arena.get(0), other.get(1));
+}
+
+#[test]
+fn arena_timestamp_ordering() {
+    // packed and interned timestamps are ordered like strings
+    let list: FMRIList = [
+        "library/zlib@1.3:20230901T080000Z",
+        "library/zlib@1.3:20230901T080001Z",
+        "library/zlib@1.3:2023",
+        "library/zlib@1.3:20230901T080000Z0",
+        "library/zlib@1.3:3",
+        "library/zlib@1.3:19991231T235959Z",
+        "library/zlib@1.3",
+    ]
+    .iter()
+    .map(|fmri| FMRI::parse_raw(fmri).unwrap())
+    .collect();
+    let arena = FMRIArena::from(&list);
+    for (view, fmri) in arena.iter().zip(&list) {
+        assert_eq!(view.to_string(), fmri.to_string());
+        for (other_view, other_fmri) in arena.iter().zip(&list) {
+            assert_eq!(
+                view.cmp(&other_view),
+                fmri.cmp(other_fmri),
+                "{} and {}",
+                fmri,
+                other_fmri
+            );
+            assert_eq!(view == other_view, fmri == other_fmri);
+        }
+    }
+}
+
+#[test]
+fn arena_interning() {
+    let list: FMRIList = [
+        "pkg://openindiana.org/shell/bash@5.2.15,5.11-2023.0.0.1:20230801T080000Z",
+        "pkg://openindiana.org/shell/bash@5.2.21,5.11-2023.0.0.0:20230901T080000Z",
+        "pkg://openindiana.org/library/zlib@1.3,5.11-2023.0.0.0:20230901T080000Z",
+    ]
+    .iter()
+    .map(|fmri| FMRI::parse_raw(fmri).unwrap())
+    .collect();
+    let arena = FMRIArena::from(&list);
+    let (first, second) = (arena.get(0).unwrap(), arena.get(1).unwrap());
+    assert!(std::ptr::eq(
+        first.get_publisher_ref().unwrap(),
+        second.get_publisher_ref().unwrap()
+    ));
+    assert!(std::ptr::eq(
+        first.get_package_name_ref(),
+        second.get_package_name_ref()
+    ));
+    assert!(std::ptr::eq(
+        first.get_build_version_ref().unwrap(),
+        arena.get(2).unwrap().get_build_version_ref().unwrap()
+    ));
+    assert!(first.package_name_eq(&second));
+}
@@ -0,0 +1,86 @@
+/* C program using include/fmri.h, compiled and run by capi_test.rs */
+
+#include <assert.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "fmri.h"
+
+/* Checks that string equals expected one and frees it, expected NULL means that string is NULL */
+static void assert_string(char *string, const char *expected) {
+    if (expected == NULL) {
+        assert(string == NULL);
+        return;
+    }
+    assert(string != NULL);
+    if (strcmp(string, expected) != 0) {
+        fprintf(stderr, "expected \"%s\", got \"%s\"\n", expected, string);
+        assert(0);
+    }
+    fmri_string_free(string);
+}
+
+int main(void) {
+    fmri_t *bash = fmri_parse(
+        "pkg://openindiana.org/shell/bash@5.2.15,5.11-2023.0.0.1:20230801T080000Z");
+    assert(bash != NULL);
+    assert_string(fmri_to_string(bash),
+        "pkg://openindiana.org/shell/bash@5.2.15,5.11-2023.0.0.1:20230801T080000Z");
+    assert_string(fmri_get_publisher(bash), "openindiana.org");
+    assert_string(fmri_get_package_name(bash), "shell/bash");
+    assert_string(fmri_get_version(bash), "5.2.15,5.11-2023.0.0.1:20230801T080000Z");
+    assert_string(fmri_get_component_version(bash), "5.2.15");
+    assert_string(fmri_get_build_version(bash), "5.11");
+    assert_string(fmri_get_branch_version(bash), "2023.0.0.1");
+    assert_string(fmri_get_timestamp(bash), "20230801T080000Z");
+
+    /* missing parts */
+    fmri_t *zlib = fmri_parse("fmri=library/zlib@1.3");
+    assert(zlib != NULL);
+    assert_string(fmri_to_string(zlib), "pkg:/library/zlib@1.3");
+    assert_string(fmri_get_publisher(zlib), NULL);
+    assert_string(fmri_get_build_version(zlib), NULL);
+    assert_string(fmri_get_timestamp(zlib), NULL);
+
+    fmri_t *grep = fmri_parse_strict("pkg:/text/gnu-grep");
+    assert(grep != NULL);
+    assert_string(fmri_get_version(grep), NULL);
+    assert_string(fmri_get_component_version(grep), NULL);
+
+    /* version comparison */
+    fmri_t *newer_bash = fmri_parse("shell/bash@5.2.21,5.11-2023.0.0.0");
+    assert(fmri_version_cmp(bash, newer_bash) == -1);
+    assert(fmri_version_cmp(newer_bash, bash) == 1);
+    assert(fmri_version_cmp(bash, bash) == 0);
+    assert(fmri_version_cmp(grep, zlib) == -1);
+    assert(fmri_version_cmp(NULL, grep) == 0);
+    /* timestamp doesn't make fmri newer */
+    fmri_t *rebuilt_bash = fmri_parse(
+        "pkg://openindiana.org/shell/bash@5.2.15,5.11-2023.0.0.1:20230901T080000Z");
+    assert(fmri_version_cmp(bash, rebuilt_bash) == 0);
+
+    /* errors */
+    assert(fmri_parse("shell/bash@5.a") == NULL);
+    assert(fmri_last_error() != NULL);
+    assert(strstr(fmri_last_error(), "component version") != NULL);
+    assert(fmri_parse_strict("fmri=shell/bash@5.2") == NULL);
+    /* malformed fmris which used to panic */
+    assert(fmri_parse("pkg://openindiana.org") == NULL);
+    assert(fmri_last_error() != NULL);
+    assert(strncmp(fmri_last_error(), "panic: ", 7) != 0);
+    assert(fmri_parse("foo pkg://x/y") == NULL);
+    assert(strstr(fmri_last_error(), "pkg://") != NULL);
+    assert(fmri_parse(NULL) == NULL);
+    assert(strcmp(fmri_last_error(), "raw fmri is NULL") == 0);
+    assert(fmri_to_string(NULL) == NULL);
+    assert(strcmp(fmri_last_error(), "fmri is NULL") == 0);
+
+    fmri_free(bash);
+    fmri_free(zlib);
+    fmri_free(grep);
+    fmri_free(newer_bash);
+    fmri_free(rebuilt_bash);
+    fmri_free(NULL);
+    fmri_string_free(NULL);
+    return 0;
+}
@@ -0,0 +1,61 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Header generated by build script
+const GENERATED_HEADER: &str = include_str!(concat!(env!("OUT_DIR"), "/fmri.h"));
+
+fn manifest_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Runs command and panics with its output if it fails
+fn run(command: &mut Command) {
+    let output = command.output().unwrap();
+    assert!(
+        output.status.success(),
+        "{:?} failed\n{}{}",
+        command,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn header_is_up_to_date() {
+    let header = std::fs::read_to_string(manifest_dir().join("include/fmri.h")).unwrap();
+    assert!(
+        header == GENERATED_HEADER,
+        "include/fmri.h is outdated, copy it from {}/fmri.h",
+        env!("OUT_DIR")
+    );
+}
+
+/// Builds static library like C users do and runs `capi_test.c` linked with it
+#[cfg(unix)]
+#[test]
+fn c_program() {
+    let target_dir = manifest_dir().join("target/capi");
+    run(Command::new(env!("CARGO"))
+        .args(["rustc", "--quiet", "--lib", "--crate-type", "staticlib"])
+        .args(["--no-default-features", "--features", "capi"])
+        .arg("--manifest-path")
+        .arg(manifest_dir().join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(&target_dir));
+
+    let program: PathBuf = target_dir.join("capi_test");
+    run(
+        Command::new(env::var("CC").unwrap_or_else(|_| "cc".to_owned()))
+            .args(["-std=c99", "-Wall", "-Werror", "-o"])
+            .arg(&program)
+            .arg("-I")
+            .arg(manifest_dir().join("include"))
+            .arg(manifest_dir().join("src/tests/capi_test.c"))
+            .arg(target_dir.join("debug/libfmri.a"))
+            .args(["-lpthread", "-ldl", "-lm"]),
+    );
+    run(&mut Command::new(program));
+}
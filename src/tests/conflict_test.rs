@@ -0,0 +1,138 @@
+use crate::conflict::{ConflictChecker, Rule};
+use crate::manifest::Manifest;
+
+fn checker(manifests: &[&str]) -> ConflictChecker {
+    let mut checker = ConflictChecker::new();
+    for manifest in manifests {
+        checker
+            .add_manifest(&Manifest::parse(manifest).unwrap())
+            .unwrap();
+    }
+    checker
+}
+
+fn rules(manifests: &[&str]) -> Vec<Rule> {
+    checker(manifests)
+        .check()
+        .iter()
+        .map(|conflict| conflict.get_rule_ref().clone())
+        .collect()
+}
+
+#[test]
+fn conflict_files() {
+    let conflicts = checker(&[
+        "set name=pkg.fmri value=pkg:/a@1\nfile path=etc/motd mode=0644\n",
+        "set name=pkg.fmri value=pkg:/b@1\nfile path=/etc/motd mode=0644\n",
+        "set name=pkg.fmri value=pkg:/c@1\nfile path=etc/issue mode=0644\n",
+    ])
+    .check();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].get_path_ref(), "etc/motd");
+    assert_eq!(conflicts[0].get_rule_ref(), &Rule::DuplicateFile);
+    assert_eq!(
+        conflicts[0]
+            .get_fmris_ref()
+            .iter()
+            .map(|fmri| fmri.to_string())
+            .collect::<Vec<String>>(),
+        vec!["pkg:/a@1", "pkg:/b@1"]
+    );
+
+    assert!(ConflictChecker::new()
+        .add_manifest(&Manifest::parse("file path=etc/motd").unwrap())
+        .is_err());
+}
+
+#[test]
+fn conflict_overlay() {
+    let allow = "set name=pkg.fmri value=pkg:/a@1\nfile path=etc/motd overlay=allow\n";
+    let overlay = "set name=pkg.fmri value=pkg:/b@1\nfile path=etc/motd overlay=true\n";
+    let other = "set name=pkg.fmri value=pkg:/c@1\nfile path=etc/motd overlay=true\n";
+    assert!(rules(&[allow, overlay]).is_empty());
+    assert_eq!(rules(&[allow, overlay, other]), vec![Rule::Overlay]);
+    assert_eq!(rules(&[overlay, other]), vec![Rule::Overlay]);
+    assert_eq!(
+        rules(&[
+            allow,
+            "set name=pkg.fmri value=pkg:/b@1\nfile path=etc/motd\n"
+        ]),
+        vec![Rule::Overlay]
+    );
+}
+
+#[test]
+fn conflict_action_types() {
+    assert_eq!(
+        rules(&[
+            "set name=pkg.fmri value=pkg:/a@1\ndir path=usr/lib/a mode=0755\n",
+            "set name=pkg.fmri value=pkg:/b@1\nfile path=usr/lib/a mode=0644\n",
+        ]),
+        vec![Rule::ActionTypes(vec!["dir".to_owned(), "file".to_owned()])]
+    );
+}
+
+#[test]
+fn conflict_dirs() {
+    let dir = "set name=pkg.fmri value=pkg:/a@1\ndir path=usr/lib mode=0755 owner=root group=bin\n";
+    assert!(rules(&[
+        dir,
+        "set name=pkg.fmri value=pkg:/b@1\ndir path=usr/lib mode=0755 owner=root group=bin\n",
+    ])
+    .is_empty());
+    assert_eq!(
+        rules(&[
+            dir,
+            "set name=pkg.fmri value=pkg:/b@1\ndir path=usr/lib mode=0775 owner=root group=sys\n",
+        ]),
+        vec![
+            Rule::Attribute("group".to_owned()),
+            Rule::Attribute("mode".to_owned())
+        ]
+    );
+}
+
+#[test]
+fn conflict_links() {
+    let link = "set name=pkg.fmri value=pkg:/a@1\nlink path=usr/bin/sh target=bash\n";
+    assert!(rules(&[
+        link,
+        "set name=pkg.fmri value=pkg:/b@1\nlink path=usr/bin/sh target=bash\n"
+    ])
+    .is_empty());
+    assert_eq!(
+        rules(&[
+            link,
+            "set name=pkg.fmri value=pkg:/b@1\nlink path=usr/bin/sh target=ksh93\n"
+        ]),
+        vec![Rule::LinkTarget]
+    );
+
+    let mediated =
+        "set name=pkg.fmri value=pkg:/a@1\nlink path=usr/bin/sh target=bash mediator=sh mediator-implementation=bash\n";
+    assert!(rules(&[
+        mediated,
+        "set name=pkg.fmri value=pkg:/b@1\nlink path=usr/bin/sh target=ksh93 mediator=sh mediator-implementation=ksh93\n",
+    ])
+    .is_empty());
+    assert_eq!(rules(&[mediated, link]), vec![Rule::Mediator]);
+}
+
+#[test]
+fn conflict_variants() {
+    let manifests = [
+        "set name=pkg.fmri value=pkg:/a@1\n\
+         file path=usr/bin/a variant.arch=i386\n\
+         file path=usr/bin/a variant.arch=sparc\n",
+        "set name=pkg.fmri value=pkg:/b@1\n\
+         dir path=usr/bin/a variant.arch=sparc variant.debug.osnet=true\n",
+    ];
+    assert!(rules(&manifests[..1]).is_empty());
+
+    let conflicts = checker(&manifests).check();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(
+        conflicts[0].to_string(),
+        "usr/bin/a: delivered as different actions (dir, file) by pkg:/a@1, pkg:/b@1"
+    );
+}
@@ -0,0 +1,223 @@
+use crate::{
+    fmri_list::{format::ListFormat, index::FMRIIndex},
+    publisher::Publisher,
+    FMRIList, FMRI,
+};
+
+fn list(fmris: &[&str]) -> FMRIList {
+    FMRIList::from(
+        fmris
+            .iter()
+            .map(|fmri| FMRI::parse_raw(fmri).unwrap())
+            .collect::<Vec<FMRI>>(),
+    )
+}
+
+#[test]
+fn diff() {
+    let old = list(&[
+        "pkg://oi/shell/bash@5.1-2023.0.0.1:20230101T000000Z",
+        "pkg://oi/library/zlib@1.2.13-2023.0.0.0",
+        "pkg://oi/editor/vim@9.0-2023.0.0.2",
+        "pkg://oi/web/curl@8.1-2023.0.0.0:20230101T000000Z",
+        "pkg://oi/system/library@0.5.11-2023.0.0.1",
+    ]);
+    let new = list(&[
+        "pkg://oi/shell/bash@5.2-2023.0.0.0",
+        "pkg://oi/editor/vim@9.0-2023.0.0.1",
+        "pkg://oi/web/curl@8.1-2023.0.0.0:20230201T000000Z",
+        "pkg://oi/system/library@0.5.11-2023.0.0.1",
+        "pkg://oi/developer/gcc-13@13.1",
+    ]);
+
+    let diff = old.diff(&new);
+    assert_eq!(
+        diff.get_added_ref().keys().collect::<Vec<&String>>(),
+        vec!["developer/gcc-13"]
+    );
+    assert_eq!(
+        diff.get_removed_ref().keys().collect::<Vec<&String>>(),
+        vec!["library/zlib"]
+    );
+    assert_eq!(
+        diff.get_upgraded_ref()["shell/bash"].get_new_ref(),
+        &FMRI::parse_raw("pkg://oi/shell/bash@5.2-2023.0.0.0").unwrap()
+    );
+    assert!(diff.get_downgraded_ref().contains_key("editor/vim"));
+    assert!(diff.get_republished_ref().contains_key("web/curl"));
+    assert!(!diff.get_republished_ref().contains_key("system/library"));
+
+    assert_eq!(
+        format!("{}", diff),
+        "added pkg://oi/developer/gcc-13@13.1\n\
+         removed pkg://oi/library/zlib@1.2.13-2023.0.0.0\n\
+         upgraded shell/bash: @5.1-2023.0.0.1:20230101T000000Z -> @5.2-2023.0.0.0\n\
+         downgraded editor/vim: @9.0-2023.0.0.2 -> @9.0-2023.0.0.1\n\
+         republished web/curl: @8.1-2023.0.0.0:20230101T000000Z -> @8.1-2023.0.0.0:20230201T000000Z\n"
+    );
+}
+
+#[test]
+fn diff_duplicates_and_empty() {
+    let old = list(&["a@1", "a@3", "a@2"]);
+    let new = list(&["a@3"]);
+    assert!(old.diff(&new).is_empty());
+    assert!(FMRIList::new().diff(&FMRIList::new()).is_empty());
+    assert_eq!(FMRIList::new().diff(&new).get_added_ref().len(), 1);
+}
+
+#[test]
+fn index() {
+    let list = list(&[
+        "pkg://oi/shell/bash@5.2-2023.0.0.0:20230101T000000Z",
+        "pkg://oi/shell/bash@5.1",
+        "pkg://other/shell/bash@5.2-2023.0.0.0:20230201T000000Z",
+        "pkg://oi/library/zlib@1.3",
+    ]);
+    let index = FMRIIndex::from(&list);
+
+    assert_eq!(index.len(), 4);
+    assert_eq!(
+        index.get("shell/bash").unwrap(),
+        &FMRI::parse_raw("pkg://other/shell/bash@5.2-2023.0.0.0:20230201T000000Z").unwrap()
+    );
+    assert_eq!(
+        index
+            .get_from_publisher("shell/bash", &Publisher::new("oi".to_owned()).unwrap())
+            .unwrap(),
+        &FMRI::parse_raw("pkg://oi/shell/bash@5.2-2023.0.0.0:20230101T000000Z").unwrap()
+    );
+    assert_eq!(
+        index
+            .versions_of("shell/bash")
+            .iter()
+            .map(|fmri| fmri.get_version_as_string().unwrap())
+            .collect::<Vec<String>>(),
+        vec![
+            "@5.1",
+            "@5.2-2023.0.0.0:20230101T000000Z",
+            "@5.2-2023.0.0.0:20230201T000000Z"
+        ]
+    );
+    assert!(index.versions_of("editor/vim").is_empty());
+    assert_eq!(
+        format!("{}", list.latest()),
+        "pkg://oi/library/zlib@1.3, pkg://other/shell/bash@5.2-2023.0.0.0:20230201T000000Z"
+    );
+}
+
+#[test]
+fn dedup_and_retain() {
+    let mut fmris = list(&[
+        "pkg://oi/a@1",
+        "pkg://other/b@2",
+        "pkg://oi/a@3",
+        "pkg://oi/a@2",
+    ]);
+    fmris.dedup_keep_newest();
+    assert_eq!(fmris, list(&["pkg://other/b@2", "pkg://oi/a@3"]));
+
+    fmris.retain_publisher(&Publisher::new("oi".to_owned()).unwrap());
+    assert_eq!(fmris, list(&["pkg://oi/a@3"]));
+
+    fmris.extend(list(&["c@1"]));
+    assert_eq!(fmris.into_iter().count(), 2);
+}
+
+#[test]
+fn display_empty() {
+    assert_eq!(format!("{}", FMRIList::new()), "");
+    assert_eq!(format!("{:?}", FMRIList::new()), "");
+}
+
+#[test]
+fn formats_round_trip() {
+    let fmris = list(&[
+        "pkg://openindiana.org/shell/bash@5.2,5.11-2023.0.0.0:20230101T000000Z",
+        "pkg:/library/zlib@1.3",
+        "editor/vim",
+    ]);
+
+    for format in [
+        ListFormat::Inline,
+        ListFormat::Lines,
+        ListFormat::Numbered,
+        ListFormat::PkgList,
+        ListFormat::PkgContents,
+        ListFormat::PkgrepoTsv,
+        #[cfg(feature = "json")]
+        ListFormat::Json,
+    ] {
+        let text = fmris.display_as(format).to_string();
+        assert_eq!(
+            FMRIList::parse_formatted(&text, format).unwrap(),
+            fmris,
+            "{:?}",
+            format
+        );
+    }
+}
+
+#[test]
+fn parse_formatted() {
+    let pkg_list = "\
+pkg://openindiana.org/archiver/gnu-tar@1.35-2023.0.0.0:20230815T091512Z      i--
+pkg://openindiana.org/shell/bash@5.2.15-2023.0.0.1:20230602T192843Z         i--
+";
+    assert_eq!(
+        FMRIList::parse_formatted(pkg_list, ListFormat::PkgList)
+            .unwrap()
+            .len(),
+        2
+    );
+
+    // header after leading blank lines
+    assert_eq!(
+        FMRIList::parse_formatted(
+            "\n\nFMRI    IFO\nshell/bash@5.2    i--\n",
+            ListFormat::PkgList
+        )
+        .unwrap(),
+        list(&["shell/bash@5.2"])
+    );
+
+    let pkg_contents = "\
+PKG.NAME      PKG.FMRI
+shell/bash    pkg://openindiana.org/shell/bash@5.2.15-2023.0.0.1:20230602T192843Z
+";
+    assert_eq!(
+        FMRIList::parse_formatted(pkg_contents, ListFormat::PkgContents).unwrap(),
+        list(&["pkg://openindiana.org/shell/bash@5.2.15-2023.0.0.1:20230602T192843Z"])
+    );
+
+    let pkgrepo_tsv =
+        "openindiana.org\tshell/bash\t\t5.2.15\tpkg://openindiana.org/shell/bash@5.2.15\n";
+    assert_eq!(
+        FMRIList::parse_formatted(pkgrepo_tsv, ListFormat::PkgrepoTsv).unwrap(),
+        list(&["pkg://openindiana.org/shell/bash@5.2.15"])
+    );
+
+    assert_eq!(
+        FMRIList::parse_formatted("a@1\n\n# comment\nb@1.x\n", ListFormat::Lines).unwrap_err(),
+        "line 4: invalid component version in \"@1.x\": invalid character 'x' at index 3"
+    );
+    assert!(
+        FMRIList::parse_formatted("NAME (PUBLISHER) VERSION IFO\n", ListFormat::PkgList)
+            .unwrap_err()
+            .starts_with("line 1:")
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn parse_json() {
+    let json = r#"[{"name": "shell/bash", "fmri": "pkg://openindiana.org/shell/bash@5.2.15"}]"#;
+    assert_eq!(
+        FMRIList::parse_formatted(json, ListFormat::Json).unwrap(),
+        list(&["pkg://openindiana.org/shell/bash@5.2.15"])
+    );
+    assert_eq!(
+        FMRIList::parse_formatted(r#"["a@1", 2]"#, ListFormat::Json).unwrap_err(),
+        "line 2: entry must be string or object with \"fmri\" string"
+    );
+}
@@ -1,4 +1,4 @@
-use crate::FMRI;
+use crate::{ParseOptions, FMRI};
 
 #[test]
 fn compare() {
@@ -27,3 +27,113 @@ fn compare() {
             < FMRI::parse_raw(&"test@2".to_owned()).unwrap().version
     );
 }
+
+#[test]
+fn matches_pattern() {
+    let fmri =
+        FMRI::parse_raw("pkg://openindiana.org/system/library/math@0.5.11-2023.0.0.1").unwrap();
+    assert!(fmri.matches_pattern("math"));
+    assert!(fmri.matches_pattern("library/math"));
+    assert!(fmri.matches_pattern("*math"));
+    assert!(fmri.matches_pattern("pkg:/system/library/*"));
+    assert!(fmri.matches_pattern("pkg://*/system/library/math"));
+    assert!(fmri.matches_pattern("math@0.5.11-*"));
+    assert!(!fmri.matches_pattern("ath"));
+    assert!(!fmri.matches_pattern("/math"));
+    assert!(!fmri.matches_pattern("pkg://solaris/system/library/math"));
+    assert!(!fmri.matches_pattern("math@1*"));
+    assert!(!FMRI::parse_raw("math")
+        .unwrap()
+        .matches_pattern("pkg://openindiana.org/math"));
+}
+
+#[test]
+fn parse_strict() {
+    let strict = ParseOptions::strict();
+
+    let fmri = FMRI::parse_with_options(
+        "pkg://openindiana.org/system/library@0.5.11,5.11-2023.0.0.1:20230101T000000Z",
+        strict,
+    )
+    .unwrap();
+    assert_eq!(
+        fmri,
+        FMRI::parse_raw(
+            "pkg://openindiana.org/system/library@0.5.11,5.11-2023.0.0.1:20230101T000000Z"
+        )
+        .unwrap()
+    );
+    assert_eq!(
+        FMRI::parse_with_options("pkg:/library/c++/libstdc++@13", strict)
+            .unwrap()
+            .canonicalize(),
+        "pkg:/library/c++/libstdc++@13"
+    );
+    assert_eq!(
+        FMRI::parse_with_options("system/library", strict)
+            .unwrap()
+            .canonicalize(),
+        "pkg:/system/library"
+    );
+
+    assert_eq!(
+        FMRI::parse_with_options("pkg:/system//library", strict).unwrap_err(),
+        "invalid package name: empty name at index 12"
+    );
+    assert_eq!(
+        FMRI::parse_with_options("pkg://open_indiana/system", strict).unwrap_err(),
+        "invalid publisher: invalid character '_' at index 10"
+    );
+    assert_eq!(
+        FMRI::parse_with_options("test@1.2abc", strict).unwrap_err(),
+        "invalid component version in \"@1.2abc\": invalid character 'a' at index 4"
+    );
+    assert!(FMRI::parse_with_options("fmri=test@1", strict).is_err());
+    assert!(FMRI::parse_with_options("pkg:///test", strict).is_err());
+    assert!(FMRI::parse_with_options("pkg://publisher", strict).is_err());
+    assert!(FMRI::parse_with_options("", strict).is_err());
+    assert!(FMRI::parse_with_options("test@1-2,3", strict).is_err());
+    assert!(FMRI::parse_with_options("test@1:20230101", strict).is_err());
+    assert!(FMRI::parse_with_options("test@1@2", strict).is_err());
+
+    assert_eq!(
+        FMRI::parse_with_options("fmri=pkg:/test/@1", ParseOptions::lenient())
+            .unwrap()
+            .canonicalize(),
+        "pkg:/test@1"
+    );
+}
+
+#[test]
+fn canonicalize() {
+    for (raw_fmri, canonical) in [
+        (
+            "fmri=pkg://openindiana.org//system//library/@0.5.11,5.11-2023.0.0.1:20230101T000000Z",
+            "pkg://openindiana.org/system/library@0.5.11,5.11-2023.0.0.1:20230101T000000Z",
+        ),
+        ("pkg:/a//b///c@1.0", "pkg:/a/b/c@1.0"),
+        ("/system/library/", "pkg:/system/library"),
+        ("shell/bash", "pkg:/shell/bash"),
+    ] {
+        let fmri = FMRI::parse_raw(raw_fmri).unwrap();
+        assert_eq!(fmri.canonicalize(), canonical);
+
+        let strict = FMRI::parse_with_options(canonical, ParseOptions::strict()).unwrap();
+        assert_eq!(strict.canonicalize(), canonical);
+        assert_eq!(strict.to_string(), canonical);
+    }
+}
+
+#[test]
+fn parse_raw_invalid() {
+    for invalid in [
+        "pkg://openindiana.org",
+        "foo pkg://x/y",
+        "/",
+        "pkg:/",
+        "pkg://openindiana.org/",
+        "@1",
+    ] {
+        assert!(FMRI::parse_raw(invalid).is_err(), "{}", invalid);
+    }
+}
@@ -0,0 +1,181 @@
+#[cfg(feature = "proptest")]
+use proptest::{option, prelude::*, sample::select};
+
+use crate::{
+    fmri_list::format::ListFormat, fuzz::MalformedFMRI, version::Version, FMRIList, ParseOptions,
+    FMRI,
+};
+#[cfg(feature = "proptest")]
+use crate::{fuzz::strategy, version::segment::Segment, Publisher};
+
+/// Checks that [`FMRI`] survives display and parsing, lenient and strict
+fn assert_fmri_round_trip(fmri: &FMRI) {
+    let raw_fmri = fmri.to_string();
+    assert_eq!(&FMRI::parse_raw(&raw_fmri).unwrap(), fmri, "{}", raw_fmri);
+    assert_eq!(
+        &FMRI::parse_with_options(&raw_fmri, ParseOptions::strict()).unwrap(),
+        fmri,
+        "{}",
+        raw_fmri
+    );
+}
+
+fn assert_malformed(malformed: &MalformedFMRI) {
+    assert!(
+        FMRI::parse_with_options(malformed.get_ref(), ParseOptions::strict()).is_err(),
+        "{}",
+        malformed
+    );
+}
+
+/// Checks that [`Ord`] is consistent with [`Eq`], antisymmetric and transitive
+fn assert_total_order<T: Ord + core::fmt::Debug>(a: &T, b: &T, c: &T) {
+    assert_eq!(a.cmp(b) == core::cmp::Ordering::Equal, a == b);
+    assert_eq!(a.cmp(b), b.cmp(a).reverse(), "{:?} and {:?}", a, b);
+    if a <= b && b <= c {
+        assert!(a <= c, "{:?} <= {:?} <= {:?}", a, b, c);
+    }
+}
+
+#[cfg(feature = "proptest")]
+/// Versions made of a few parts, so that they often share some of them
+fn similar_version() -> impl Strategy<Value = Version> {
+    (
+        select(vec!["1", "1.2", "1.2.0", "2"]),
+        option::of(select(vec!["5.11", "5.12"])),
+        option::of(select(vec!["0", "1", "2023.0.0.1"])),
+        option::of(select(vec!["20230101T000000Z", "20230201T000000Z"])),
+    )
+        .prop_map(|(component, build, branch, timestamp)| {
+            let mut version = component.to_owned();
+            for (separator, part) in [(',', build), ('-', branch), (':', timestamp)] {
+                if let Some(part) = part {
+                    version.push(separator);
+                    version.push_str(part);
+                }
+            }
+            Version::new(version).unwrap()
+        })
+}
+
+#[cfg(feature = "proptest")]
+fn similar_fmri() -> impl Strategy<Value = FMRI> {
+    (
+        option::of(select(vec!["openindiana.org", "solaris"])),
+        select(vec!["shell/bash", "shell/zsh"]),
+        option::of(similar_version()),
+    )
+        .prop_map(|(publisher, package_name, version)| {
+            let mut fmri = FMRI::new_from_package_name(package_name.to_owned()).unwrap();
+            if let Some(publisher) = publisher {
+                fmri.change_publisher(Publisher::new(publisher.to_owned()).unwrap());
+            }
+            if let Some(version) = version {
+                fmri.change_version(version);
+            }
+            fmri
+        })
+}
+
+#[cfg(feature = "proptest")]
+proptest! {
+    #[test]
+    fn fmri_round_trip(fmri in strategy::fmri()) {
+        assert_fmri_round_trip(&fmri);
+    }
+
+    #[test]
+    fn version_round_trip(version in strategy::version()) {
+        let raw_version = version.to_string();
+        prop_assert_eq!(&Version::new(raw_version.clone()).unwrap(), &version);
+        prop_assert_eq!(Version::parse_with_options(&raw_version, ParseOptions::strict()).unwrap(), version);
+    }
+
+    #[test]
+    fn segment_round_trip(segment in strategy::segment()) {
+        prop_assert_eq!(Segment::try_from(segment.to_string()).unwrap(), segment);
+    }
+
+    #[test]
+    fn publisher_round_trip(publisher in strategy::publisher()) {
+        let parsed = Publisher::parse_publisher_from_raw_fmri(publisher.to_string()).unwrap();
+        prop_assert_eq!(parsed, Some(publisher));
+    }
+
+    #[test]
+    fn fmri_list_round_trip(list in strategy::fmri_list()) {
+        for format in [ListFormat::Inline, ListFormat::Lines, #[cfg(feature = "json")] ListFormat::Json] {
+            let formatted = list.display_as(format).to_string();
+            prop_assert_eq!(&FMRIList::parse_formatted(&formatted, format).unwrap(), &list);
+        }
+    }
+
+    #[test]
+    fn malformed_fmri_is_rejected(malformed in strategy::malformed_fmri()) {
+        assert_malformed(&malformed);
+    }
+
+    #[test]
+    fn version_total_order(a in strategy::version(), b in strategy::version(), c in strategy::version()) {
+        assert_total_order(&a, &b, &c);
+    }
+
+    #[test]
+    fn similar_version_total_order(a in similar_version(), b in similar_version(), c in similar_version()) {
+        assert_total_order(&a, &b, &c);
+    }
+
+    #[test]
+    fn fmri_total_order(a in similar_fmri(), b in similar_fmri(), c in similar_fmri()) {
+        assert_total_order(&a, &b, &c);
+    }
+
+    #[test]
+    fn segment_total_order(a in strategy::segment(), b in strategy::segment(), c in strategy::segment()) {
+        assert_total_order(&a, &b, &c);
+    }
+}
+
+/// Bytes of xorshift generator, so that the test is deterministic
+#[cfg(feature = "arbitrary")]
+fn pseudo_random_bytes(seed: u64, len: usize) -> Vec<u8> {
+    let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect()
+}
+
+#[cfg(feature = "arbitrary")]
+#[test]
+fn arbitrary_values_are_valid() {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    for seed in 0..500 {
+        let bytes = pseudo_random_bytes(seed, 1024);
+
+        let mut u = Unstructured::new(&bytes);
+        let list = FMRIList::arbitrary(&mut u).unwrap();
+        list.iter().for_each(assert_fmri_round_trip);
+        assert_eq!(
+            FMRIList::parse_formatted(&list.to_string(), ListFormat::Inline).unwrap(),
+            list
+        );
+
+        let mut u = Unstructured::new(&bytes);
+        let (a, b, c) = <(Version, Version, Version)>::arbitrary(&mut u).unwrap();
+        assert_total_order(&a, &b, &c);
+
+        let mut u = Unstructured::new(&bytes);
+        assert_malformed(&MalformedFMRI::arbitrary(&mut u).unwrap());
+    }
+
+    // running out of data still gives valid values
+    let mut u = Unstructured::new(&[]);
+    assert_fmri_round_trip(&FMRI::arbitrary(&mut u).unwrap());
+    assert_malformed(&MalformedFMRI::arbitrary(&mut u).unwrap());
+}
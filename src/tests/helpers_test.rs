@@ -1,4 +1,4 @@
-use crate::helpers::check_character_collision;
+use crate::helpers::{check_character_collision, check_timestamp, glob_match};
 
 #[test]
 fn _check_character_collision() {
@@ -13,3 +13,23 @@ fn _check_character_collision() {
 fn _check_character_collision_panic1() {
     check_character_collision(&"test@".to_owned()).unwrap();
 }
+
+#[test]
+fn _glob_match() {
+    assert!(glob_match("system/library", "system/library"));
+    assert!(glob_match("system/*", "system/library"));
+    assert!(glob_match("*/lib?ary", "system/library"));
+    assert!(glob_match("*", ""));
+    assert!(glob_match("a*b*c", "aXXbYYc"));
+    assert!(!glob_match("system/*", "library"));
+    assert!(!glob_match("lib?", "lib"));
+    assert!(!glob_match("a*b", "aXXbc"));
+}
+
+#[test]
+fn _check_timestamp() {
+    check_timestamp("20171212T185746Z").unwrap();
+    assert!(check_timestamp("20171212T185746").is_err());
+    assert!(check_timestamp("2017121aT185746Z").is_err());
+    assert!(check_timestamp("").is_err());
+}
@@ -0,0 +1,149 @@
+use std::path::Path;
+
+use crate::fmri_list::format::ListFormat;
+use crate::history::{History, HistoryEntry};
+use crate::{FMRIList, FMRI};
+
+fn fmri(raw_fmri: &str) -> FMRI {
+    FMRI::parse_raw(raw_fmri).unwrap()
+}
+
+#[test]
+fn history_entry_parse() {
+    let history = History::read_image(
+        &Path::new(env!("CARGO_MANIFEST_DIR")).join("src/tests/fixtures/history"),
+    )
+    .unwrap();
+    assert_eq!(history.len(), 4);
+
+    let entry = &history.get_ref()[0];
+    assert_eq!(entry.get_client_name_ref(), "pkg");
+    assert_eq!(entry.get_client_version_ref().as_deref(), Some("e2c3bca5"));
+    assert_eq!(
+        entry.get_client_args_ref(),
+        &vec!["/usr/bin/pkg", "install", "bash", "vim"]
+    );
+    assert_eq!(entry.get_operation_ref(), "install");
+    assert_eq!(
+        entry.get_start_time_ref().as_deref(),
+        Some("20230801T100000Z")
+    );
+    assert_eq!(
+        entry.get_end_time_ref().as_deref(),
+        Some("20230801T100112Z")
+    );
+    assert_eq!(entry.get_userid(), Some(0));
+    assert_eq!(entry.get_username_ref().as_deref(), Some("root"));
+    assert_eq!(entry.get_be_ref().as_deref(), Some("openindiana"));
+    assert_eq!(entry.get_new_be_ref(), &None);
+    assert!(entry.get_start_state_ref().is_empty());
+    assert_eq!(entry.get_end_state_ref().len(), 2);
+    assert_eq!(entry.installed().len(), 2);
+    assert!(entry.removed().is_empty());
+    assert!(entry.is_succeeded());
+
+    let failed = &history.get_ref()[2];
+    assert!(!failed.is_succeeded());
+    assert_eq!(failed.get_result_ref(), &vec!["Failed", "Constrained"]);
+    assert_eq!(
+        failed.get_errors_ref(),
+        &vec!["shell/bash is required by system/core-os"]
+    );
+}
+
+#[test]
+fn history_entry_invalid() {
+    for xml in [
+        "<history>",
+        "<operations/>",
+        "<history><operation name=\"install\"/></history>",
+        "<history><client name=\"pkg\"/></history>",
+        "<history><client name=\"pkg\"/><operation/></history>",
+        "<history><client name=\"pkg\"/><operation name=\"install\" userid=\"root\"/></history>",
+        "<history><client name=\"pkg\"/><operation name=\"install\">\
+         <end_state>pkg:/a@@1</end_state></operation></history>",
+        "<history><client name=\"pkg\"/><operation name=\"install\">\
+         <end_state>None -> pkg:/a@@1</end_state></operation></history>",
+        "<history><client name=\"pkg\"/><operation name=\"install\">\
+         <start_state>Solver: [ State: Succeeded]\nPackage version changes:\n\
+         None -> pkg://openindiana.org</start_state></operation></history>",
+    ] {
+        assert!(HistoryEntry::parse(xml).is_err(), "{}", xml);
+    }
+    assert!(History::read_dir(Path::new("/nonexistent")).is_err());
+}
+
+#[test]
+fn history_entry_plan() {
+    let parse = |operation: &str| {
+        HistoryEntry::parse(&format!(
+            "<history><client name=\"pkg\"/><operation name=\"update\">{}</operation></history>",
+            operation
+        ))
+        .unwrap()
+    };
+
+    // failed execution records only full plan
+    let entry = parse(
+        "<start_state><![CDATA[Solver: [ Variables: 4 Clauses: 8 Iterations: 2 State: Succeeded]
+Timings: [phase 1:  0.012]
+Package version changes:
+pkg://openindiana.org/shell/bash@5.2.15,5.11-2023.0.0.1 -> pkg://openindiana.org/shell/bash@5.2.21,5.11-2023.0.0.0
+None -> pkg://openindiana.org/shell/zsh@5.9
+
+Actuators:
+None
+]]></start_state>",
+    );
+    assert_eq!(
+        entry.get_start_state_ref(),
+        &FMRIList::parse_formatted(
+            "pkg://openindiana.org/shell/bash@5.2.15,5.11-2023.0.0.1",
+            ListFormat::Inline
+        )
+        .unwrap()
+    );
+    assert_eq!(entry.get_end_state_ref().len(), 2);
+    assert_eq!(entry.installed().len(), 2);
+    assert_eq!(entry.removed().len(), 1);
+
+    for plan in [
+        "<start_state>UNEVALUATED:\n</start_state>",
+        "<start_state>Solver: [ State: Failed]\n</start_state>",
+        "<end_state></end_state>",
+    ] {
+        let entry = parse(plan);
+        assert!(entry.get_start_state_ref().is_empty(), "{}", plan);
+        assert!(entry.get_end_state_ref().is_empty(), "{}", plan);
+    }
+}
+
+#[test]
+fn history_audit() {
+    let history = History::read_image(
+        &Path::new(env!("CARGO_MANIFEST_DIR")).join("src/tests/fixtures/history"),
+    )
+    .unwrap();
+
+    let bash = history.installations_of(&fmri("shell/bash"));
+    assert_eq!(bash.len(), 2);
+    assert_eq!(bash[1].get_operation_ref(), "update");
+    assert!(bash[1].diff().get_upgraded_ref().contains_key("shell/bash"));
+
+    let installed = history.installations_of(&fmri("shell/bash@5.2.21"));
+    assert_eq!(installed.len(), 1);
+    assert_eq!(
+        installed[0].get_new_be_ref().as_deref(),
+        Some("openindiana-1")
+    );
+    assert!(history.installations_of(&fmri("shell/bash@5.1")).is_empty());
+
+    // failed uninstall isn't removal
+    let removed = history.removals_of(&fmri("shell/bash"));
+    assert_eq!(removed.len(), 1);
+    assert_eq!(
+        removed[0].get_end_time_ref().as_deref(),
+        Some("20230905T101830Z")
+    );
+    assert_eq!(history.removals_of(&fmri("editor/vim@9")).len(), 1);
+}
@@ -0,0 +1,105 @@
+use crate::incorporation::Incorporations;
+use crate::manifest::Manifest;
+use crate::{FMRIList, FMRI};
+
+fn fmri(raw_fmri: &str) -> FMRI {
+    FMRI::parse_raw(raw_fmri).unwrap()
+}
+
+#[test]
+fn incorporations_add() {
+    let mut incorporations = Incorporations::new();
+    for manifest in [
+        "set name=pkg.fmri value=pkg://oi/incorporation/a@1.0\n\
+         depend fmri=library/a@1.2-2023.0 type=incorporate\n\
+         depend fmri=library/b@2 type=incorporate\n\
+         depend fmri=library/c@3.0 type=require\n",
+        "set name=pkg.fmri value=pkg://oi/incorporation/b@1.0\n\
+         depend fmri=library/b@2.1 type=incorporate\n",
+    ] {
+        incorporations
+            .add_manifest(&Manifest::parse(manifest).unwrap())
+            .unwrap();
+    }
+    assert_eq!(incorporations.len(), 3);
+    assert_eq!(incorporations.get("library/b").unwrap().len(), 2);
+    assert_eq!(incorporations.get("library/c"), None);
+
+    let mut incorporations = Incorporations::new();
+    assert!(incorporations
+        .add(fmri("library/a"), fmri("incorporation/a@1"))
+        .is_err());
+    assert!(incorporations
+        .add_manifest(&Manifest::parse("depend fmri=library/a@1 type=incorporate").unwrap())
+        .is_err());
+}
+
+#[test]
+fn incorporations_check() {
+    let mut incorporations = Incorporations::new();
+    for (constraint, incorporation) in [
+        ("library/a@1.2-2023.0", "pkg://oi/incorporation/a@1.0"),
+        ("library/b@2", "pkg://oi/incorporation/a@1.0"),
+        ("library/b@2.1", "pkg://oi/incorporation/b@1.0"),
+    ] {
+        incorporations
+            .add(fmri(constraint), fmri(incorporation))
+            .unwrap();
+    }
+
+    let list: FMRIList = [
+        "library/a@1.2-2023.0.0.5",
+        "library/b@2.1.3",
+        "library/c@1.0",
+        "library/d@1.0",
+        "library/a",
+    ]
+    .iter()
+    .map(|raw_fmri| fmri(raw_fmri))
+    .collect();
+    assert!(incorporations.check(&list).is_empty());
+
+    let list: FMRIList = ["library/a@1.3-2023.0.0.5", "library/b@2.2"]
+        .iter()
+        .map(|raw_fmri| fmri(raw_fmri))
+        .collect();
+    let violations = incorporations.check(&list);
+    assert_eq!(violations.len(), 2);
+    assert_eq!(
+        violations[0].get_fmri_ref(),
+        &fmri("library/a@1.3-2023.0.0.5")
+    );
+    assert_eq!(
+        violations[0].get_constraint_ref(),
+        &fmri("library/a@1.2-2023.0")
+    );
+    assert_eq!(
+        violations[1].to_string(),
+        "pkg:/library/b@2.2 doesn't match pkg:/library/b@2.1 incorporated by pkg://oi/incorporation/b@1.0"
+    );
+}
+
+#[test]
+fn incorporations_generate() {
+    let list: FMRIList = [
+        "pkg://oi/library/b@2.1:20230101T000000Z",
+        "library/a@1.2-2023.0.0.5",
+        "library/a@1.1",
+        "library/c",
+    ]
+    .iter()
+    .map(|raw_fmri| fmri(raw_fmri))
+    .collect();
+
+    let manifest = Incorporations::generate(&fmri("pkg://oi/incorporation/new@1.0"), &list);
+    assert_eq!(
+        manifest.to_string(),
+        "set name=pkg.fmri value=pkg://oi/incorporation/new@1.0\n\
+         depend fmri=pkg:/library/a@1.2-2023.0.0.5 type=incorporate\n\
+         depend fmri=pkg:/library/b@2.1 type=incorporate\n"
+    );
+
+    let mut incorporations = Incorporations::new();
+    incorporations.add_manifest(&manifest).unwrap();
+    assert!(incorporations.check(&list.latest()).is_empty());
+}
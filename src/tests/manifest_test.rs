@@ -0,0 +1,91 @@
+use crate::manifest::{action::Action, Manifest};
+use crate::FMRI;
+
+#[test]
+fn parse_action() {
+    let action = Action::parse(
+        "file 1e5a3c7f path=usr/bin/bash owner=root group=bin mode=0555 \
+         pkg.description='It''s \"quoted\"' facet.doc=true facet.doc=all empty=\"\"",
+    );
+    assert!(action.is_err());
+
+    let action = Action::parse(
+        "file 1e5a3c7f path=usr/bin/bash mode=0555 note='say \"hi\"' \
+         text=\"a \\\"b\\\" 'c'\" empty=\"\" variant.arch=i386 variant.arch=sparc",
+    )
+    .unwrap();
+    assert_eq!(action.get_action_type_ref(), "file");
+    assert_eq!(action.get_payload_ref().unwrap(), "1e5a3c7f");
+    assert_eq!(action.get_key_attribute().unwrap(), "usr/bin/bash");
+    assert_eq!(action.get_attribute("note").unwrap(), "say \"hi\"");
+    assert_eq!(action.get_attribute("text").unwrap(), "a \"b\" 'c'");
+    assert_eq!(action.get_attribute("empty").unwrap(), "");
+    assert_eq!(
+        action.get_attribute_values("variant.arch"),
+        vec!["i386", "sparc"]
+    );
+
+    assert_eq!(Action::parse(&action.to_string()).unwrap(), action);
+    assert_eq!(
+        action.to_string(),
+        "file 1e5a3c7f path=usr/bin/bash mode=0555 note='say \"hi\"' \
+         text=\"a \\\"b\\\" 'c'\" empty=\"\" variant.arch=i386 variant.arch=sparc"
+    );
+
+    assert!(Action::parse("=value").is_err());
+    assert!(Action::parse("set name=\"unterminated").is_err());
+    assert!(Action::parse("file hash path").is_err());
+    assert!(Action::parse("set =value").is_err());
+}
+
+#[test]
+fn action_attributes() {
+    let mut action = Action::parse("dir path=opt mode=0755 facet.a=1 facet.a=2").unwrap();
+    action.set_attribute("facet.a".to_owned(), "3".to_owned());
+    action.add_attribute("owner".to_owned(), "root".to_owned());
+    action.remove_attribute("mode");
+    assert_eq!(action.to_string(), "dir path=opt facet.a=3 owner=root");
+    assert!(action.get_payload_ref().is_none());
+
+    let action = Action::parse("license license=MIT hash=abc").unwrap();
+    assert_eq!(action.get_payload_ref().unwrap(), "abc");
+    assert_eq!(action.get_key_attribute().unwrap(), "MIT");
+}
+
+#[test]
+fn parse_manifest() {
+    let manifest = Manifest::parse(
+        "\
+set name=pkg.fmri value=pkg://openindiana.org/shell/bash@5.2-2023.0.0.0
+set name=pkg.renamed value=True
+
+# comment
+depend fmri=pkg:/library/ncurses \\
+    type=require
+dir path=usr owner=root group=sys mode=0755
+",
+    )
+    .unwrap();
+
+    assert_eq!(manifest.len(), 4);
+    assert_eq!(
+        manifest.get_fmri().unwrap().unwrap(),
+        FMRI::parse_raw("pkg://openindiana.org/shell/bash@5.2-2023.0.0.0").unwrap()
+    );
+    assert!(manifest.is_set_true("pkg.renamed"));
+    assert!(!manifest.is_set_true("pkg.obsolete"));
+    assert_eq!(
+        manifest
+            .actions_of_type("depend")
+            .next()
+            .unwrap()
+            .to_string(),
+        "depend fmri=pkg:/library/ncurses type=require"
+    );
+    assert_eq!(Manifest::parse(&manifest.to_string()).unwrap(), manifest);
+
+    assert_eq!(
+        Manifest::parse("set name=a value=b\n\nset name=\"c\n").unwrap_err(),
+        "line 3: unterminated quoted value of \"name\" in \"set name=\"c\""
+    );
+}
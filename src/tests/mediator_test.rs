@@ -0,0 +1,155 @@
+use crate::manifest::{action::Action, Manifest};
+use crate::mediator::{Mediation, Mediators, Priority};
+
+#[test]
+fn mediation_from_action() {
+    let action = Action::parse(
+        "link path=usr/bin/vi target=vim mediator=vi mediator-implementation=vim@9.0 \
+         mediator-priority=site",
+    )
+    .unwrap();
+    let (mediator, mediation) = Mediation::from_action(&action).unwrap().unwrap();
+    assert_eq!(mediator, "vi");
+    assert_eq!(mediation.get_implementation_ref().as_ref().unwrap(), "vim");
+    assert_eq!(
+        mediation
+            .get_implementation_version_ref()
+            .as_ref()
+            .unwrap()
+            .as_string(),
+        "9.0"
+    );
+    assert_eq!(mediation.get_priority(), Priority::Site);
+    assert_eq!(
+        mediation.to_string(),
+        "mediator-implementation=vim@9.0 mediator-priority=site"
+    );
+
+    let action = Action::parse("link path=usr/bin/vi target=vim").unwrap();
+    assert_eq!(Mediation::from_action(&action).unwrap(), None);
+
+    for action in [
+        "link path=a target=b mediator=vi",
+        "link path=a target=b mediator=vi mediator-version=x.1",
+        "link path=a target=b mediator=vi mediator-version=1 mediator-priority=user",
+        "link path=a target=b mediator=v/i mediator-version=1",
+        "link path=a target=b mediator=vi mediator-implementation=@1",
+    ] {
+        assert!(Mediation::from_action(&Action::parse(action).unwrap()).is_err());
+    }
+}
+
+#[test]
+fn mediators_resolve() {
+    let mut mediators = Mediators::new();
+    for manifest in [
+        "set name=pkg.fmri value=pkg://oi/runtime/python-39@3.9.18\n\
+         link path=usr/bin/python target=python3.9 mediator=python mediator-version=3.9\n\
+         link path=usr/bin/pydoc target=pydoc3.9 mediator=python mediator-version=3.9\n",
+        "set name=pkg.fmri value=pkg://oi/runtime/python-311@3.11.6\n\
+         link path=usr/bin/python target=python3.11 mediator=python mediator-version=3.11\n\
+         link path=usr/bin/pydoc target=pydoc3.11 mediator=python mediator-version=3.11\n",
+        "set name=pkg.fmri value=pkg://oi/runtime/python-27@2.7.18\n\
+         link path=usr/bin/python target=python2.7 mediator=python mediator-version=2.7 \
+         mediator-priority=vendor\n",
+        "set name=pkg.fmri value=pkg://oi/editor/vim@9.0\n\
+         link path=usr/bin/vi target=vim mediator=vi mediator-implementation=vim\n\
+         link path=usr/bin/ex target=vim\n",
+        "set name=pkg.fmri value=pkg://oi/editor/nvi@1.81\n\
+         link path=usr/bin/vi target=nvi mediator=vi mediator-implementation=nvi@1.81\n",
+    ] {
+        mediators
+            .add_manifest(&Manifest::parse(manifest).unwrap())
+            .unwrap();
+    }
+    assert_eq!(
+        mediators.mediators().collect::<Vec<&String>>(),
+        vec!["python", "vi"]
+    );
+    assert_eq!(mediators.get("python").unwrap().len(), 5);
+
+    let selected = mediators.resolve().unwrap();
+    // vendor priority overrides higher versions
+    let python = &selected["python"];
+    assert_eq!(python.get_mediation_ref().get_priority(), Priority::Vendor);
+    assert_eq!(python.get_links_ref().len(), 1);
+    assert_eq!(
+        python.get_links_ref()[0]
+            .get_fmri_ref()
+            .get_package_name_as_ref_string(),
+        "runtime/python-27"
+    );
+    // implementation with version is preferred
+    assert_eq!(selected["vi"].targets()["usr/bin/vi"], "nvi");
+}
+
+#[test]
+fn mediators_settings() {
+    let mut mediators = Mediators::new();
+    for manifest in [
+        "set name=pkg.fmri value=pkg://oi/runtime/python-39@3.9.18\n\
+         link path=usr/bin/python target=python3.9 mediator=python mediator-version=3.9\n\
+         link path=usr/bin/pydoc target=pydoc3.9 mediator=python mediator-version=3.9\n",
+        "set name=pkg.fmri value=pkg://oi/runtime/python-311@3.11.6\n\
+         link path=usr/bin/python target=python3.11 mediator=python mediator-version=3.11\n\
+         link path=usr/bin/pydoc target=pydoc3.11 mediator=python mediator-version=3.11\n",
+        "set name=pkg.fmri value=pkg://oi/editor/vim@9.0\n\
+         link path=usr/bin/vi target=vim mediator=vi mediator-implementation=vim\n",
+        "set name=pkg.fmri value=pkg://oi/editor/nvi@1.81\n\
+         link path=usr/bin/vi target=nvi mediator=vi mediator-implementation=nvi@1.81\n",
+    ] {
+        mediators
+            .add_manifest(&Manifest::parse(manifest).unwrap())
+            .unwrap();
+    }
+
+    let mut setting = Mediation::new();
+    setting.set_version(Some("3.11".try_into().unwrap()));
+    mediators.set("python".to_owned(), setting.clone());
+    assert_eq!(mediators.get_setting("python"), Some(&setting));
+    let selected = mediators.resolve().unwrap();
+    assert_eq!(
+        selected["python"].targets(),
+        [
+            ("usr/bin/pydoc".to_owned(), "pydoc3.11".to_owned()),
+            ("usr/bin/python".to_owned(), "python3.11".to_owned())
+        ]
+        .into()
+    );
+
+    let mut setting = Mediation::new();
+    setting.set_implementation("vim").unwrap();
+    mediators.set("vi".to_owned(), setting);
+    assert_eq!(
+        mediators.resolve().unwrap()["vi"].targets()["usr/bin/vi"],
+        "vim"
+    );
+
+    let mut setting = Mediation::new();
+    setting.set_version(Some("3.12".try_into().unwrap()));
+    mediators.set("python".to_owned(), setting);
+    assert!(mediators.resolve().is_err());
+
+    mediators.unset("python");
+    assert_eq!(mediators.get_setting("python"), None);
+    assert!(mediators.resolve().is_ok());
+}
+
+#[test]
+fn mediation_preference() {
+    let mediation = |action: &str| {
+        Mediation::from_action(&Action::parse(action).unwrap())
+            .unwrap()
+            .unwrap()
+            .1
+    };
+    let site = mediation("link mediator=a mediator-version=1 mediator-priority=site");
+    let vendor = mediation("link mediator=a mediator-version=2 mediator-priority=vendor");
+    let newest = mediation("link mediator=a mediator-version=3.10");
+    let older = mediation("link mediator=a mediator-version=3.9");
+    assert!(site.cmp_preference(&vendor).is_gt());
+    assert!(vendor.cmp_preference(&newest).is_gt());
+    assert!(newest.cmp_preference(&older).is_gt());
+    assert!(older.satisfies(&Mediation::new()));
+    assert!(!older.satisfies(&newest));
+}
@@ -0,0 +1,227 @@
+use std::fs;
+
+use crate::manifest::Manifest;
+use crate::mogrify::transform::{Operation, Transform};
+use crate::mogrify::Mogrify;
+
+fn mogrify(transforms: &str, manifest: &str) -> Result<String, String> {
+    let mut mogrify = Mogrify::new();
+    mogrify.load(transforms, None)?;
+    Ok(mogrify
+        .apply(&Manifest::parse(manifest).unwrap())?
+        .to_string())
+}
+
+#[test]
+fn transform_parse() {
+    let transform = Transform::parse(
+        "<transform file link path=\"usr/lib/(.*)\\.so\" -> edit path '^usr/lib/' usr/lib/64/>",
+    )
+    .unwrap();
+    assert_eq!(
+        transform.get_action_types_ref(),
+        &vec!["file".to_owned(), "link".to_owned()]
+    );
+    assert_eq!(
+        transform.get_operation_ref(),
+        &Operation::Edit {
+            attribute: "path".to_owned(),
+            pattern: "^usr/lib/".to_owned(),
+            replacement: "usr/lib/64/".to_owned(),
+        }
+    );
+    assert_eq!(
+        transform.to_string(),
+        "<transform file link path=usr/lib/(.*)\\.so -> edit path ^usr/lib/ usr/lib/64/>"
+    );
+    assert_eq!(Transform::parse(&transform.to_string()).unwrap(), transform);
+
+    assert_eq!(
+        Transform::parse("<transform -> emit set name=a value=b>")
+            .unwrap()
+            .get_operation_ref(),
+        &Operation::Emit("set name=a value=b".to_owned())
+    );
+
+    assert!(Transform::parse("<transform file -> drop").is_ok());
+    assert!(Transform::parse("<transform file drop>").is_err());
+    assert!(Transform::parse("<transform file -> rename a b>").is_err());
+    assert!(Transform::parse("<transform file -> set a>").is_err());
+    assert!(Transform::parse("<transform file -> drop now>").is_err());
+    assert!(Transform::parse("<transform file path=( -> drop>").is_err());
+    assert!(Transform::parse("<transform file -> set a \"b>").is_err());
+}
+
+#[test]
+fn transform_operations() {
+    let manifest = "file path=usr/bin/bash mode=0555\n\
+                    file path=usr/share/man/man1/bash.1 mode=0444\n\
+                    dir path=usr/bin\n";
+
+    assert_eq!(
+        mogrify("<transform file path=usr/share/man/.* -> drop>", manifest).unwrap(),
+        "file path=usr/bin/bash mode=0555\ndir path=usr/bin\n"
+    );
+    assert_eq!(
+        mogrify("<transform file -> set mode 0444>", manifest).unwrap(),
+        "file path=usr/bin/bash mode=0444\n\
+         file path=usr/share/man/man1/bash.1 mode=0444\n\
+         dir path=usr/bin\n"
+    );
+    assert_eq!(
+        mogrify(
+            "<transform dir -> add group bin>\n<transform dir -> add group sys>",
+            manifest
+        )
+        .unwrap()
+        .lines()
+        .last(),
+        Some("dir path=usr/bin group=bin group=sys")
+    );
+    assert_eq!(
+        mogrify(
+            "<transform file mode=0555 -> default owner root>\n\
+             <transform file -> default owner bin>",
+            manifest
+        )
+        .unwrap(),
+        "file path=usr/bin/bash mode=0555 owner=root\n\
+         file path=usr/share/man/man1/bash.1 mode=0444 owner=bin\n\
+         dir path=usr/bin\n"
+    );
+    assert_eq!(
+        mogrify(
+            "<transform file -> delete mode 04.*>",
+            "file path=a mode=0444 mode=0555\n"
+        )
+        .unwrap(),
+        "file path=a mode=0555\n"
+    );
+    // pattern is anchored, "usr/bin" doesn't match "usr/bin/bash"
+    assert_eq!(
+        mogrify(
+            "<transform path=usr/bin -> edit path usr/(.*) opt/\\1>",
+            manifest
+        )
+        .unwrap(),
+        "file path=usr/bin/bash mode=0555\n\
+         file path=usr/share/man/man1/bash.1 mode=0444\n\
+         dir path=opt/bin\n"
+    );
+    assert_eq!(
+        mogrify(
+            "<transform file -> edit action.hash ^ payload/>",
+            "file bash path=usr/bin/bash\n"
+        )
+        .unwrap(),
+        "file payload/bash path=usr/bin/bash\n"
+    );
+}
+
+#[test]
+fn transform_references() {
+    let manifest = "set name=pkg.fmri value=pkg:/shell/bash@5.2\n\
+                    file path=usr/bin/bash mode=0555\n";
+
+    assert_eq!(
+        mogrify(
+            "<transform file path=usr/(bin)/(.*) -> emit link path=usr/%<1>/%<2>-5 target=%<2>>",
+            manifest
+        )
+        .unwrap(),
+        "set name=pkg.fmri value=pkg:/shell/bash@5.2\n\
+         file path=usr/bin/bash mode=0555\n\
+         link path=usr/bin/bash-5 target=bash\n"
+    );
+    assert_eq!(
+        mogrify(
+            "<transform file -> set pkg.origin %{{pkg.fmri}}:%{path}:%{owner;notfound=root}>",
+            manifest
+        )
+        .unwrap(),
+        "set name=pkg.fmri value=pkg:/shell/bash@5.2\n\
+         file path=usr/bin/bash mode=0555 pkg.origin=pkg:/shell/bash@5.2:usr/bin/bash:root\n"
+    );
+    assert_eq!(
+        mogrify("<transform file -> set mode 100%>", manifest)
+            .unwrap()
+            .lines()
+            .last(),
+        Some("file path=usr/bin/bash mode=100%")
+    );
+
+    assert!(mogrify("<transform file -> set owner %{owner}>", manifest).is_err());
+    assert!(mogrify("<transform file -> set owner %{{pkg.summary}}>", manifest).is_err());
+    assert!(mogrify("<transform file -> set owner %<1>>", manifest).is_err());
+    assert!(mogrify("<transform file -> emit invalid=action>", manifest).is_err());
+}
+
+#[test]
+fn mogrify_macros() {
+    let mut mogrify = Mogrify::new();
+    mogrify.define("ARCH".to_owned(), "i386".to_owned());
+    assert_eq!(
+        mogrify.expand_macros("$(ARCH) $(MACH64) $(ARCH"),
+        "i386 $(MACH64) $(ARCH"
+    );
+
+    let manifest = mogrify
+        .process(
+            "<transform file -> set variant.arch $(ARCH)>\n\
+             # comment\n\
+             file path=usr/bin/$(ARCH)/bash \\\n\
+             \x20   mode=0555\n",
+        )
+        .unwrap();
+    assert_eq!(
+        manifest.to_string(),
+        "file path=usr/bin/i386/bash mode=0555 variant.arch=i386\n"
+    );
+
+    assert_eq!(
+        Mogrify::new().process("file path=a\n<unknown>\n"),
+        Err("line 2: unknown directive \"<unknown>\"".to_owned())
+    );
+}
+
+#[test]
+fn mogrify_include() {
+    let directory = std::env::temp_dir().join(format!("fmri-mogrify-{}", std::process::id()));
+    let includes = directory.join("includes");
+    fs::create_dir_all(&includes).unwrap();
+    fs::write(
+        includes.join("common.mog"),
+        "<transform file -> default owner root>\n<include nested.mog>\n",
+    )
+    .unwrap();
+    fs::write(
+        includes.join("nested.mog"),
+        "<transform file -> default group bin>\nset name=pkg.summary value=nested\n",
+    )
+    .unwrap();
+    fs::write(includes.join("loop.mog"), "<include loop.mog>\n").unwrap();
+    fs::write(
+        directory.join("bash.p5m"),
+        "<include common.mog>\nfile path=usr/bin/bash\n",
+    )
+    .unwrap();
+
+    let mut mogrify = Mogrify::new();
+    assert!(mogrify.load_file(&directory.join("bash.p5m")).is_err());
+
+    mogrify.add_include_dir(includes.clone());
+    let manifest = mogrify.load_file(&directory.join("bash.p5m")).unwrap();
+    assert_eq!(mogrify.get_transforms_ref().len(), 2);
+    assert_eq!(
+        mogrify.apply(&manifest).unwrap().to_string(),
+        "set name=pkg.summary value=nested\n\
+         file path=usr/bin/bash owner=root group=bin\n"
+    );
+
+    assert!(Mogrify::new()
+        .load_file(&includes.join("loop.mog"))
+        .unwrap_err()
+        .contains("includes itself"));
+
+    fs::remove_dir_all(directory).unwrap();
+}
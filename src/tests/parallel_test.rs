@@ -0,0 +1,129 @@
+use crate::{FMRIList, FMRI};
+
+#[test]
+fn par_parse_like_serial() {
+    // duplicate versions, different timestamps and publishers
+    let mut lines: Vec<String> = (0..6000)
+        .map(|line| {
+            format!(
+                "pkg://publisher-{}/package-{}@{}.{}-2023.0.0.{}:202309{:02}T000000Z",
+                line % 2,
+                line % 100,
+                line % 7,
+                line % 3,
+                line % 2,
+                1 + line % 5,
+            )
+        })
+        .collect();
+    lines.push("package-1".to_owned());
+    let list = FMRIList::par_parse(&lines).unwrap();
+    assert_eq!(
+        list,
+        lines
+            .iter()
+            .map(|line| FMRI::parse_raw(line).unwrap())
+            .collect()
+    );
+
+    lines.insert(10, "  ".to_owned());
+    lines.insert(20, "# comment".to_owned());
+    assert_eq!(FMRIList::par_parse(&lines).unwrap(), list);
+}
+
+#[test]
+fn par_parse_errors() {
+    let lines = [
+        "shell/bash@5.2",
+        "shell/bash@5.a",
+        "",
+        "pkg://openindiana.org/shell/bash@1.2,5.a",
+        "shell/bash@5.2.1",
+    ];
+    let errors = FMRIList::par_parse(&lines).unwrap_err();
+    assert_eq!(
+        errors
+            .iter()
+            .map(|(number, _)| *number)
+            .collect::<Vec<usize>>(),
+        [2, 4]
+    );
+    assert_eq!(errors[0].1, FMRI::parse_raw(lines[1]).unwrap_err());
+}
+
+#[test]
+fn par_parse_malformed() {
+    // inputs which used to panic in parsing
+    let lines = ["a@1", "pkg://foo", "foo pkg://x/y", "@1"];
+    let errors = FMRIList::par_parse(&lines).unwrap_err();
+    assert_eq!(
+        errors
+            .iter()
+            .map(|(number, _)| *number)
+            .collect::<Vec<usize>>(),
+        [2, 3, 4]
+    );
+    for (number, error) in errors {
+        assert_eq!(error, FMRI::parse_raw(lines[number - 1]).unwrap_err());
+    }
+}
+
+#[test]
+fn par_sort_like_serial() {
+    // same versions with different timestamps and publishers keep their order
+    let mut serial: FMRIList = (0..6000)
+        .map(|fmri| {
+            FMRI::parse_raw(&format!(
+                "pkg://publisher-{}/package-{}@{}.{}:202309{:02}T000000Z",
+                fmri % 2,
+                fmri % 100,
+                fmri % 7,
+                fmri % 3,
+                1 + fmri % 5,
+            ))
+            .unwrap()
+        })
+        .collect();
+    serial.add(FMRI::parse_raw("package-1").unwrap());
+    let mut parallel = serial.clone();
+    serial.sort_by_version();
+    parallel.par_sort_by_version();
+    assert_eq!(
+        format!("{:?}", parallel.get_ref()),
+        format!("{:?}", serial.get_ref())
+    );
+    assert!(parallel
+        .get_ref()
+        .windows(2)
+        .all(|pair| pair[0].get_version_ref() <= pair[1].get_version_ref()));
+}
+
+#[test]
+fn par_latest_like_serial() {
+    let list: FMRIList = (0..6000)
+        .map(|fmri| {
+            FMRI::parse_raw(&format!(
+                "pkg://publisher-{}/package-{}@{}.{}-2023.0.0.{}",
+                fmri % 2,
+                fmri % 100,
+                fmri % 7,
+                fmri % 3,
+                fmri % 2,
+            ))
+            .unwrap()
+        })
+        .collect();
+    let latest = list.par_latest_per_package();
+    assert_eq!(latest.len(), 100);
+    assert_eq!(format!("{:?}", latest), format!("{:?}", list.latest()));
+
+    // the later of the same versions is kept
+    let list: FMRIList = (0..1000)
+        .map(|publisher| FMRI::parse_raw(&format!("pkg://publisher-{}/x@1", publisher)).unwrap())
+        .collect();
+    assert_eq!(
+        list.par_latest_per_package().to_string(),
+        "pkg://publisher-999/x@1"
+    );
+    assert_eq!(list.par_latest_per_package(), list.latest());
+}
@@ -0,0 +1,214 @@
+use std::fs;
+use std::io::Read;
+
+use flate2::read::GzDecoder;
+use goblin::elf::Elf;
+
+use crate::manifest::{action::Action, Manifest};
+use crate::payload::verify::{Mismatch, Verifier};
+use crate::payload::{compress, content_hash, elf_hash, sha1_hash, PayloadHashes};
+
+#[test]
+fn payload_hashes() {
+    assert_eq!(sha1_hash(b""), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+    assert_eq!(
+        content_hash(b"hello\n"),
+        "file:sha512t_256:7f3f0c0d5219f51459578305ed2bbc198588758da85d08024c79c1195d1cd611"
+    );
+    assert_eq!(elf_hash(b"hello\n"), Ok(None));
+    assert!(elf_hash(b"\x7fELF broken").is_err());
+
+    let compressed = compress(b"hello\n").unwrap();
+    // gzip header with zero modification time
+    assert_eq!(compressed[..8], [0x1f, 0x8b, 8, 0, 0, 0, 0, 0]);
+    let mut decompressed = String::new();
+    GzDecoder::new(&compressed[..])
+        .read_to_string(&mut decompressed)
+        .unwrap();
+    assert_eq!(decompressed, "hello\n");
+
+    let hashes = PayloadHashes::compute(b"hello\n").unwrap();
+    assert_eq!(hashes.get_size(), 6);
+    assert_eq!(hashes.get_csize(), compressed.len() as u64);
+    assert_eq!(hashes.get_chash_ref(), &sha1_hash(&compressed));
+    assert!(hashes
+        .get_compressed_content_hash_ref()
+        .starts_with("gzip:sha512t_256:"));
+
+    let mut action =
+        Action::parse("file NOHASH path=etc/motd pkg.content-hash=file:sha512t_256:old").unwrap();
+    hashes.apply_to(&mut action);
+    assert_eq!(
+        action.to_string(),
+        format!(
+            "file f572d396fae9206628714fb2ce00f72e94f2258f path=etc/motd \
+             pkg.content-hash={} pkg.content-hash={} chash={} pkg.size=6 pkg.csize={}",
+            hashes.get_content_hash_ref(),
+            hashes.get_compressed_content_hash_ref(),
+            hashes.get_chash_ref(),
+            hashes.get_csize()
+        )
+    );
+}
+
+#[test]
+fn payload_hashes_of_pkg5() {
+    // values of PkgGzipFile (pkg/pkggzip.py), which compresses payloads for pkgsend
+    let hashes = PayloadHashes::compute(b"hello\n").unwrap();
+    assert_eq!(
+        hashes.get_chash_ref(),
+        "4fcc0cd8aeb1c813792e25c8f96ef28d420f54b5"
+    );
+    assert_eq!(hashes.get_csize(), 26);
+    assert_eq!(
+        hashes.get_compressed_content_hash_ref(),
+        "gzip:sha512t_256:236db88d2fcc31f8bee7d5078962d198b59fec3139c1c79b3571f4b050d889c9"
+    );
+
+    // long enough for deflate to differ between zlib and other implementations
+    let content: String = (0..2000)
+        .map(|i| format!("line {} {}\n", i, i * i % 97))
+        .collect();
+    let hashes = PayloadHashes::compute(content.as_bytes()).unwrap();
+    assert_eq!(
+        hashes.get_hash_ref(),
+        "7d8fd159fffa9d6d43687f899e8096e7a0a0b1d7"
+    );
+    assert_eq!(
+        hashes.get_chash_ref(),
+        "48ac8c945f9cfe7a377c30360c09ba2e3a96342c"
+    );
+    assert_eq!(hashes.get_csize(), 6975);
+}
+
+#[test]
+fn payload_elf_hash() {
+    let binary = fs::read(std::env::current_exe().unwrap()).unwrap();
+    let hash = elf_hash(&binary).unwrap().unwrap();
+    assert!(hash.starts_with("gelf:sha512t_256:"));
+    assert_eq!(
+        PayloadHashes::compute(&binary).unwrap().get_elf_hash_ref(),
+        Some(&hash)
+    );
+
+    // changes of ignored sections don't change the hash
+    let elf = Elf::parse(&binary).unwrap();
+    let comment = elf
+        .section_headers
+        .iter()
+        .find(|section| elf.shdr_strtab.get_at(section.sh_name) == Some(".comment"))
+        .and_then(|section| section.file_range())
+        .filter(|range| !range.is_empty());
+    if let Some(range) = comment {
+        let mut changed = binary.clone();
+        changed[range.start] ^= 0xff;
+        assert_eq!(elf_hash(&changed).unwrap().unwrap(), hash);
+        assert_ne!(content_hash(&changed), content_hash(&binary));
+    }
+
+    let text = elf
+        .section_headers
+        .iter()
+        .find(|section| elf.shdr_strtab.get_at(section.sh_name) == Some(".text"))
+        .and_then(|section| section.file_range())
+        .unwrap();
+    let mut changed = binary.clone();
+    changed[text.start] ^= 0xff;
+    assert_ne!(elf_hash(&changed).unwrap().unwrap(), hash);
+}
+
+#[test]
+fn payload_verify() {
+    let root = std::env::temp_dir().join(format!("fmri-verify-{}", std::process::id()));
+    fs::create_dir_all(root.join("etc")).unwrap();
+    fs::write(root.join("etc/motd"), "hello\n").unwrap();
+    fs::write(root.join("etc/legacy"), "hello\n").unwrap();
+    fs::create_dir_all(root.join("etc/dir")).unwrap();
+
+    let mut motd = Action::parse("file NOHASH path=etc/motd").unwrap();
+    PayloadHashes::compute(b"hello\n")
+        .unwrap()
+        .apply_to(&mut motd);
+    let mut manifest = Manifest::new();
+    manifest.add(motd);
+    for action in [
+        "file f572d396fae9206628714fb2ce00f72e94f2258f path=etc/legacy",
+        "file path=etc/missing",
+        "file path=etc/dir",
+        "file NOHASH path=/etc/legacy pkg.size=7",
+    ] {
+        manifest.add(Action::parse(action).unwrap());
+    }
+
+    let verifier = Verifier::new(root.clone());
+    assert!(!verifier.is_checking_ownership());
+    let mismatches = verifier.verify(&manifest);
+    assert_eq!(
+        mismatches.keys().collect::<Vec<&String>>(),
+        vec!["/etc/legacy", "etc/dir", "etc/missing"]
+    );
+    assert_eq!(mismatches["etc/missing"], vec![Mismatch::Missing]);
+    assert_eq!(mismatches["etc/dir"], vec![Mismatch::NotFile]);
+    assert_eq!(
+        mismatches["/etc/legacy"],
+        vec![Mismatch::Size {
+            expected: 7,
+            actual: 6
+        }]
+    );
+
+    fs::write(root.join("etc/motd"), "hello!\n").unwrap();
+    let mismatches = verifier.verify(&manifest);
+    assert_eq!(mismatches["etc/motd"].len(), 2);
+    assert!(matches!(mismatches["etc/motd"][1], Mismatch::Hash { .. }));
+    assert_eq!(
+        mismatches["etc/motd"][0].to_string(),
+        "size: expected 6, found 7"
+    );
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+        fs::set_permissions(root.join("etc/legacy"), fs::Permissions::from_mode(0o640)).unwrap();
+        let metadata = fs::metadata(root.join("etc/legacy")).unwrap();
+        fs::write(
+            root.join("etc/passwd"),
+            format!("builder:x:{}:1::/home/builder:/bin/sh\n", metadata.uid()),
+        )
+        .unwrap();
+        fs::write(
+            root.join("etc/group"),
+            format!("staff::{}:\n", metadata.gid()),
+        )
+        .unwrap();
+
+        let mut manifest = Manifest::new();
+        manifest
+            .add(Action::parse("file path=etc/legacy mode=0644 owner=root group=staff").unwrap());
+        let mut verifier = Verifier::new(root.clone());
+        assert_eq!(
+            verifier.verify(&manifest)["etc/legacy"],
+            vec![Mismatch::Mode {
+                expected: 0o644,
+                actual: 0o640
+            }]
+        );
+        verifier.set_check_ownership(true);
+        let mismatches = verifier.verify(&manifest);
+        assert_eq!(
+            mismatches["etc/legacy"][1],
+            Mismatch::Owner {
+                expected: "root".to_owned(),
+                actual: "builder".to_owned()
+            }
+        );
+        assert_eq!(mismatches["etc/legacy"].len(), 2);
+        assert_eq!(
+            mismatches["etc/legacy"][0].to_string(),
+            "mode: expected 0644, found 0640"
+        );
+    }
+
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,314 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::manifest::action::Action;
+use crate::manifest::Manifest;
+use crate::pkgdepend::{Generated, Generator, Resolver};
+
+struct Proto(PathBuf);
+
+impl Proto {
+    fn new(name: &str) -> Self {
+        let directory =
+            std::env::temp_dir().join(format!("fmri-pkgdepend-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        Self(directory)
+    }
+
+    fn add(&self, path: &str, content: &[u8]) {
+        let file = self.0.join(path);
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(file, content).unwrap();
+    }
+
+    fn generate(&self, manifest: &str) -> Result<Generated, String> {
+        let mut generator = Generator::new();
+        generator.add_proto_dir(self.0.clone());
+        generator.generate(&Manifest::parse(manifest).unwrap())
+    }
+
+    /// Generates dependencies of manifest, whose files are all expected to be fine
+    fn dependencies(&self, manifest: &str) -> Vec<Action> {
+        let generated = self.generate(manifest).unwrap();
+        assert_eq!(generated.get_errors_ref(), &Vec::<String>::new());
+        generated.get_dependencies_ref().get_ref().clone()
+    }
+}
+
+impl Drop for Proto {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+const SMF_MANIFEST: &str = r#"<?xml version="1.0"?>
+<!DOCTYPE service_bundle SYSTEM "/usr/share/lib/xml/dtd/service_bundle.dtd.1">
+<service_bundle type="manifest" name="network/ssh">
+    <service name="network/ssh" type="service" version="1">
+        <dependency name="fs" grouping="require_all" restart_on="none" type="service">
+            <service_fmri value="svc:/system/filesystem/local:default"/>
+        </dependency>
+        <dependency name="net" grouping="optional_all" restart_on="none" type="service">
+            <service_fmri value="svc:/network/physical"/>
+        </dependency>
+        <dependency name="config" grouping="require_all" restart_on="none" type="path">
+            <service_fmri value="file://localhost/etc/ssh/sshd_config"/>
+        </dependency>
+    </service>
+</service_bundle>
+"#;
+
+#[test]
+fn generate_elf() {
+    let proto = Proto::new("elf");
+    proto.add(
+        "usr/bin/test",
+        &fs::read(std::env::current_exe().unwrap()).unwrap(),
+    );
+
+    let dependencies = proto.dependencies("file path=usr/bin/test mode=0555\n");
+    assert!(!dependencies.is_empty());
+    let libc = dependencies
+        .iter()
+        .find(|dependency| {
+            dependency
+                .get_attribute("pkg.debug.depend.file")
+                .is_some_and(|file| file.starts_with("libc.so"))
+        })
+        .unwrap();
+    assert_eq!(libc.get_attribute("fmri").unwrap(), "__TBD");
+    assert_eq!(libc.get_attribute("pkg.debug.depend.type").unwrap(), "elf");
+    assert_eq!(
+        libc.get_attribute("pkg.debug.depend.reason").unwrap(),
+        "usr/bin/test"
+    );
+    assert!(libc
+        .get_attribute_values("pkg.debug.depend.path")
+        .contains(&&"usr/lib/64".to_owned()));
+
+    let dependencies = proto
+        .dependencies("file path=usr/bin/test pkg.depend.runpath=opt/lib:$PKGDEPEND_RUNPATH\n");
+    assert_eq!(
+        dependencies[0].get_attribute_values("pkg.debug.depend.path")[..3],
+        [
+            &"opt/lib".to_owned(),
+            &"lib/64".to_owned(),
+            &"usr/lib/64".to_owned()
+        ]
+    );
+
+    let dependencies = proto.dependencies("file path=usr/bin/test pkg.depend.bypass-generate=.*\n");
+    assert!(dependencies.is_empty());
+}
+
+#[test]
+fn generate_scripts() {
+    let proto = Proto::new("scripts");
+    proto.add("usr/bin/script", b"#!/usr/bin/bash -e\necho test\n");
+    proto.add("usr/bin/env-script", b"#!/usr/bin/env python3\n");
+    proto.add(
+        "usr/bin/tool",
+        b"#!/usr/bin/python3.9 -s\nimport os, sys\nimport xml.dom as dom\nfrom tool import main\nfrom . import local\n",
+    );
+    proto.add(
+        "usr/perl5/vendor_perl/5.36/Foo/Bar.pm",
+        b"package Foo::Bar;\nuse strict;\nuse 5.010;\nuse File::Spec;\nrequire Carp;\n__END__\nuse Not::Used;\n",
+    );
+
+    assert_eq!(
+        proto
+            .dependencies(
+                "file path=usr/bin/script mode=0555\nfile path=usr/bin/script mode=0444\n"
+            )
+            .iter()
+            .map(Action::to_string)
+            .collect::<Vec<String>>(),
+        vec![
+            "depend fmri=__TBD pkg.debug.depend.file=bash pkg.debug.depend.path=usr/bin \
+             pkg.debug.depend.reason=usr/bin/script pkg.debug.depend.type=script type=require"
+        ]
+    );
+
+    let dependencies = proto.dependencies("file path=usr/bin/tool mode=0555\n");
+    assert_eq!(dependencies.len(), 4);
+    assert_eq!(
+        dependencies[0]
+            .get_attribute("pkg.debug.depend.file")
+            .unwrap(),
+        "python3.9"
+    );
+    assert_eq!(
+        dependencies[1].get_attribute_values("pkg.debug.depend.file"),
+        vec!["os.py", "os.pyc", "os/__init__.py", "os.so", "osmodule.so"]
+    );
+    assert_eq!(
+        dependencies[1].get_attribute_values("pkg.debug.depend.path"),
+        vec![
+            "usr/bin",
+            "usr/lib/python3.9",
+            "usr/lib/python3.9/lib-dynload",
+            "usr/lib/python3.9/vendor-packages",
+            "usr/lib/python3.9/site-packages"
+        ]
+    );
+    assert_eq!(
+        dependencies[2]
+            .get_attribute("pkg.debug.depend.file")
+            .unwrap(),
+        "xml/dom.py"
+    );
+    assert_eq!(
+        dependencies[3]
+            .get_attribute("pkg.debug.depend.file")
+            .unwrap(),
+        "tool.py"
+    );
+
+    let dependencies =
+        proto.dependencies("file path=usr/perl5/vendor_perl/5.36/Foo/Bar.pm mode=0444\n");
+    assert_eq!(
+        dependencies
+            .iter()
+            .map(|dependency| dependency.get_attribute("pkg.debug.depend.file").unwrap())
+            .collect::<Vec<&String>>(),
+        vec!["File/Spec.pm", "Carp.pm"]
+    );
+    assert_eq!(
+        dependencies[0].get_attribute_values("pkg.debug.depend.path"),
+        vec![
+            "usr/perl5/5.36/lib",
+            "usr/perl5/vendor_perl/5.36",
+            "usr/perl5/site_perl/5.36"
+        ]
+    );
+}
+
+#[test]
+fn generate_with_errors() {
+    let proto = Proto::new("errors");
+    proto.add("usr/bin/script", b"#!/usr/bin/bash -e\necho test\n");
+    proto.add("usr/bin/env-script", b"#!/usr/bin/env python3\n");
+    proto.add("usr/bin/relative-script", b"#!bin/sh\n");
+    proto.add("usr/share/doc/README", b"text\n");
+
+    let generated = proto
+        .generate(
+            "file path=usr/bin/env-script mode=0555\n\
+             file path=usr/bin/script mode=0555\n\
+             file path=usr/bin/relative-script mode=0555\n\
+             file path=usr/share/doc/README mode=0444\n",
+        )
+        .unwrap();
+    assert_eq!(
+        generated.get_dependencies_ref().to_string(),
+        "depend fmri=__TBD pkg.debug.depend.file=bash pkg.debug.depend.path=usr/bin \
+         pkg.debug.depend.reason=usr/bin/script pkg.debug.depend.type=script type=require\n"
+    );
+    assert_eq!(
+        generated.get_errors_ref(),
+        &vec![
+            "usr/bin/env-script: interpreter \"/usr/bin/env python3\" hides real interpreter, \
+             use its absolute path"
+                .to_owned(),
+            "usr/bin/relative-script: interpreter \"bin/sh\" must be absolute path".to_owned(),
+        ]
+    );
+}
+
+#[test]
+fn generate_smf() {
+    let proto = Proto::new("smf");
+    proto.add("lib/svc/manifest/network/ssh.xml", SMF_MANIFEST.as_bytes());
+    let manifest = "file path=lib/svc/manifest/network/ssh.xml mode=0444\n";
+
+    let generated = proto.generate(manifest).unwrap();
+    assert!(generated.get_dependencies_ref().is_empty());
+    assert_eq!(generated.get_errors_ref().len(), 1);
+    assert!(generated.get_errors_ref()[0].starts_with("lib/svc/manifest/network/ssh.xml: "));
+
+    let root = Proto::new("smf-root");
+    root.add(
+        "lib/svc/manifest/system/filesystem/local-fs.xml",
+        br#"<service_bundle type="manifest" name="fs"><service name="system/filesystem/local"/></service_bundle>"#,
+    );
+    let mut generator = Generator::new();
+    generator.add_proto_dir(proto.0.clone());
+    generator.add_smf_manifest_dir(Path::new(&root.0)).unwrap();
+    assert_eq!(
+        generator
+            .generate(&Manifest::parse(manifest).unwrap())
+            .unwrap()
+            .get_dependencies_ref()
+            .to_string(),
+        "depend fmri=__TBD pkg.debug.depend.file=local-fs.xml \
+         pkg.debug.depend.path=lib/svc/manifest/system/filesystem \
+         pkg.debug.depend.reason=lib/svc/manifest/network/ssh.xml \
+         pkg.debug.depend.type=smf type=require\n"
+    );
+
+    assert!(generator
+        .add_smf_manifest("broken.xml".to_owned(), "<service")
+        .is_err());
+    assert!(proto.generate("file path=missing\n").is_err());
+}
+
+#[test]
+fn resolve() {
+    let mut resolver = Resolver::new();
+    for manifest in [
+        "set name=pkg.fmri value=pkg://oi/system/library@0.5.11-2023.0.0.1:20230101T000000Z\n\
+         file path=lib/64/libc.so.1\n\
+         link path=usr/lib/64/libc.so.1 target=../../../lib/64/libc.so.1\n",
+        "set name=pkg.fmri value=pkg://oi/compat/links@1.0\n\
+         link path=usr/bin/bash target=/usr/gnu/bin/bash\n",
+        "set name=pkg.fmri value=pkg://oi/runtime/python-39@3.9.18\n\
+         file path=usr/lib/python3.9/os.py\n\
+         link path=usr/lib/python3.9/loop.py target=loop.py\n",
+    ] {
+        resolver
+            .add_manifest(&Manifest::parse(manifest).unwrap())
+            .unwrap();
+    }
+    assert!(resolver.add_manifest(&Manifest::new()).is_err());
+
+    let resolved = resolver
+        .resolve(
+            &Manifest::parse(
+                "set name=pkg.fmri value=pkg://oi/shell/bash@5.2\n\
+                 file path=usr/gnu/bin/bash\n\
+                 file path=usr/lib/bash/helper.py\n\
+                 depend fmri=__TBD pkg.debug.depend.file=libc.so.1 \
+                     pkg.debug.depend.path=usr/lib/64 pkg.debug.depend.path=lib/64 \
+                     pkg.debug.depend.reason=usr/gnu/bin/bash pkg.debug.depend.type=elf type=require\n\
+                 depend fmri=__TBD pkg.debug.depend.file=libc.so.1 pkg.debug.depend.path=lib/64 \
+                     pkg.debug.depend.reason=usr/lib/bash/loadable pkg.debug.depend.type=elf type=require\n\
+                 depend fmri=__TBD pkg.debug.depend.file=bash pkg.debug.depend.path=usr/bin \
+                     pkg.debug.depend.reason=usr/lib/bash/script pkg.debug.depend.type=script type=require\n\
+                 depend fmri=__TBD pkg.debug.depend.file=helper.py pkg.debug.depend.path=usr/lib/bash \
+                     pkg.debug.depend.reason=usr/lib/bash/tool pkg.debug.depend.type=python type=require\n\
+                 depend fmri=__TBD pkg.debug.depend.file=loop.py pkg.debug.depend.file=missing.py \
+                     pkg.debug.depend.path=usr/lib/python3.9 \
+                     pkg.debug.depend.reason=usr/lib/bash/tool pkg.debug.depend.type=python type=require\n\
+                 depend fmri=pkg:/system/locale type=require\n",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+    assert_eq!(
+        resolved.get_dependencies_ref().to_string(),
+        "depend fmri=pkg:/compat/links@1.0 type=require pkg.debug.depend.file=usr/bin/bash \
+         pkg.debug.depend.reason=usr/lib/bash/script\n\
+         depend fmri=pkg:/system/library@0.5.11-2023.0.0.1 type=require \
+         pkg.debug.depend.file=usr/lib/64/libc.so.1 pkg.debug.depend.reason=usr/gnu/bin/bash \
+         pkg.debug.depend.file=lib/64/libc.so.1 pkg.debug.depend.reason=usr/lib/bash/loadable\n"
+    );
+    assert_eq!(resolved.get_unresolved_ref().len(), 1);
+    assert_eq!(
+        resolved.get_unresolved_ref()[0]
+            .get_attribute("pkg.debug.depend.file")
+            .unwrap(),
+        "loop.py"
+    );
+}
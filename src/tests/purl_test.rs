@@ -0,0 +1,71 @@
+use crate::helpers::{percent_decode, percent_encode};
+use crate::FMRI;
+
+#[test]
+fn purl_round_trip() {
+    for (raw_fmri, purl) in [
+        ("shell/bash", "pkg:ips/shell%2Fbash"),
+        ("pkg:/shell/bash@5.2", "pkg:ips/shell%2Fbash@5.2"),
+        (
+            "pkg://openindiana.org/shell/bash@5.2.21",
+            "pkg:ips/openindiana.org/shell%2Fbash@5.2.21",
+        ),
+        (
+            "pkg://openindiana.org/library/c++/sigcpp@2.10.8,5.11-2023.0.0.0:20230303T102345Z",
+            "pkg:ips/openindiana.org/library%2Fc%2B%2B%2Fsigcpp@2.10.8\
+             ?branch=2023.0.0.0&build=5.11&timestamp=20230303T102345Z",
+        ),
+        (
+            "pkg://on-nightly/system/kernel@0.5.11:20230303T102345Z",
+            "pkg:ips/on-nightly/system%2Fkernel@0.5.11?timestamp=20230303T102345Z",
+        ),
+        // lenient timestamp
+        ("a@1.0:abc", "pkg:ips/a@1.0?timestamp=abc"),
+    ] {
+        let fmri = FMRI::parse_raw(raw_fmri).unwrap();
+        assert_eq!(fmri.to_purl(), purl);
+        assert_eq!(FMRI::from_purl(purl).unwrap(), fmri);
+        assert_eq!(FMRI::from_purl(purl).unwrap().to_string(), fmri.to_string());
+    }
+}
+
+#[test]
+fn from_purl_lenient() {
+    let fmri = FMRI::parse_raw("pkg://oi/shell/bash@5.2-2023.0").unwrap();
+    for purl in [
+        "pkg:IPS/oi/shell%2fbash@5.2?branch=2023.0",
+        "pkg:ips/oi/shell%2Fbash@5.2?repository_url=https%3A%2F%2Fpkg.openindiana.org&branch=2023.0",
+        "pkg:ips/oi/shell%2Fbash@5.2?branch=2023.0#usr/bin/bash",
+    ] {
+        assert_eq!(FMRI::from_purl(purl).unwrap(), fmri, "{}", purl);
+    }
+}
+
+#[test]
+fn from_purl_invalid() {
+    for purl in [
+        "ips/shell%2Fbash",
+        "pkg:deb/shell%2Fbash",
+        "pkg:ips",
+        "pkg:ips/",
+        "pkg:ips/a/b/c",
+        "pkg:ips/shell%2Fbash@5.a",
+        "pkg:ips/shell%2Fbash?branch=1",
+        "pkg:ips/shell%2Fbash@5?build=x",
+        "pkg:ips/shell%2Fbash@5?timestamp=",
+        "pkg:ips/shell%2F%2Fbash",
+        "pkg:ips/open%20indiana/bash",
+        "pkg:ips/shell%2Gbash",
+    ] {
+        assert!(FMRI::from_purl(purl).is_err(), "{}", purl);
+    }
+}
+
+#[test]
+fn _percent_encoding() {
+    assert_eq!(percent_encode("a/b c+d~e"), "a%2Fb%20c%2Bd~e");
+    assert_eq!(percent_decode("a%2Fb%20c%2bd~e").unwrap(), "a/b c+d~e");
+    assert_eq!(percent_decode("%C5%BE").unwrap(), "ž");
+    assert!(percent_decode("%2").is_err());
+    assert!(percent_decode("%FF").is_err());
+}
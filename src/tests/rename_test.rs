@@ -0,0 +1,183 @@
+use crate::manifest::Manifest;
+use crate::rename::{PackageStatus, RenameResolver, Resolution};
+use crate::{FMRIList, FMRI};
+
+fn fmri(raw_fmri: &str) -> FMRI {
+    FMRI::parse_raw(raw_fmri).unwrap()
+}
+
+#[test]
+fn resolve() {
+    let mut resolver = RenameResolver::new();
+    for manifest in [
+        "set name=pkg.fmri value=pkg://oi/library/a@1.0\n",
+        "set name=pkg.fmri value=pkg://oi/library/a@2.0\n\
+         set name=pkg.renamed value=true\n\
+         depend fmri=library/b@2.0 type=require\n",
+        "set name=pkg.fmri value=pkg://oi/library/b@2.0\n\
+         set name=pkg.renamed value=true\n\
+         depend fmri=library/c@2.0 type=require\n",
+        "set name=pkg.fmri value=pkg://oi/library/c@2.0\n",
+        "set name=pkg.fmri value=pkg://oi/library/old@1.0\n\
+         set name=pkg.obsolete value=true\n",
+        "set name=pkg.fmri value=pkg://oi/library/d@1.0\n\
+         set name=pkg.renamed value=true\n\
+         depend fmri=library/old@1.0 type=require\n",
+    ] {
+        resolver
+            .add_manifest(&Manifest::parse(manifest).unwrap())
+            .unwrap();
+    }
+
+    assert_eq!(
+        resolver.resolve(&fmri("library/a")).unwrap(),
+        Resolution::Renamed {
+            chain: vec![
+                fmri("pkg://oi/library/a@2.0"),
+                fmri("pkg://oi/library/b@2.0")
+            ],
+            current: fmri("library/c@2.0"),
+        }
+    );
+    assert_eq!(
+        resolver.resolve(&fmri("library/c")).unwrap(),
+        Resolution::Current(fmri("library/c"))
+    );
+    assert_eq!(
+        resolver.resolve(&fmri("library/unknown@1")).unwrap(),
+        Resolution::Current(fmri("library/unknown@1"))
+    );
+    assert_eq!(
+        resolver.resolve(&fmri("library/d")).unwrap(),
+        Resolution::Obsolete {
+            chain: vec![
+                fmri("pkg://oi/library/d@1.0"),
+                fmri("pkg://oi/library/old@1.0")
+            ]
+        }
+    );
+    // requested version is newer than all known versions
+    assert_eq!(
+        resolver.resolve(&fmri("library/a@3.0")).unwrap(),
+        Resolution::Current(fmri("library/a@3.0"))
+    );
+}
+
+#[test]
+fn resolve_cycle() {
+    let mut resolver = RenameResolver::new();
+    resolver.add(fmri("x@1"), PackageStatus::Renamed(fmri("y@1")));
+    resolver.add(fmri("y@1"), PackageStatus::Renamed(fmri("x@1")));
+    assert_eq!(
+        resolver.resolve(&fmri("x")).unwrap_err(),
+        "rename cycle: x -> y -> x"
+    );
+}
+
+#[test]
+fn rewrite() {
+    let mut resolver = RenameResolver::new();
+    resolver.add(
+        fmri("library/a@2.0"),
+        PackageStatus::Renamed(fmri("library/c@2.0")),
+    );
+    resolver.add(fmri("library/c@2.0"), PackageStatus::Normal);
+    resolver.add(fmri("library/old@1.0"), PackageStatus::Obsolete);
+
+    let list: FMRIList = [
+        "library/a",
+        "library/c@2.0",
+        "library/old",
+        "shell/bash@5.2",
+    ]
+    .iter()
+    .map(|raw_fmri| fmri(raw_fmri))
+    .collect();
+
+    let rewritten = resolver.rewrite(&list).unwrap();
+    assert_eq!(
+        rewritten.get_list_ref(),
+        &FMRIList::from(vec![fmri("library/c@2.0"), fmri("shell/bash@5.2")])
+    );
+    assert_eq!(rewritten.get_renamed_ref().len(), 1);
+    assert_eq!(
+        rewritten.get_renamed_ref()[0].get_new_ref(),
+        &fmri("library/c")
+    );
+    assert_eq!(
+        rewritten.get_obsolete_ref(),
+        &FMRIList::from(vec![fmri("library/old")])
+    );
+}
+
+#[test]
+fn catalog_part() {
+    let mut resolver = RenameResolver::new();
+    resolver
+        .add_catalog_part(
+            r#"{
+                "_SIGNATURE": {"sha-1": "abc"},
+                "openindiana.org": {
+                    "library/a": [
+                        {"version": "1.0-2023.0.0.0:20230101T000000Z", "actions": [
+                            "set name=pkg.renamed value=true",
+                            "depend fmri=library/b@1.0 type=require"
+                        ]}
+                    ],
+                    "library/b": [{"version": "1.0-2023.0.0.0:20230101T000000Z"}]
+                }
+            }"#,
+        )
+        .unwrap();
+
+    assert_eq!(
+        resolver.resolve(&fmri("library/a")).unwrap(),
+        Resolution::Renamed {
+            chain: vec![fmri(
+                "pkg://openindiana.org/library/a@1.0-2023.0.0.0:20230101T000000Z"
+            )],
+            current: fmri("library/b@1.0"),
+        }
+    );
+    assert!(resolver.add_catalog_part("[]").is_err());
+    assert!(resolver
+        .add_catalog_part(
+            r#"{"oi": {"x": [{"version": "1", "actions": ["set name=pkg.renamed value=true"]}]}}"#
+        )
+        .is_err());
+}
+
+#[test]
+fn rename_target_skips_incorporation() {
+    let renamed = |depends: &str| {
+        Manifest::parse(&format!(
+            "set name=pkg.fmri value=pkg://oi/library/python/six-39@1.16\n\
+             set name=pkg.renamed value=true\n{}",
+            depends
+        ))
+        .unwrap()
+    };
+
+    for depends in [
+        "depend fmri=consolidation/userland/userland-incorporation type=require\n\
+         depend fmri=library/python/six-311@1.16 type=require\n",
+        "depend fmri=library/python/six-311@1.16 type=require\n\
+         depend fmri=entire type=require\n",
+    ] {
+        let mut resolver = RenameResolver::new();
+        resolver.add_manifest(&renamed(depends)).unwrap();
+        assert_eq!(
+            resolver.get(&fmri("library/python/six-39")).unwrap().1,
+            PackageStatus::Renamed(fmri("library/python/six-311@1.16"))
+        );
+    }
+
+    let mut resolver = RenameResolver::new();
+    assert!(resolver
+        .add_manifest(&renamed(
+            "depend fmri=library/python/six-311 type=require\n\
+             depend fmri=library/python/six-312 type=require\n"
+        ))
+        .unwrap_err()
+        .contains("ambiguous"));
+}
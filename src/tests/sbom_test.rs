@@ -0,0 +1,223 @@
+use serde_json::Value;
+
+use crate::manifest::Manifest;
+use crate::sbom::Sbom;
+use crate::{FMRIList, FMRI};
+
+#[test]
+fn sbom_components() {
+    let list: FMRIList = [
+        "pkg://openindiana.org/shell/bash@5.2.21,5.11-2023.0.0.1:20230901T080000Z",
+        "pkg://openindiana.org/library/ncurses@6.4-2023.0.0.0",
+        "pkg://openindiana.org/library/readline@8.2",
+    ]
+    .iter()
+    .map(|fmri| FMRI::parse_raw(fmri).unwrap())
+    .collect();
+
+    let mut sbom = Sbom::from_fmri_list("oi-image".to_owned(), &list);
+    sbom.add_manifest(
+        &Manifest::parse(
+            "set name=pkg.fmri value=pkg://openindiana.org/shell/bash@5.2.21,5.11-2023.0.0.1:20230901T080000Z\n\
+             set name=pkg.summary value=\"GNU Bourne Again shell\"\n\
+             set name=info.upstream-url value=https://www.gnu.org/software/bash/\n\
+             set name=info.source-url value=https://ftp.gnu.org/gnu/bash/bash-5.2.21.tar.gz\n\
+             license bash.license license=GPLv3\n",
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    sbom.set_created("2023-09-05T10:15:30Z".to_owned());
+    assert_eq!(sbom.get_name_ref(), "oi-image");
+    let names: Vec<&String> = sbom
+        .components()
+        .map(|component| component.get_fmri_ref().get_package_name_as_ref_string())
+        .collect();
+    assert_eq!(
+        names,
+        vec!["library/ncurses", "library/readline", "shell/bash"]
+    );
+
+    let bash = sbom.get("shell/bash").unwrap();
+    assert_eq!(
+        bash.get_summary_ref().as_deref(),
+        Some("GNU Bourne Again shell")
+    );
+    assert_eq!(bash.get_licenses_ref(), &vec!["GPLv3"]);
+    assert_eq!(
+        bash.get_upstream_url_ref().as_deref(),
+        Some("https://www.gnu.org/software/bash/")
+    );
+    assert!(bash.get_source_url_ref().is_some());
+    assert_eq!(
+        bash.bom_ref(),
+        "pkg:ips/openindiana.org/shell%2Fbash@5.2.21?branch=2023.0.0.1&build=5.11&timestamp=20230901T080000Z"
+    );
+    assert!(bash
+        .spdx_id()
+        .starts_with("SPDXRef-Package-shell-bash-5.2.21-5.11-2023.0.0.1-20230901T080000Z-"));
+    assert_ne!(
+        bash.spdx_id(),
+        sbom.get("library/ncurses").unwrap().spdx_id()
+    );
+
+    let mut manifest_only = Sbom::new("image".to_owned());
+    manifest_only
+        .add_manifest(&Manifest::parse("set name=pkg.fmri value=pkg:/a@1").unwrap())
+        .unwrap();
+    assert!(manifest_only.get("a").is_some());
+    assert!(manifest_only
+        .add_manifest(&Manifest::parse("set name=pkg.summary value=a").unwrap())
+        .is_err());
+}
+
+#[test]
+fn sbom_cyclonedx() {
+    let list: FMRIList = [
+        "pkg://openindiana.org/shell/bash@5.2.21,5.11-2023.0.0.1:20230901T080000Z",
+        "pkg://openindiana.org/library/ncurses@6.4-2023.0.0.0",
+        "pkg://openindiana.org/library/readline@8.2",
+    ]
+    .iter()
+    .map(|fmri| FMRI::parse_raw(fmri).unwrap())
+    .collect();
+
+    let mut sbom = Sbom::from_fmri_list("oi-image".to_owned(), &list);
+    sbom.add_manifest(
+        &Manifest::parse(
+            "set name=pkg.fmri value=pkg://openindiana.org/shell/bash@5.2.21,5.11-2023.0.0.1:20230901T080000Z\n\
+             set name=pkg.summary value=\"GNU Bourne Again shell\"\n\
+             set name=info.upstream-url value=https://www.gnu.org/software/bash/\n\
+             set name=info.source-url value=https://ftp.gnu.org/gnu/bash/bash-5.2.21.tar.gz\n\
+             license bash.license license=GPLv3\n\
+             depend fmri=pkg:/library/ncurses@6.4 type=require\n\
+             depend fmri=library/readline type=optional\n\
+             depend fmri=library/missing type=require\n\
+             depend fmri=consolidation/userland/userland-incorporation type=incorporate\n",
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    sbom.set_created("2023-09-05T10:15:30Z".to_owned());
+    let document: Value = serde_json::from_str(&sbom.to_cyclonedx_json()).unwrap();
+    assert_eq!(document["bomFormat"], "CycloneDX");
+    assert_eq!(document["specVersion"], "1.5");
+    assert_eq!(document["metadata"]["timestamp"], "2023-09-05T10:15:30Z");
+    assert_eq!(document["metadata"]["component"]["name"], "oi-image");
+
+    let bash = &document["components"][2];
+    assert_eq!(bash["name"], "shell/bash");
+    assert_eq!(bash["version"], "5.2.21,5.11-2023.0.0.1:20230901T080000Z");
+    assert_eq!(bash["publisher"], "openindiana.org");
+    assert_eq!(bash["bom-ref"], bash["purl"]);
+    assert_eq!(bash["description"], "GNU Bourne Again shell");
+    assert_eq!(bash["licenses"][0]["license"]["name"], "GPLv3");
+    assert_eq!(bash["externalReferences"][0]["type"], "website");
+    assert_eq!(bash["externalReferences"][1]["type"], "source-distribution");
+    assert!(document["components"][0].get("description").is_none());
+
+    let dependencies = &document["dependencies"][2];
+    assert_eq!(dependencies["ref"], bash["bom-ref"]);
+    assert_eq!(
+        dependencies["dependsOn"],
+        serde_json::json!([
+            document["components"][0]["bom-ref"],
+            document["components"][1]["bom-ref"]
+        ])
+    );
+    assert_eq!(
+        document["dependencies"][0]["dependsOn"],
+        serde_json::json!([])
+    );
+}
+
+#[test]
+fn sbom_spdx() {
+    let image = || {
+        let list: FMRIList = [
+            "pkg://openindiana.org/shell/bash@5.2.21,5.11-2023.0.0.1:20230901T080000Z",
+            "pkg://openindiana.org/library/ncurses@6.4-2023.0.0.0",
+            "pkg://openindiana.org/library/readline@8.2",
+        ]
+        .iter()
+        .map(|fmri| FMRI::parse_raw(fmri).unwrap())
+        .collect();
+
+        let mut sbom = Sbom::from_fmri_list("oi-image".to_owned(), &list);
+        sbom.add_manifest(
+            &Manifest::parse(
+                "set name=pkg.fmri value=pkg://openindiana.org/shell/bash@5.2.21,5.11-2023.0.0.1:20230901T080000Z\n\
+                 set name=pkg.summary value=\"GNU Bourne Again shell\"\n\
+                 set name=info.upstream-url value=https://www.gnu.org/software/bash/\n\
+                 set name=info.source-url value=https://ftp.gnu.org/gnu/bash/bash-5.2.21.tar.gz\n\
+                 license bash.license license=GPLv3\n\
+                 depend fmri=pkg:/library/ncurses@6.4 type=require\n\
+                 depend fmri=library/readline type=optional\n\
+                 depend fmri=library/missing type=require\n\
+                 depend fmri=consolidation/userland/userland-incorporation type=incorporate\n",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        sbom.set_created("2023-09-05T10:15:30Z".to_owned());
+        sbom
+    };
+    let sbom = image();
+    let bash = sbom.get("shell/bash").unwrap().spdx_id();
+    let ncurses = sbom.get("library/ncurses").unwrap().spdx_id();
+    let readline = sbom.get("library/readline").unwrap().spdx_id();
+
+    let document: Value = serde_json::from_str(&sbom.to_spdx_json()).unwrap();
+    assert_eq!(document["spdxVersion"], "SPDX-2.3");
+    assert_eq!(document["SPDXID"], "SPDXRef-DOCUMENT");
+    assert_eq!(document["creationInfo"]["created"], "2023-09-05T10:15:30Z");
+    assert!(document["documentNamespace"]
+        .as_str()
+        .unwrap()
+        .starts_with("https://spdx.org/spdxdocs/oi-image-"));
+    let package = &document["packages"][2];
+    assert_eq!(package["SPDXID"], bash.as_str());
+    assert_eq!(package["supplier"], "Organization: openindiana.org");
+    assert_eq!(
+        package["downloadLocation"],
+        "https://ftp.gnu.org/gnu/bash/bash-5.2.21.tar.gz"
+    );
+    assert_eq!(package["licenseComments"], "Licenses: GPLv3");
+    assert_eq!(package["externalRefs"][0]["referenceType"], "purl");
+    assert_eq!(document["packages"][0]["downloadLocation"], "NOASSERTION");
+    assert_eq!(document["relationships"].as_array().unwrap().len(), 5);
+
+    let tag_value = sbom.to_spdx_tag_value();
+    assert!(tag_value.starts_with("SPDXVersion: SPDX-2.3\nDataLicense: CC0-1.0\n"));
+    assert!(tag_value.contains("Created: 2023-09-05T10:15:30Z\n"));
+    assert!(tag_value.contains(&format!(
+        "Relationship: SPDXRef-DOCUMENT DESCRIBES {}\n",
+        bash
+    )));
+    assert!(tag_value.contains(&format!("Relationship: {} DEPENDS_ON {}\n", bash, ncurses)));
+    assert!(tag_value.contains(&format!(
+        "Relationship: {} OPTIONAL_DEPENDENCY_OF {}\n",
+        readline, bash
+    )));
+    assert!(tag_value.contains("PackageHomePage: https://www.gnu.org/software/bash/\n"));
+
+    // namespace and output are stable
+    assert_eq!(tag_value, image().to_spdx_tag_value());
+    let mut sbom = image();
+    sbom.set_namespace("https://example.org/sbom".to_owned());
+    assert!(sbom
+        .to_spdx_tag_value()
+        .contains("DocumentNamespace: https://example.org/sbom\n"));
+}
+
+#[test]
+fn sbom_created_now() {
+    let created = Sbom::new("image".to_owned()).to_spdx_tag_value();
+    let created = created
+        .lines()
+        .find_map(|line| line.strip_prefix("Created: "))
+        .unwrap();
+    assert_eq!(created.len(), 20);
+    assert!(created.starts_with("20"));
+    assert!(created.ends_with('Z'));
+}
@@ -0,0 +1,201 @@
+use crate::manifest::Manifest;
+use crate::search::{query::Query, SearchIndex};
+
+fn packages(index: &SearchIndex, query: &str) -> Vec<String> {
+    index
+        .search(&Query::parse(query).unwrap())
+        .iter()
+        .map(|result| {
+            result
+                .get_fmri_ref()
+                .get_package_name_as_ref_string()
+                .clone()
+        })
+        .collect()
+}
+
+#[test]
+fn search_index_add() {
+    let mut index = SearchIndex::new();
+    for manifest in [
+        "set name=pkg.fmri value=pkg://oi/shell/bash@5.2\n",
+        "set name=pkg.fmri value=pkg://oi/shell/zsh@5.9\n",
+    ] {
+        index
+            .add_manifest(&Manifest::parse(manifest).unwrap())
+            .unwrap();
+    }
+    assert_eq!(index.len(), 2);
+    assert!(!index.is_empty());
+    assert!(index
+        .add_manifest(&Manifest::parse("set name=pkg.fmri value=pkg://oi/shell/zsh@5.9").unwrap())
+        .is_err());
+    assert!(index
+        .add_manifest(&Manifest::parse("file path=usr/bin/ksh").unwrap())
+        .is_err());
+    assert_eq!(index.fmris().count(), 2);
+}
+
+#[test]
+fn search_terms() {
+    let mut index = SearchIndex::new();
+    for manifest in [
+        "set name=pkg.fmri value=pkg://oi/shell/bash@5.2\n\
+         set name=pkg.description value=\"GNU Bourne-Again shell\"\n\
+         file 1234 path=usr/bin/bash mode=0555\n\
+         link path=usr/bin/sh target=bash\n\
+         depend fmri=pkg:/library/ncurses@6.4 type=require\n",
+        "set name=pkg.fmri value=pkg://oi/shell/zsh@5.9\n\
+         set name=pkg.description value=\"Z shell\"\n\
+         file 5678 path=usr/bin/zsh mode=0555\n\
+         depend fmri=library/ncurses type=require\n\
+         license zsh.license license=MIT\n",
+        "set name=pkg.fmri value=pkg://oi/library/ncurses@6.4\n\
+         dir path=usr/lib mode=0755\n\
+         user username=ncurses uid=100\n",
+    ] {
+        index
+            .add_manifest(&Manifest::parse(manifest).unwrap())
+            .unwrap();
+    }
+    assert_eq!(packages(&index, "bash"), vec!["shell/bash"]);
+    assert_eq!(packages(&index, "BASH"), vec!["shell/bash"]);
+    assert_eq!(packages(&index, "usr/bin/zsh"), vec!["shell/zsh"]);
+    assert_eq!(
+        packages(&index, "path:usr/bin/*"),
+        vec!["shell/bash", "shell/zsh"]
+    );
+    assert_eq!(packages(&index, "link:basename:sh"), vec!["shell/bash"]);
+    assert_eq!(packages(&index, "file:basename:sh").len(), 0);
+    assert_eq!(
+        packages(&index, "pkg.description:bourne-again"),
+        vec!["shell/bash"]
+    );
+    assert_eq!(packages(&index, "fmri:ncurses"), vec!["library/ncurses"]);
+    assert_eq!(
+        packages(&index, "depend:require:ncurses"),
+        vec!["shell/bash", "shell/zsh"]
+    );
+    assert_eq!(packages(&index, "zsh:::ncurses"), vec!["shell/zsh"]);
+    assert_eq!(packages(&index, "content:5678"), vec!["shell/zsh"]);
+    assert_eq!(packages(&index, "license:MIT"), vec!["shell/zsh"]);
+    assert_eq!(
+        packages(&index, "username:ncurses"),
+        vec!["library/ncurses"]
+    );
+    assert_eq!(packages(&index, "sh?ll").len(), 2);
+}
+
+#[test]
+fn search_matches() {
+    let mut index = SearchIndex::new();
+    for manifest in [
+        "set name=pkg.fmri value=pkg://oi/shell/bash@5.2\n\
+         depend fmri=pkg:/library/ncurses@6.4 type=require\n",
+        "set name=pkg.fmri value=pkg://oi/shell/zsh@5.9\n\
+         depend fmri=library/ncurses type=require\n",
+        "set name=pkg.fmri value=pkg://oi/library/ncurses@6.4\n\
+         user username=ncurses uid=100\n",
+    ] {
+        index
+            .add_manifest(&Manifest::parse(manifest).unwrap())
+            .unwrap();
+    }
+    let results = index.search(&Query::parse("ncurses").unwrap());
+    assert_eq!(results.len(), 3);
+    let matches = results[0].get_matches_ref();
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].get_index_ref(), "require");
+    assert_eq!(matches[0].get_value_ref(), "pkg:/library/ncurses@6.4");
+    assert_eq!(matches[0].get_action_ref().get_action_type_ref(), "depend");
+    assert_eq!(results[2].get_matches_ref().len(), 2);
+
+    let results = index.search(&Query::parse("<ncurses>").unwrap());
+    assert_eq!(results.len(), 3);
+    assert!(results
+        .iter()
+        .all(|result| result.get_matches_ref().is_empty()));
+}
+
+#[test]
+fn search_boolean() {
+    let mut index = SearchIndex::new();
+    for manifest in [
+        "set name=pkg.fmri value=pkg://oi/shell/bash@5.2\n\
+         set name=pkg.description value=\"GNU Bourne-Again shell\"\n\
+         file path=usr/bin/bash mode=0555\n\
+         link path=usr/bin/sh target=bash\n\
+         depend fmri=pkg:/library/ncurses@6.4 type=require\n",
+        "set name=pkg.fmri value=pkg://oi/shell/zsh@5.9\n\
+         set name=pkg.description value=\"Z shell\"\n\
+         file path=usr/bin/zsh mode=0555\n\
+         depend fmri=library/ncurses type=require\n",
+        "set name=pkg.fmri value=pkg://oi/library/ncurses@6.4\n\
+         dir path=usr/lib mode=0755\n",
+    ] {
+        index
+            .add_manifest(&Manifest::parse(manifest).unwrap())
+            .unwrap();
+    }
+    assert_eq!(
+        packages(&index, "shell AND bourne-again"),
+        vec!["shell/bash"]
+    );
+    assert_eq!(packages(&index, "shell bourne-again"), vec!["shell/bash"]);
+    assert_eq!(
+        packages(&index, "bash OR zsh"),
+        vec!["shell/bash", "shell/zsh"]
+    );
+    assert_eq!(
+        packages(&index, "ncurses AND (bash OR zsh)"),
+        vec!["shell/bash", "shell/zsh"]
+    );
+    assert_eq!(packages(&index, "uid OR bash zsh").len(), 0);
+    assert_eq!(
+        packages(&index, "usr/lib OR bash zsh"),
+        vec!["library/ncurses"]
+    );
+
+    let results = index.search(&Query::parse("bash AND pkg.description:shell").unwrap());
+    assert_eq!(results[0].get_matches_ref().len(), 3);
+}
+
+#[test]
+fn search_case_sensitive() {
+    let mut index = SearchIndex::new();
+    index
+        .add_manifest(
+            &Manifest::parse(
+                "set name=pkg.fmri value=pkg://oi/shell/bash@5.2\n\
+                 set name=pkg.description value=\"GNU Bourne-Again shell\"\n",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    let mut query = Query::parse("gnu").unwrap();
+    assert!(!query.is_case_sensitive());
+    assert_eq!(index.search(&query).len(), 1);
+    query.set_case_sensitive(true);
+    assert_eq!(index.search(&query).len(), 0);
+    query = Query::parse("GNU").unwrap();
+    query.set_case_sensitive(true);
+    assert_eq!(index.search(&query).len(), 1);
+}
+
+#[test]
+fn query_parse() {
+    assert_eq!(
+        Query::parse("a b OR c").unwrap().to_string(),
+        "((:::a AND :::b) OR :::c)"
+    );
+    assert_eq!(
+        Query::parse("<file:path:usr/*> OR shell/*:set:pkg.summary:x")
+            .unwrap()
+            .to_string(),
+        "(<:file:path:usr/*> OR shell/*:set:pkg.summary:x)"
+    );
+    assert_eq!(Query::parse("a::b:c:d").unwrap().to_string(), "a::b:c:d");
+    for query in ["", "  ", "a OR", "AND a", "(a", "a)", "<a", "a:", "()"] {
+        assert!(Query::parse(query).is_err(), "{}", query);
+    }
+}
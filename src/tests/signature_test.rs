@@ -0,0 +1,182 @@
+use crate::manifest::Manifest;
+use crate::signature::certificate::CertificateStore;
+use crate::signature::{sign, signed_message, SignaturePolicy};
+
+const ROOT_CA: &str = include_str!("fixtures/signature/root-ca.pem");
+const INTERMEDIATE_CA: &str = include_str!("fixtures/signature/intermediate-ca.pem");
+const SIGNER: &str = include_str!("fixtures/signature/signer.pem");
+const SIGNER_KEY: &str = include_str!("fixtures/signature/signer.key");
+const EXPIRED_SIGNER: &str = include_str!("fixtures/signature/expired-signer.pem");
+const UNTRUSTED_CA: &str = include_str!("fixtures/signature/untrusted-ca.pem");
+const UNTRUSTED_SIGNER: &str = include_str!("fixtures/signature/untrusted-signer.pem");
+const LEAF_ISSUED_SIGNER: &str = include_str!("fixtures/signature/leaf-issued-signer.pem");
+
+const MANIFEST: &str = "set name=pkg.fmri value=pkg://openindiana.org/shell/bash@5.2\n\
+                        set name=pkg.summary value=\"GNU Bourne Again shell\"\n\
+                        file 1234 path=usr/bin/bash mode=0555 owner=root group=bin\n";
+
+#[test]
+fn sign_and_verify() {
+    let mut manifest = Manifest::parse(MANIFEST).unwrap();
+    sign(&mut manifest, SIGNER_KEY, SIGNER, &[INTERMEDIATE_CA]).unwrap();
+
+    let signature = manifest.actions_of_type("signature").next().unwrap();
+    assert_eq!(signature.get_attribute("algorithm").unwrap(), "rsa-sha256");
+    assert_eq!(signature.get_attribute("version").unwrap(), "0");
+    assert_eq!(
+        signature.get_attribute("chain").unwrap(),
+        &crate::payload::sha1_hash(INTERMEDIATE_CA.as_bytes())
+    );
+
+    let mut store = CertificateStore::new();
+    store.add_trust_anchor(ROOT_CA).unwrap();
+    store.add_certificate(INTERMEDIATE_CA).unwrap();
+    store.add_certificate(SIGNER).unwrap();
+    let chain = store.verify_signature(&manifest, signature).unwrap();
+    assert_eq!(chain.get_ref().len(), 3);
+    assert_eq!(chain.common_names()[0], "fmri test signer");
+
+    assert!(store.verify(&manifest, &SignaturePolicy::Verify).is_ok());
+    assert!(store
+        .verify(&manifest, &SignaturePolicy::RequireSignatures)
+        .is_ok());
+    assert!(store
+        .verify(
+            &manifest,
+            &SignaturePolicy::RequireNames(vec!["fmri test signer".to_owned()])
+        )
+        .is_ok());
+    assert!(store
+        .verify(
+            &manifest,
+            &SignaturePolicy::RequireNames(vec!["someone else".to_owned()])
+        )
+        .is_err());
+
+    // signed manifest survives printing and parsing
+    let reparsed = Manifest::parse(&manifest.to_string()).unwrap();
+    assert!(store
+        .verify(&reparsed, &SignaturePolicy::RequireSignatures)
+        .is_ok());
+}
+
+#[test]
+fn tampered_manifest() {
+    let mut manifest = Manifest::parse(MANIFEST).unwrap();
+    sign(&mut manifest, SIGNER_KEY, SIGNER, &[INTERMEDIATE_CA]).unwrap();
+    manifest.add(crate::manifest::action::Action::parse("dir path=usr/evil mode=0777").unwrap());
+
+    let mut store = CertificateStore::new();
+    store.add_trust_anchor(ROOT_CA).unwrap();
+    store.add_certificate(INTERMEDIATE_CA).unwrap();
+    store.add_certificate(SIGNER).unwrap();
+    assert!(store.verify(&manifest, &SignaturePolicy::Verify).is_err());
+    assert!(store.verify(&manifest, &SignaturePolicy::Ignore).is_ok());
+}
+
+#[test]
+fn missing_chain_certificate() {
+    let mut manifest = Manifest::parse(MANIFEST).unwrap();
+    sign(&mut manifest, SIGNER_KEY, SIGNER, &[]).unwrap();
+    let mut store = CertificateStore::new();
+    store.add_trust_anchor(ROOT_CA).unwrap();
+    store.add_certificate(INTERMEDIATE_CA).unwrap();
+    store.add_certificate(SIGNER).unwrap();
+    assert!(store.verify(&manifest, &SignaturePolicy::Verify).is_err());
+
+    let mut manifest = Manifest::parse(MANIFEST).unwrap();
+    sign(&mut manifest, SIGNER_KEY, SIGNER, &[INTERMEDIATE_CA]).unwrap();
+    let mut store = CertificateStore::new();
+    store.add_trust_anchor(ROOT_CA).unwrap();
+    store.add_certificate(SIGNER).unwrap();
+    assert!(store.verify(&manifest, &SignaturePolicy::Verify).is_err());
+}
+
+#[test]
+fn invalid_chains() {
+    let mut store = CertificateStore::new();
+    store.add_trust_anchor(ROOT_CA).unwrap();
+    for certificate in [
+        INTERMEDIATE_CA,
+        SIGNER,
+        EXPIRED_SIGNER,
+        UNTRUSTED_CA,
+        UNTRUSTED_SIGNER,
+        LEAF_ISSUED_SIGNER,
+    ] {
+        store.add_certificate(certificate).unwrap();
+    }
+
+    for (certificate, chain) in [
+        (UNTRUSTED_SIGNER, vec![UNTRUSTED_CA]),
+        (EXPIRED_SIGNER, vec![INTERMEDIATE_CA]),
+        (LEAF_ISSUED_SIGNER, vec![SIGNER, INTERMEDIATE_CA]),
+    ] {
+        let mut manifest = Manifest::parse(MANIFEST).unwrap();
+        sign(&mut manifest, SIGNER_KEY, certificate, &chain).unwrap();
+        assert!(store.verify(&manifest, &SignaturePolicy::Verify).is_err());
+    }
+}
+
+#[test]
+fn key_mismatch() {
+    let mut manifest = Manifest::parse(MANIFEST).unwrap();
+    assert!(sign(&mut manifest, SIGNER_KEY, ROOT_CA, &[]).is_err());
+    assert!(sign(&mut manifest, "invalid", SIGNER, &[]).is_err());
+}
+
+#[test]
+fn hash_only_signature() {
+    use rsa::sha2::{Digest, Sha256};
+
+    let mut manifest = Manifest::parse(MANIFEST).unwrap();
+    let mut signature =
+        crate::manifest::action::Action::parse("signature algorithm=sha256 value=x version=0")
+            .unwrap();
+    let digest = Sha256::digest(signed_message(&manifest, &signature).as_bytes());
+    signature.set_attribute("value".to_owned(), hex::encode(digest));
+    manifest.add(signature);
+
+    let store = CertificateStore::new();
+    assert!(store.verify(&manifest, &SignaturePolicy::Verify).is_ok());
+    assert!(store
+        .verify(&manifest, &SignaturePolicy::RequireSignatures)
+        .is_err());
+
+    manifest.add(crate::manifest::action::Action::parse("dir path=usr mode=0755").unwrap());
+    assert!(store.verify(&manifest, &SignaturePolicy::Verify).is_err());
+}
+
+#[test]
+fn message() {
+    let manifest = Manifest::parse(MANIFEST).unwrap();
+    let signature =
+        crate::manifest::action::Action::parse("signature 5678 algorithm=sha256 value=abcd")
+            .unwrap();
+    assert_eq!(
+        signed_message(&manifest, &signature),
+        "file 1234 group=bin mode=0555 owner=root path=usr/bin/bash\n\
+         set name=pkg.fmri value=pkg://openindiana.org/shell/bash@5.2\n\
+         set name=pkg.summary value=\"GNU Bourne Again shell\"\n\
+         signature 5678 algorithm=sha256 value=\"\""
+    );
+}
+
+#[test]
+fn policy() {
+    assert_eq!(
+        SignaturePolicy::parse("ignore", vec![]).unwrap(),
+        SignaturePolicy::Ignore
+    );
+    assert_eq!(
+        SignaturePolicy::parse("require-signatures", vec![]).unwrap(),
+        SignaturePolicy::RequireSignatures
+    );
+    assert_eq!(
+        SignaturePolicy::parse("require-names", vec!["a".to_owned()]).unwrap(),
+        SignaturePolicy::RequireNames(vec!["a".to_owned()])
+    );
+    assert!(SignaturePolicy::parse("require-names", vec![]).is_err());
+    assert!(SignaturePolicy::parse("paranoid", vec![]).is_err());
+    assert_eq!(SignaturePolicy::default(), SignaturePolicy::Verify);
+}
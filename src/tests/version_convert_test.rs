@@ -0,0 +1,255 @@
+use std::cmp::Ordering;
+
+use crate::version::{
+    convert::{cmp_debian, cmp_rpm},
+    Version,
+};
+
+/// Versions sorted by pkg(5), all with build and branch version
+const SORTED: [&str; 10] = [
+    "0.5.11,5.11-2023.0.0.1",
+    "1,5.11-2023.0.0.1",
+    "1.2,5.11-0",
+    "1.2,5.11-2022.0.0.5",
+    "1.2,5.11-2023.0.0.0",
+    "1.2.0,5.11-1",
+    "1.2.0.1,5.11-0",
+    "1.2.9,5.11-2023.0.0.0:20230901T080000Z",
+    "1.2.10,5.11-0",
+    "2023.10.1,5.12-0",
+];
+
+fn version(version: &str) -> Version {
+    Version::new(version.to_owned()).unwrap()
+}
+
+/// Checks that ordering of every pair of versions is the same after conversion
+fn assert_ordering_preserved(
+    versions: &[&str],
+    convert: impl Fn(&Version) -> String,
+    cmp: fn(&str, &str) -> Ordering,
+) {
+    for a in versions {
+        for b in versions {
+            let (a, b) = (version(a), version(b));
+            assert_eq!(
+                a.cmp(&b),
+                cmp(&convert(&a), &convert(&b)),
+                "{} and {}",
+                a,
+                b
+            );
+        }
+    }
+}
+
+#[test]
+fn debian_round_trip() {
+    for (raw_version, debian) in [
+        ("1.2", "1.2"),
+        ("1.2,5.11", "1.2+5.11"),
+        ("1.2-2023.0.0.1", "1.2-2023.0.0.1"),
+        ("0.5.11,5.11-2023.0.0.1", "0.5.11+5.11-2023.0.0.1"),
+    ] {
+        assert_eq!(version(raw_version).to_debian(), debian);
+        assert_eq!(Version::from_debian(debian).unwrap(), version(raw_version));
+    }
+
+    // timestamp is dropped
+    assert_eq!(version("1.2-1:20230901T080000Z").to_debian(), "1.2-1");
+    assert_eq!(Version::from_debian("0:1.2-1").unwrap(), version("1.2-1"));
+    for invalid in ["1:1.2", "1.2~rc1", "2.36-9+deb12u1", "1.2+", "1.02"] {
+        assert!(Version::from_debian(invalid).is_err(), "{}", invalid);
+    }
+}
+
+#[test]
+fn rpm_round_trip() {
+    for (raw_version, evr) in [
+        ("1.2", "1.2"),
+        ("1.2-2023.0.0.1", "1.2-2023.0.0.1"),
+        ("0.5.11-2023.0.0.1", "0.5.11-2023.0.0.1"),
+    ] {
+        assert_eq!(version(raw_version).to_rpm_evr(), evr);
+        assert_eq!(Version::from_rpm_evr(evr).unwrap(), version(raw_version));
+    }
+
+    // build version and timestamp are dropped
+    assert_eq!(version("1.2,5.11-1:20230901T080000Z").to_rpm_evr(), "1.2-1");
+    for invalid in ["1:1.2", "1.2-1.fc39", "1.2^git1", "1.2-"] {
+        assert!(Version::from_rpm_evr(invalid).is_err(), "{}", invalid);
+    }
+}
+
+#[test]
+fn cmp_debian_like_dpkg() {
+    // results of dpkg --compare-versions
+    for (a, b, ordering) in [
+        ("1.2~rc1", "1.2", Ordering::Less),
+        ("1.2+5.11", "1.2.1", Ordering::Less),
+        ("1.2", "1.2.0", Ordering::Less),
+        ("1.2-1", "1.2+5.11-0", Ordering::Less),
+        ("1:0.1", "2.0", Ordering::Greater),
+        ("1.2-0", "1.2", Ordering::Equal),
+        ("1.2a", "1.2.0", Ordering::Less),
+        ("1.10", "1.9", Ordering::Greater),
+        ("1.002", "1.2", Ordering::Equal),
+        ("2.36-9+deb12u1", "2.36-9", Ordering::Greater),
+    ] {
+        assert_eq!(cmp_debian(a, b), ordering, "{} and {}", a, b);
+        assert_eq!(cmp_debian(b, a), ordering.reverse(), "{} and {}", b, a);
+    }
+}
+
+#[test]
+fn cmp_rpm_like_rpmvercmp() {
+    for (a, b, ordering) in [
+        ("1.2~rc1", "1.2", Ordering::Less),
+        ("1.2^git1", "1.2", Ordering::Greater),
+        ("1.2^git1", "1.2.1", Ordering::Less),
+        ("1.2", "1.2.0", Ordering::Less),
+        ("1.2-1", "1.2.0-0", Ordering::Less),
+        ("1.2", "1.2-1", Ordering::Less),
+        ("1:0.1", "2.0", Ordering::Greater),
+        ("1.10", "1.9", Ordering::Greater),
+        ("1.002", "1.2", Ordering::Equal),
+        ("1.a", "1.1", Ordering::Less),
+        ("1_2", "1.2", Ordering::Equal),
+        ("1.2-1.fc39", "1.2-1.fc38", Ordering::Greater),
+    ] {
+        assert_eq!(cmp_rpm(a, b), ordering, "{} and {}", a, b);
+        assert_eq!(cmp_rpm(b, a), ordering.reverse(), "{} and {}", b, a);
+    }
+}
+
+#[test]
+fn debian_ordering_compatibility() {
+    // preserved if build versions are the same
+    assert_ordering_preserved(&SORTED, Version::to_debian, cmp_debian);
+    assert_ordering_preserved(
+        &[
+            "1.2",
+            "1.2-1",
+            "1.2.0-0",
+            "1.2.0-2023.0.0.0",
+            "1.10-2023.0.0.0",
+        ],
+        Version::to_debian,
+        cmp_debian,
+    );
+
+    // pkg(5) compares branch version before build version, Debian after
+    let (a, b) = (version("1.2-1"), version("1.2,5.11-0"));
+    assert_eq!(a.cmp(&b), Ordering::Greater);
+    assert_eq!(cmp_debian(&a.to_debian(), &b.to_debian()), Ordering::Less);
+
+    // timestamp is dropped
+    let (a, b) = (
+        version("1.2-1:20230101T000000Z"),
+        version("1.2-1:20230201T000000Z"),
+    );
+    assert_eq!(a.cmp(&b), Ordering::Less);
+    assert_eq!(cmp_debian(&a.to_debian(), &b.to_debian()), Ordering::Equal);
+}
+
+#[test]
+fn rpm_ordering_compatibility() {
+    // preserved if build versions are the same
+    assert_ordering_preserved(&SORTED, Version::to_rpm_evr, cmp_rpm);
+    assert_ordering_preserved(
+        &[
+            "1.2",
+            "1.2-1",
+            "1.2.0-0",
+            "1.2.0-2023.0.0.0",
+            "1.10-2023.0.0.0",
+        ],
+        Version::to_rpm_evr,
+        cmp_rpm,
+    );
+
+    // build version and timestamp are dropped
+    for (a, b) in [
+        ("1.2,5.11-1", "1.2,5.12-1"),
+        ("1.2-1:20230101T000000Z", "1.2-1:20230201T000000Z"),
+    ] {
+        let (a, b) = (version(a), version(b));
+        assert_eq!(a.cmp(&b), Ordering::Less);
+        assert_eq!(cmp_rpm(&a.to_rpm_evr(), &b.to_rpm_evr()), Ordering::Equal);
+    }
+}
+
+#[cfg(feature = "semver")]
+#[test]
+fn semver_round_trip() {
+    for (raw_version, semver) in [
+        ("1.2.3", "1.2.3"),
+        ("1.2", "1.2.0+component.1.2"),
+        ("1.2.3.4", "1.2.3+component.1.2.3.4"),
+        (
+            "0.5.11,5.11-2023.0.0.1:20230901T080000Z",
+            "0.5.11+build.5.11.branch.2023.0.0.1.timestamp.20230901T080000Z",
+        ),
+        // lenient timestamps
+        ("1.0.0:abc", "1.0.0+timestamp.abc"),
+        ("1.0.0:build.1", "1.0.0+timestamp.build.1"),
+    ] {
+        let version = version(raw_version);
+        assert_eq!(version.to_semver().unwrap().to_string(), semver);
+        let parsed = Version::from_semver(&semver::Version::parse(semver).unwrap()).unwrap();
+        assert_eq!(parsed.to_string(), version.to_string());
+    }
+
+    for invalid in [
+        "1.2.3-rc.1",
+        "1.2.3+git.abcdef",
+        "1.2.3+component.1.3",
+        "1.2.3+timestamp",
+    ] {
+        let semver = semver::Version::parse(invalid).unwrap();
+        assert!(Version::from_semver(&semver).is_err(), "{}", invalid);
+    }
+
+    for lenient in ["1.0:a_b", "1.0:a..b", "1.0:.a"] {
+        assert!(version(lenient).to_semver().is_err(), "{}", lenient);
+    }
+}
+
+#[cfg(feature = "semver")]
+#[test]
+fn semver_ordering_compatibility() {
+    // precedence never contradicts pkg(5), but can make versions equal
+    for a in SORTED {
+        for b in SORTED {
+            let (a, b) = (version(a), version(b));
+            let precedence = a
+                .to_semver()
+                .unwrap()
+                .cmp_precedence(&b.to_semver().unwrap());
+            assert!(
+                precedence == a.cmp(&b) || precedence == Ordering::Equal,
+                "{} and {}",
+                a,
+                b
+            );
+        }
+    }
+
+    for (a, b) in [
+        // only the first three numbers of component version
+        ("1.2.0.1", "1.2.0.2"),
+        // length of component version
+        ("1.2", "1.2.0"),
+        // build and branch versions
+        ("1.2.0,5.11-1", "1.2.0,5.12-2"),
+    ] {
+        let (a, b) = (version(a), version(b));
+        assert_eq!(a.cmp(&b), Ordering::Less);
+        assert_eq!(
+            a.to_semver()
+                .unwrap()
+                .cmp_precedence(&b.to_semver().unwrap()),
+            Ordering::Equal
+        );
+    }
+}
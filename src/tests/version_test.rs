@@ -1,4 +1,5 @@
-use crate::version::segment::Segment;
+use crate::version::builder::VersionBuilder;
+use crate::version::segment::{Segment, MAX_POSITION};
 use crate::version::segments::Segments;
 use crate::version::Version;
 
@@ -168,3 +169,165 @@ fn parse_version_from_raw_fmri() {
             .unwrap();
     assert_eq!(version, None);
 }
+
+#[test]
+fn segment_arithmetic() {
+    let mut segment = Segment::try_from("1.2.3").unwrap();
+    segment.increment(1).unwrap();
+    assert_eq!(segment.as_string(), "1.3.0");
+    segment.zero(0).unwrap();
+    assert_eq!(segment.as_string(), "0.3.0");
+    segment.set(4, 7).unwrap();
+    assert_eq!(segment.as_string(), "0.3.0.0.7");
+    segment.truncate(2);
+    assert_eq!(segment.as_string(), "0.3");
+    segment.truncate(0);
+    assert_eq!(segment.as_string(), "0");
+
+    let mut segment = Segment::new(vec![1, u64::MAX, 2]).unwrap();
+    assert!(segment.increment(1).is_err());
+    assert_eq!(segment, Segment::new(vec![1, u64::MAX, 2]).unwrap());
+
+    // positions far after the end aren't filled with zeros
+    for position in [MAX_POSITION + 1, usize::MAX] {
+        assert!(segment.set(position, 1).is_err());
+        assert!(segment.zero(position).is_err());
+        assert!(segment.increment(position).is_err());
+    }
+    assert_eq!(segment, Segment::new(vec![1, u64::MAX, 2]).unwrap());
+    segment.set(MAX_POSITION, 1).unwrap();
+    assert_eq!(segment.get_ref().len(), MAX_POSITION + 1);
+
+    assert!(Segment::new(vec![]).is_err());
+    assert_eq!(Segment::new(vec![5, 11]).unwrap().as_string(), "5.11");
+}
+
+#[test]
+fn version_setters() {
+    let mut version = Version::new("2.1.1,5.11-2017.0.0.0:20171212T185746Z".to_owned()).unwrap();
+    version.remove_build_version();
+    version.remove_timestamp();
+    version.set_component_version(Segment::try_from("2.2").unwrap());
+    version
+        .get_branch_version_segment_mut()
+        .unwrap()
+        .increment(0)
+        .unwrap();
+    assert_eq!(format!("{}", version), "@2.2-2018.0.0.0");
+
+    assert!(version.set_timestamp("2017-12-12".to_owned()).is_err());
+    version
+        .set_timestamp("20180101T000000Z".to_owned())
+        .unwrap();
+    version.set_build_version(Segment::try_from("5.11").unwrap());
+    assert_eq!(
+        format!("{}", version),
+        "@2.2,5.11-2018.0.0.0:20180101T000000Z"
+    );
+    assert!(version.get_build_version_segment_mut().is_some());
+
+    version.remove_branch_version();
+    assert!(version.get_branch_version_segment_mut().is_none());
+}
+
+#[test]
+fn version_builder() {
+    let version = Version::new("2.1.1,5.11-2017.0.0.0:20171212T185746Z".to_owned()).unwrap();
+    assert_eq!(
+        VersionBuilder::from(version.clone()).build().unwrap(),
+        version
+    );
+    assert_eq!(
+        Version::builder()
+            .component_version(Segment::try_from("1").unwrap())
+            .branch_version(Segment::try_from("2").unwrap())
+            .build()
+            .unwrap(),
+        Version::new("1-2".to_owned()).unwrap()
+    );
+    assert!(Version::builder().build().is_err());
+    assert!(Version::builder()
+        .component_version(Segment::try_from("1").unwrap())
+        .timestamp("now".to_owned())
+        .build()
+        .is_err());
+}
+
+#[test]
+fn dot_sequence() {
+    assert_eq!(
+        Segment::try_from("20231015120000.0").unwrap().get(),
+        vec![20231015120000, 0]
+    );
+    assert_eq!(
+        Segment::try_from("18446744073709551615").unwrap().get(),
+        vec![u64::MAX]
+    );
+    assert_eq!(Segment::try_from("0.10").unwrap().get(), vec![0, 10]);
+
+    assert_eq!(
+        Segment::try_from("1.01").unwrap_err(),
+        "invalid dot sequence \"1.01\": leading zero at index 2"
+    );
+    assert_eq!(
+        Segment::try_from("1..2").unwrap_err(),
+        "invalid dot sequence \"1..2\": empty number at index 2"
+    );
+    assert_eq!(
+        Segment::try_from("1.2.").unwrap_err(),
+        "invalid dot sequence \"1.2.\": empty number at index 4"
+    );
+    assert_eq!(
+        Segment::try_from(".1").unwrap_err(),
+        "invalid dot sequence \".1\": empty number at index 0"
+    );
+    assert_eq!(
+        Segment::try_from("").unwrap_err(),
+        "invalid dot sequence \"\": empty number at index 0"
+    );
+    assert_eq!(
+        Segment::try_from("3.a.4").unwrap_err(),
+        "invalid dot sequence \"3.a.4\": invalid character 'a' at index 2"
+    );
+    assert_eq!(
+        Segment::try_from("18446744073709551616").unwrap_err(),
+        "invalid dot sequence \"18446744073709551616\": number too large at index 0"
+    );
+}
+
+#[test]
+fn version_error_position() {
+    assert_eq!(
+        Version::new("1.2-2023.01".to_owned()).unwrap_err(),
+        "invalid branch version in \"@1.2-2023.01\": leading zero at index 10"
+    );
+    assert_eq!(
+        Version::new("1.2abc".to_owned()).unwrap_err(),
+        "invalid component version in \"@1.2abc\": invalid character 'a' at index 4"
+    );
+    assert!(Version::new("1.2.-2023".to_owned()).is_err());
+}
+
+#[test]
+fn version_is_successor() {
+    let version = |raw: &str| Version::new(raw.to_owned()).unwrap();
+    let candidate = version("5.2.1,5.11-2023.0.0.3:20230101T000000Z");
+
+    assert!(candidate.is_successor(&version("5")));
+    assert!(candidate.is_successor(&version("5.2")));
+    assert!(candidate.is_successor(&version("5.2.1-2023")));
+    assert!(candidate.is_successor(&version("5.2.1,5.11-2023.0.0.3")));
+    assert!(candidate.is_successor(&version("5.2.1-2023.0.0.3:20230101T000000Z")));
+    // build version is ignored
+    assert!(candidate.is_successor(&version("5.2.1,5.10-2023.0.0.3")));
+    assert!(candidate.is_successor(&version("5.2.1,5.12")));
+    assert!(version("5.2.1-2023").is_successor(&version("5.2,5.11")));
+
+    assert!(!candidate.is_successor(&version("5.3")));
+    assert!(!candidate.is_successor(&version("5.2.1.0")));
+    // component version must be equal when branch is incorporated
+    assert!(!candidate.is_successor(&version("5.2-2023")));
+    assert!(!candidate.is_successor(&version("5.2.1-2023.1")));
+    assert!(!candidate.is_successor(&version("5.2.1-2023.0.0.3:20230102T000000Z")));
+    assert!(!version("5.2.1").is_successor(&version("5.2.1-2023")));
+}
@@ -1,10 +1,28 @@
-use std::cmp::Ordering;
-use std::fmt::{Debug, Display, Formatter};
+use alloc::{
+    borrow::ToOwned,
+    format,
+    string::{String, ToString},
+};
+use core::cmp::Ordering;
+use core::fmt::{Debug, Display, Formatter};
 
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::{FMRI, version::segments::Segments};
+#[cfg(doc)]
+use crate::FMRI;
+use crate::{
+    helpers::check_timestamp,
+    version::{
+        builder::VersionBuilder,
+        segment::{parse_dot_sequence, Segment},
+        segments::Segments,
+    },
+    ParseOptions,
+};
 
+pub mod builder;
+pub mod convert;
 pub mod segment;
 pub mod segments;
 
@@ -21,7 +39,8 @@ pub mod segments;
 /// ```
 ///
 /// `* = continues package name`
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[derive(Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Version {
     component_version: Segments,
     /// Build_version is optional
@@ -51,6 +70,57 @@ impl Version {
         })
     }
 
+    /// Parses [`Version`] using given [`ParseOptions`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmri::{ParseOptions, version::Version};
+    /// assert!(Version::parse_with_options("1.2-2023.0.0.1", ParseOptions::strict()).is_ok());
+    /// assert!(Version::parse_with_options("1.2:2023", ParseOptions::strict()).is_err());
+    /// ```
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if one of the segments is invalid
+    pub fn parse_with_options(version: &str, options: ParseOptions) -> Result<Self, String> {
+        if !options.is_strict() {
+            return Self::new(version.to_owned());
+        }
+
+        let version = format!("@{}", version.strip_prefix('@').unwrap_or(version));
+        let (rest, timestamp) = split_once_at(&version, ':');
+        let (rest, branch) = split_once_at(rest, '-');
+        let (release, build) = split_once_at(rest, ',');
+
+        let segment = |name: &str, (start, part): (usize, &str)| {
+            parse_dot_sequence(part).map_err(|(index, reason)| {
+                format!(
+                    "invalid {} in \"{}\": {} at index {}",
+                    name,
+                    version,
+                    reason,
+                    start + index
+                )
+            })
+        };
+
+        let mut builder =
+            Self::builder().component_version(segment("component version", (1, &release[1..]))?);
+        if let Some(build) = build {
+            builder = builder.build_version(segment("build version", build)?);
+        }
+        if let Some(branch) = branch {
+            builder = builder.branch_version(segment("branch version", branch)?);
+        }
+        if let Some((_, timestamp)) = timestamp {
+            builder = builder.timestamp(timestamp.to_owned());
+        }
+        builder
+            .build()
+            .map_err(|e| format!("invalid version \"{}\": {}", version, e))
+    }
+
     /// Parses [`Version`] from raw [`FMRI`]
     ///
     /// # Examples
@@ -81,6 +151,193 @@ impl Version {
             }
         }
     }
+
+    pub fn get_component_version_ref(&self) -> &Segments {
+        &self.component_version
+    }
+
+    /// Returns [`Segments::None`] if there isn't build version
+    pub fn get_build_version_ref(&self) -> &Segments {
+        &self.build_version
+    }
+
+    /// Returns [`Segments::None`] if there isn't branch version
+    pub fn get_branch_version_ref(&self) -> &Segments {
+        &self.branch_version
+    }
+
+    /// Returns [`Segments::None`] if there isn't timestamp
+    pub fn get_timestamp_ref(&self) -> &Segments {
+        &self.timestamp
+    }
+
+    /// Returns [`VersionBuilder`] for constructing [`Version`] field by field
+    pub fn builder() -> VersionBuilder {
+        VersionBuilder::new()
+    }
+
+    pub fn get_component_version_segment_mut(&mut self) -> Option<&mut Segment> {
+        match &mut self.component_version {
+            Segments::ComponentVersion(segment) => Some(segment),
+            _ => None,
+        }
+    }
+
+    /// Returns [`None`] if there isn't build version
+    pub fn get_build_version_segment_mut(&mut self) -> Option<&mut Segment> {
+        match &mut self.build_version {
+            Segments::BuildVersion(segment) => Some(segment),
+            _ => None,
+        }
+    }
+
+    /// Returns [`None`] if there isn't branch version
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmri::version::Version;
+    /// let mut version = Version::new("1.2-2023.0.0.5".to_owned()).unwrap();
+    /// version.get_branch_version_segment_mut().unwrap().increment(3).unwrap();
+    /// assert_eq!(version, Version::new("1.2-2023.0.0.6".to_owned()).unwrap());
+    /// ```
+    pub fn get_branch_version_segment_mut(&mut self) -> Option<&mut Segment> {
+        match &mut self.branch_version {
+            Segments::BranchVersion(segment) => Some(segment),
+            _ => None,
+        }
+    }
+
+    pub fn set_component_version(&mut self, segment: Segment) {
+        self.component_version = Segments::ComponentVersion(segment)
+    }
+
+    pub fn set_build_version(&mut self, segment: Segment) {
+        self.build_version = Segments::BuildVersion(segment)
+    }
+
+    pub fn remove_build_version(&mut self) {
+        self.build_version = Segments::None
+    }
+
+    pub fn set_branch_version(&mut self, segment: Segment) {
+        self.branch_version = Segments::BranchVersion(segment)
+    }
+
+    pub fn remove_branch_version(&mut self) {
+        self.branch_version = Segments::None
+    }
+
+    /// Sets timestamp in format "20171212T185746Z"
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if timestamp is invalid
+    pub fn set_timestamp(&mut self, timestamp: String) -> Result<(), String> {
+        check_timestamp(&timestamp)?;
+        self.timestamp = Segments::Timestamp(timestamp);
+        Ok(())
+    }
+
+    /// Compares component, branch and build version, missing ones are older
+    ///
+    /// Build version is compared after branch version, like pkg(5) does. Timestamp doesn't
+    /// determine if the package is newer, so it's ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use fmri::version::Version;
+    /// let version = |version: &str| Version::new(version.to_owned()).unwrap();
+    /// assert_eq!(version("1.2-1").cmp_without_timestamp(&version("1.2,5.11-0")), Ordering::Greater);
+    /// assert_eq!(version("1.2").cmp_without_timestamp(&version("1.2-0")), Ordering::Less);
+    /// assert_eq!(
+    ///     version("1.2-1:20230101T000000Z").cmp_without_timestamp(&version("1.2-1:20230201T000000Z")),
+    ///     Ordering::Equal
+    /// );
+    /// ```
+    pub fn cmp_without_timestamp(&self, other: &Self) -> Ordering {
+        [
+            (&self.component_version, &other.component_version),
+            (&self.branch_version, &other.branch_version),
+            (&self.build_version, &other.build_version),
+        ]
+        .iter()
+        .map(|(own, other)| own.get_segment_ref().cmp(&other.get_segment_ref()))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+    }
+
+    /// Sets timestamp without checking, lenient parsing accepts any timestamp
+    pub(crate) fn set_timestamp_unchecked(&mut self, timestamp: String) {
+        self.timestamp = Segments::Timestamp(timestamp)
+    }
+
+    pub fn remove_timestamp(&mut self) {
+        self.timestamp = Segments::None
+    }
+
+    /// Checks if [`Version`] satisfies incorporated version, like pkg(5) does
+    ///
+    /// Only parts present in incorporated version are checked. Build version is ignored like in
+    /// pkg(5). The last present part (of component and branch version) is matched as prefix,
+    /// component version must be equal if branch is present and timestamp must be equal if it is
+    /// present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmri::version::Version;
+    /// let incorporated = Version::new("5.2-2023.0".to_owned()).unwrap();
+    /// assert!(Version::new("5.2-2023.0.0.1".to_owned()).unwrap().is_successor(&incorporated));
+    /// assert!(!Version::new("5.2.1-2023.0.0.1".to_owned()).unwrap().is_successor(&incorporated));
+    /// assert!(Version::new("5.2.1".to_owned()).unwrap().is_successor(&Version::new("5".to_owned()).unwrap()));
+    /// assert!(Version::new("1.0,5.12".to_owned()).unwrap().is_successor(&Version::new("1.0,5.11".to_owned()).unwrap()));
+    /// ```
+    pub fn is_successor(&self, incorporated: &Version) -> bool {
+        let parts = [
+            (&self.component_version, &incorporated.component_version),
+            (&self.branch_version, &incorporated.branch_version),
+        ];
+        let last = parts
+            .iter()
+            .rposition(|(_, incorporated)| **incorporated != Segments::None);
+
+        let segments_match =
+            parts
+                .iter()
+                .enumerate()
+                .all(|(position, (own, incorporated))| {
+                    match (own.get_segment_ref(), incorporated.get_segment_ref()) {
+                        (_, None) => true,
+                        (None, Some(_)) => false,
+                        (Some(own), Some(incorporated)) => match Some(position) == last {
+                            true => incorporated.is_prefix_of(own),
+                            false => incorporated == own,
+                        },
+                    }
+                });
+
+        segments_match
+            && match &incorporated.timestamp {
+                Segments::Timestamp(timestamp) => {
+                    matches!(&self.timestamp, Segments::Timestamp(own) if own == timestamp)
+                }
+                _ => true,
+            }
+    }
+}
+
+/// Splits string at the first separator, returns part before and index with part after
+fn split_once_at(string: &str, separator: char) -> (&str, Option<(usize, &str)>) {
+    match string.find(separator) {
+        None => (string, None),
+        Some(position) => (
+            &string[..position],
+            Some((position + 1, &string[position + 1..])),
+        ),
+    }
 }
 
 impl PartialOrd<Self> for Version {
@@ -90,41 +347,22 @@ impl PartialOrd<Self> for Version {
 }
 
 impl Ord for Version {
+    /// Compares versions like [`Version::cmp_without_timestamp`], same versions are decided by
+    /// timestamp, so the ordering is total and consistent with equality
     fn cmp(&self, other: &Self) -> Ordering {
-        if let (
-            Segments::ComponentVersion(self_segment),
-            Segments::ComponentVersion(other_segment),
-        ) = (&self.component_version, &other.component_version)
-        {
-            if self_segment != other_segment {
-                return self_segment.cmp(other_segment);
-            }
-        }
-
-        if let (Segments::BuildVersion(self_segment), Segments::BuildVersion(other_segment)) =
-            (&self.build_version, &other.build_version)
-        {
-            if self_segment != other_segment {
-                return self_segment.cmp(other_segment);
+        fn timestamp(version: &Version) -> Option<&String> {
+            match &version.timestamp {
+                Segments::Timestamp(timestamp) => Some(timestamp),
+                _ => None,
             }
         }
-
-        if let (Segments::BranchVersion(self_segment), Segments::BranchVersion(other_segment)) =
-            (&self.branch_version, &other.branch_version)
-        {
-            if self_segment != other_segment {
-                return self_segment.cmp(other_segment);
-            }
-        }
-
-        // ignoring timestamp because timestamp does not determine if the package is newer
-
-        Ordering::Equal
+        self.cmp_without_timestamp(other)
+            .then_with(|| timestamp(self).cmp(&timestamp(other)))
     }
 }
 
 impl Display for Version {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         let string: &mut String = &mut "".to_string();
 
         if let Segments::ComponentVersion(segment) = &self.component_version {
@@ -152,7 +390,7 @@ impl Display for Version {
 }
 
 impl Debug for Version {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self)
     }
 }
@@ -0,0 +1,102 @@
+use alloc::string::String;
+
+use crate::{
+    helpers::check_timestamp,
+    version::{segment::Segment, segments::Segments, Version},
+};
+
+/// [`VersionBuilder`] constructs [`Version`] field by field
+///
+/// # Examples
+///
+/// ```
+/// use fmri::version::{segment::Segment, Version};
+/// let version = Version::builder()
+///     .component_version(Segment::try_from("2.1.1").unwrap())
+///     .build_version(Segment::try_from("5.11").unwrap())
+///     .branch_version(Segment::try_from("2017.0.0.0").unwrap())
+///     .timestamp("20171212T185746Z".to_owned())
+///     .build()
+///     .unwrap();
+/// assert_eq!(format!("{}", version), "@2.1.1,5.11-2017.0.0.0:20171212T185746Z");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VersionBuilder {
+    component_version: Option<Segment>,
+    build_version: Option<Segment>,
+    branch_version: Option<Segment>,
+    timestamp: Option<String>,
+}
+
+impl VersionBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn component_version(mut self, segment: Segment) -> Self {
+        self.component_version = Some(segment);
+        self
+    }
+
+    pub fn build_version(mut self, segment: Segment) -> Self {
+        self.build_version = Some(segment);
+        self
+    }
+
+    pub fn branch_version(mut self, segment: Segment) -> Self {
+        self.branch_version = Some(segment);
+        self
+    }
+
+    /// Timestamp in format "20171212T185746Z"
+    pub fn timestamp(mut self, timestamp: String) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Returns [`Version`]
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if component version is missing or timestamp is invalid
+    pub fn build(self) -> Result<Version, String> {
+        let component_version = self
+            .component_version
+            .ok_or("component version is required")?;
+        if let Some(timestamp) = &self.timestamp {
+            check_timestamp(timestamp)?;
+        }
+
+        Ok(Version {
+            component_version: Segments::ComponentVersion(component_version),
+            build_version: self
+                .build_version
+                .map_or(Segments::None, Segments::BuildVersion),
+            branch_version: self
+                .branch_version
+                .map_or(Segments::None, Segments::BranchVersion),
+            timestamp: self.timestamp.map_or(Segments::None, Segments::Timestamp),
+        })
+    }
+}
+
+impl From<Version> for VersionBuilder {
+    fn from(value: Version) -> Self {
+        let segment = |segments: Segments| match segments {
+            Segments::ComponentVersion(segment)
+            | Segments::BuildVersion(segment)
+            | Segments::BranchVersion(segment) => Some(segment),
+            _ => None,
+        };
+
+        Self {
+            component_version: segment(value.component_version),
+            build_version: segment(value.build_version),
+            branch_version: segment(value.branch_version),
+            timestamp: match value.timestamp {
+                Segments::Timestamp(timestamp) => Some(timestamp),
+                _ => None,
+            },
+        }
+    }
+}
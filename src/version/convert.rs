@@ -0,0 +1,470 @@
+#[cfg(feature = "semver")]
+use alloc::{borrow::ToOwned, vec, vec::Vec};
+use alloc::{format, string::String};
+use core::cmp::Ordering;
+
+use crate::version::{segment::Segment, segments::Segments, Version};
+
+impl Version {
+    /// Returns Debian version `upstream[-revision]` of [`Version`]
+    ///
+    /// Component version is upstream version, build version is appended after '+' and branch
+    /// version is revision. Timestamp is dropped.
+    ///
+    /// Ordering by [`cmp_debian`] is the same as ordering of [`Version`] if build versions are the
+    /// same, Debian compares build version before revision. Versions differing only in
+    /// timestamp are equal in Debian.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmri::version::Version;
+    /// let version = Version::new("2.1.1,5.11-2023.0.0.1:20230901T080000Z".to_owned()).unwrap();
+    /// assert_eq!(version.to_debian(), "2.1.1+5.11-2023.0.0.1");
+    /// ```
+    pub fn to_debian(&self) -> String {
+        let mut string = segment_string(&self.component_version);
+        if let Segments::BuildVersion(segment) = &self.build_version {
+            string.push('+');
+            string.push_str(&segment.as_string());
+        }
+        if let Segments::BranchVersion(segment) = &self.branch_version {
+            string.push('-');
+            string.push_str(&segment.as_string());
+        }
+        string
+    }
+
+    /// Returns [`Version`] from Debian version made by [`Version::to_debian`]
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if version has non-zero epoch or its parts aren't dot
+    /// sequences, like "1.2~rc1" or "2.36-9+deb12u1"
+    pub fn from_debian(version: &str) -> Result<Self, String> {
+        let error = |e: String| format!("can't map Debian version \"{}\": {}", version, e);
+        let (epoch, upstream, revision) = split_evr(version);
+        check_epoch(epoch).map_err(error)?;
+        let (component, build) = match upstream.split_once('+') {
+            Some((component, build)) => (component, Some(build)),
+            None => (upstream, None),
+        };
+        build_version(component, build, revision).map_err(error)
+    }
+
+    /// Returns RPM `version[-release]` of [`Version`]
+    ///
+    /// Component version is version and branch version is release. Build version and timestamp
+    /// are dropped.
+    ///
+    /// Ordering by [`cmp_rpm`] is the same as ordering of [`Version`] if build versions are the
+    /// same. Versions differing only in timestamp are equal in RPM.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmri::version::Version;
+    /// let version = Version::new("2.1.1,5.11-2023.0.0.1:20230901T080000Z".to_owned()).unwrap();
+    /// assert_eq!(version.to_rpm_evr(), "2.1.1-2023.0.0.1");
+    /// ```
+    pub fn to_rpm_evr(&self) -> String {
+        let mut string = segment_string(&self.component_version);
+        if let Segments::BranchVersion(segment) = &self.branch_version {
+            string.push('-');
+            string.push_str(&segment.as_string());
+        }
+        string
+    }
+
+    /// Returns [`Version`] from RPM `[epoch:]version[-release]`
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if EVR has non-zero epoch or version and release
+    /// aren't dot sequences, like "1.fc39"
+    pub fn from_rpm_evr(evr: &str) -> Result<Self, String> {
+        let error = |e: String| format!("can't map RPM EVR \"{}\": {}", evr, e);
+        let (epoch, version, release) = split_evr(evr);
+        check_epoch(epoch).map_err(error)?;
+        build_version(version, None, release).map_err(error)
+    }
+
+    /// Returns [`semver::Version`] of [`Version`]
+    ///
+    /// Major, minor and patch are the first three numbers of component version, missing numbers
+    /// are 0. Everything else is kept in build metadata, timestamp is the last, so
+    /// [`Version::from_semver`] returns the same [`Version`].
+    ///
+    /// Build metadata doesn't take part in semver precedence, so component versions differing
+    /// only after the third number or in length (1.2 and 1.2.0), branch and build versions and
+    /// timestamps have the same precedence. Other orderings are preserved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmri::version::Version;
+    /// let version = Version::new("2.1.1.4,5.11-2023.0.0.1".to_owned()).unwrap();
+    /// let semver = version.to_semver().unwrap();
+    /// assert_eq!(
+    ///     semver.to_string(),
+    ///     "2.1.1+component.2.1.1.4.build.5.11.branch.2023.0.0.1"
+    /// );
+    /// assert_eq!(Version::from_semver(&semver).unwrap(), version);
+    /// assert!(Version::new("1.0:a_b".to_owned()).unwrap().to_semver().is_err());
+    /// ```
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if timestamp accepted by lenient parsing isn't valid
+    /// build metadata, like "a_b"
+    #[cfg(feature = "semver")]
+    pub fn to_semver(&self) -> Result<semver::Version, String> {
+        let numbers = self
+            .component_version
+            .get_segment_ref()
+            .map(Segment::get_ref)
+            .cloned()
+            .unwrap_or_default();
+        let number = |index: usize| numbers.get(index).copied().unwrap_or(0);
+
+        let mut metadata = vec![];
+        if numbers.len() != 3 {
+            metadata.push(format!(
+                "component.{}",
+                segment_string(&self.component_version)
+            ));
+        }
+        if let Segments::BuildVersion(segment) = &self.build_version {
+            metadata.push(format!("build.{}", segment));
+        }
+        if let Segments::BranchVersion(segment) = &self.branch_version {
+            metadata.push(format!("branch.{}", segment));
+        }
+        if let Segments::Timestamp(timestamp) = &self.timestamp {
+            metadata.push(format!("timestamp.{}", timestamp));
+        }
+
+        let mut version = semver::Version::new(number(0), number(1), number(2));
+        let metadata = metadata.join(".");
+        version.build = semver::BuildMetadata::new(&metadata)
+            .map_err(|e| format!("invalid semver build metadata \"{}\": {}", metadata, e))?;
+        Ok(version)
+    }
+
+    /// Returns [`Version`] from [`semver::Version`]
+    ///
+    /// Build metadata made by [`Version::to_semver`] is restored. Timestamp isn't checked like
+    /// in [`Version::new`], everything after "timestamp" is the timestamp.
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if version has pre-release, which pkg(5) can't order
+    /// before release, or unknown build metadata
+    #[cfg(feature = "semver")]
+    pub fn from_semver(version: &semver::Version) -> Result<Self, String> {
+        let error = |e: String| format!("can't map semver \"{}\": {}", version, e);
+        if !version.pre.is_empty() {
+            return Err(error("pre-release isn't supported".to_owned()));
+        }
+
+        let mut component = vec![version.major, version.minor, version.patch];
+        let mut builder = Version::builder();
+        let mut timestamp = None;
+        let mut fields: Vec<(&str, Vec<&str>)> = vec![];
+        for identifier in version.build.as_str().split('.').filter(|i| !i.is_empty()) {
+            let in_timestamp = matches!(fields.last(), Some(("timestamp", _)));
+            match identifier {
+                "component" | "build" | "branch" | "timestamp" if !in_timestamp => {
+                    fields.push((identifier, vec![]))
+                }
+                _ => match fields.last_mut() {
+                    Some((_, values)) => values.push(identifier),
+                    None => {
+                        return Err(error(format!("unknown build metadata \"{}\"", identifier)))
+                    }
+                },
+            }
+        }
+        for (field, values) in fields {
+            let value = values.join(".");
+            let segment = || Segment::try_from(value.as_str()).map_err(error);
+            match field {
+                "component" => {
+                    let segment = segment()?;
+                    let mut padded = segment.get_ref().clone();
+                    padded.resize(padded.len().max(3), 0);
+                    if padded[..3] != component[..] {
+                        return Err(error(format!(
+                            "component {} doesn't match version",
+                            segment
+                        )));
+                    }
+                    component = segment.get();
+                }
+                "build" => builder = builder.build_version(segment()?),
+                "branch" => builder = builder.branch_version(segment()?),
+                _ if value.is_empty() => return Err(error("timestamp is empty".to_owned())),
+                _ => timestamp = Some(value),
+            }
+        }
+
+        let mut version = builder
+            .component_version(Segment::new(component).map_err(error)?)
+            .build()
+            .map_err(error)?;
+        if let Some(timestamp) = timestamp {
+            version.set_timestamp_unchecked(timestamp);
+        }
+        Ok(version)
+    }
+}
+
+/// Compares Debian versions like `dpkg --compare-versions`
+///
+/// # Examples
+///
+/// ```
+/// use core::cmp::Ordering;
+/// use fmri::version::convert::cmp_debian;
+/// assert_eq!(cmp_debian("1.10-1", "1.9-2"), Ordering::Greater);
+/// assert_eq!(cmp_debian("1.2~rc1", "1.2"), Ordering::Less);
+/// assert_eq!(cmp_debian("1:0.1", "2.0"), Ordering::Greater);
+/// ```
+pub fn cmp_debian(a: &str, b: &str) -> Ordering {
+    let (a_epoch, a_upstream, a_revision) = split_evr(a);
+    let (b_epoch, b_upstream, b_revision) = split_evr(b);
+    cmp_epoch(a_epoch, b_epoch)
+        .then_with(|| cmp_debian_part(a_upstream, b_upstream))
+        .then_with(|| {
+            cmp_debian_part(
+                a_revision.unwrap_or_default(),
+                b_revision.unwrap_or_default(),
+            )
+        })
+}
+
+/// Compares RPM EVRs like `rpmdev-vercmp`, missing release is older than any release
+///
+/// # Examples
+///
+/// ```
+/// use core::cmp::Ordering;
+/// use fmri::version::convert::cmp_rpm;
+/// assert_eq!(cmp_rpm("1.10-1", "1.9-2"), Ordering::Greater);
+/// assert_eq!(cmp_rpm("1.2~rc1", "1.2"), Ordering::Less);
+/// assert_eq!(cmp_rpm("1.2", "1.2-1"), Ordering::Less);
+/// ```
+pub fn cmp_rpm(a: &str, b: &str) -> Ordering {
+    let (a_epoch, a_version, a_release) = split_evr(a);
+    let (b_epoch, b_version, b_release) = split_evr(b);
+    cmp_epoch(a_epoch, b_epoch)
+        .then_with(|| rpmvercmp(a_version, b_version))
+        .then_with(|| match (a_release, b_release) {
+            (Some(a), Some(b)) => rpmvercmp(a, b),
+            (a, b) => a.is_some().cmp(&b.is_some()),
+        })
+}
+
+fn segment_string(segments: &Segments) -> String {
+    segments
+        .get_segment_ref()
+        .map(Segment::as_string)
+        .unwrap_or_default()
+}
+
+/// Splits `[epoch:]version[-release]`, release is after the last '-'
+fn split_evr(evr: &str) -> (Option<&str>, &str, Option<&str>) {
+    let (epoch, rest) = match evr.split_once(':') {
+        Some((epoch, rest)) => (Some(epoch), rest),
+        None => (None, evr),
+    };
+    match rest.rsplit_once('-') {
+        Some((version, release)) => (epoch, version, Some(release)),
+        None => (epoch, rest, None),
+    }
+}
+
+fn check_epoch(epoch: Option<&str>) -> Result<(), String> {
+    match epoch {
+        None | Some("0") => Ok(()),
+        Some(epoch) => Err(format!("epoch {} isn't supported", epoch)),
+    }
+}
+
+fn cmp_epoch(a: Option<&str>, b: Option<&str>) -> Ordering {
+    let epoch = |epoch: Option<&str>| epoch.and_then(|e| e.parse::<u64>().ok()).unwrap_or(0);
+    epoch(a).cmp(&epoch(b))
+}
+
+fn build_version(
+    component: &str,
+    build: Option<&str>,
+    branch: Option<&str>,
+) -> Result<Version, String> {
+    let mut builder = Version::builder().component_version(Segment::try_from(component)?);
+    if let Some(build) = build {
+        builder = builder.build_version(Segment::try_from(build)?);
+    }
+    if let Some(branch) = branch {
+        builder = builder.branch_version(Segment::try_from(branch)?);
+    }
+    builder.build()
+}
+
+/// Compares upstream versions or revisions like `verrevcmp` of dpkg
+fn cmp_debian_part(a: &str, b: &str) -> Ordering {
+    // '~' sorts before anything, even the end, letters sort before other characters
+    fn order(c: Option<&u8>) -> i32 {
+        match c {
+            None => 0,
+            Some(c) if c.is_ascii_digit() => 0,
+            Some(c) if c.is_ascii_alphabetic() => *c as i32,
+            Some(b'~') => -1,
+            Some(c) => *c as i32 + 256,
+        }
+    }
+    let digit = |c: Option<&u8>| c.is_some_and(u8::is_ascii_digit);
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() || j < b.len() {
+        while a.get(i).is_some_and(|c| !c.is_ascii_digit())
+            || b.get(j).is_some_and(|c| !c.is_ascii_digit())
+        {
+            let (a_order, b_order) = (order(a.get(i)), order(b.get(j)));
+            if a_order != b_order {
+                return a_order.cmp(&b_order);
+            }
+            i += 1;
+            j += 1;
+        }
+
+        while a.get(i) == Some(&b'0') {
+            i += 1;
+        }
+        while b.get(j) == Some(&b'0') {
+            j += 1;
+        }
+        let mut first_difference = Ordering::Equal;
+        while digit(a.get(i)) && digit(b.get(j)) {
+            first_difference = first_difference.then(a[i].cmp(&b[j]));
+            i += 1;
+            j += 1;
+        }
+        if digit(a.get(i)) {
+            return Ordering::Greater;
+        }
+        if digit(b.get(j)) {
+            return Ordering::Less;
+        }
+        if first_difference != Ordering::Equal {
+            return first_difference;
+        }
+    }
+    Ordering::Equal
+}
+
+/// Compares versions or releases like `rpmvercmp` of RPM
+fn rpmvercmp(a: &str, b: &str) -> Ordering {
+    if a == b {
+        return Ordering::Equal;
+    }
+    let separator =
+        |c: Option<&u8>| c.is_some_and(|c| !c.is_ascii_alphanumeric() && *c != b'~' && *c != b'^');
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() || j < b.len() {
+        while separator(a.get(i)) {
+            i += 1;
+        }
+        while separator(b.get(j)) {
+            j += 1;
+        }
+        let (a_char, b_char) = (a.get(i).copied(), b.get(j).copied());
+
+        // '~' sorts before anything, even the end
+        if a_char == Some(b'~') || b_char == Some(b'~') {
+            if a_char != Some(b'~') {
+                return Ordering::Greater;
+            }
+            if b_char != Some(b'~') {
+                return Ordering::Less;
+            }
+            i += 1;
+            j += 1;
+            continue;
+        }
+
+        // '^' sorts after the end, but before anything else
+        if a_char == Some(b'^') || b_char == Some(b'^') {
+            if a_char.is_none() {
+                return Ordering::Less;
+            }
+            if b_char.is_none() {
+                return Ordering::Greater;
+            }
+            if a_char != Some(b'^') {
+                return Ordering::Greater;
+            }
+            if b_char != Some(b'^') {
+                return Ordering::Less;
+            }
+            i += 1;
+            j += 1;
+            continue;
+        }
+
+        let Some(a_char) = a_char else { break };
+        if b_char.is_none() {
+            break;
+        }
+
+        // segment is numeric or alphabetic by the first character of a
+        let numeric = a_char.is_ascii_digit();
+        let end = |string: &[u8], start: usize| {
+            start
+                + string[start..]
+                    .iter()
+                    .take_while(|c| match numeric {
+                        true => c.is_ascii_digit(),
+                        false => c.is_ascii_alphabetic(),
+                    })
+                    .count()
+        };
+        let (a_end, b_end) = (end(a, i), end(b, j));
+        let (mut a_segment, mut b_segment) = (&a[i..a_end], &b[j..b_end]);
+        (i, j) = (a_end, b_end);
+
+        // numeric segment is newer than alphabetic one
+        if b_segment.is_empty() {
+            return match numeric {
+                true => Ordering::Greater,
+                false => Ordering::Less,
+            };
+        }
+
+        if numeric {
+            while a_segment.first() == Some(&b'0') {
+                a_segment = &a_segment[1..];
+            }
+            while b_segment.first() == Some(&b'0') {
+                b_segment = &b_segment[1..];
+            }
+            let length = a_segment.len().cmp(&b_segment.len());
+            if length != Ordering::Equal {
+                return length;
+            }
+        }
+        let ordering = a_segment.cmp(b_segment);
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    match (i < a.len(), j < b.len()) {
+        (false, false) => Ordering::Equal,
+        (true, _) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+    }
+}
@@ -1,8 +1,20 @@
-use std::fmt::{Display, Formatter};
+use alloc::{
+    borrow::ToOwned,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt::{Display, Formatter};
 
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::{FMRI, version::Version};
+#[cfg(doc)]
+use crate::{version::Version, FMRI};
+
+/// The highest position [`Segment::set`] and friends fill up to, versions are much shorter
+pub const MAX_POSITION: usize = 63;
 
 /// [`Segment`] is a part of [`Version`] in [`FMRI`]
 ///
@@ -18,11 +30,26 @@ use crate::{FMRI, version::Version};
 /// ```plain
 /// .32.4
 /// 3.a.4
+/// 1.02
+/// 1.2.
 /// ```
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct Segment(Vec<i32>);
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Segment(Vec<u64>);
 
 impl Segment {
+    /// Returns [`Segment`] from numbers
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if there isn't any number
+    pub fn new(numbers: Vec<u64>) -> Result<Self, String> {
+        if numbers.is_empty() {
+            return Err("segment must contain at least one number".to_owned());
+        }
+        Ok(Self(numbers))
+    }
+
     /// Returns [`Segment`] as [String] ("1.2.3")
     pub fn as_string(&self) -> String {
         self.get_ref()
@@ -32,17 +59,105 @@ impl Segment {
             .join(".")
     }
 
-    pub fn get(self) -> Vec<i32> {
+    pub fn get(self) -> Vec<u64> {
         self.0
     }
 
-    pub fn get_ref(&self) -> &Vec<i32> {
+    pub fn get_ref(&self) -> &Vec<u64> {
         &self.0
     }
 
-    pub fn get_ref_mut(&mut self) -> &mut Vec<i32> {
+    pub fn get_ref_mut(&mut self) -> &mut Vec<u64> {
         &mut self.0
     }
+
+    /// Increments number at given position and sets all following numbers to zero
+    ///
+    /// Missing positions are filled with zeros.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmri::version::segment::Segment;
+    /// let mut segment = Segment::try_from("2023.0.0.5").unwrap();
+    /// segment.increment(3).unwrap();
+    /// assert_eq!(segment, Segment::try_from("2023.0.0.6").unwrap());
+    /// segment.increment(1).unwrap();
+    /// assert_eq!(segment, Segment::try_from("2023.1.0.0").unwrap());
+    /// segment.increment(5).unwrap();
+    /// assert_eq!(segment, Segment::try_from("2023.1.0.0.0.1").unwrap());
+    /// ```
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if the number would overflow or position is after
+    /// [`MAX_POSITION`], [`Segment`] isn't changed
+    pub fn increment(&mut self, position: usize) -> Result<(), String> {
+        check_position(position)?;
+        let number = self.0.get(position).copied().unwrap_or(0);
+        let incremented = number.checked_add(1).ok_or(format!(
+            "number {} at position {} overflows",
+            number, position
+        ))?;
+        self.set(position, incremented)?;
+        self.0[position + 1..]
+            .iter_mut()
+            .for_each(|number| *number = 0);
+        Ok(())
+    }
+
+    /// Sets number at given position to zero, missing positions are filled with zeros
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if position is after [`MAX_POSITION`]
+    pub fn zero(&mut self, position: usize) -> Result<(), String> {
+        self.set(position, 0)
+    }
+
+    /// Sets number at given position, missing positions are filled with zeros
+    ///
+    /// # Error
+    ///
+    /// Returns a string with error message if position is after [`MAX_POSITION`]
+    pub fn set(&mut self, position: usize, number: u64) -> Result<(), String> {
+        check_position(position)?;
+        if position >= self.0.len() {
+            self.0.resize(position + 1, 0);
+        }
+        self.0[position] = number;
+        Ok(())
+    }
+
+    /// Shortens [`Segment`] to given count of numbers, at least one number is always kept
+    pub fn truncate(&mut self, len: usize) {
+        self.0.truncate(len.max(1))
+    }
+
+    /// Checks if all numbers of [`Segment`] are at the beginning of other [`Segment`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fmri::version::segment::Segment;
+    /// let segment = Segment::try_from("2023.0").unwrap();
+    /// assert!(segment.is_prefix_of(&Segment::try_from("2023.0.0.5").unwrap()));
+    /// assert!(!segment.is_prefix_of(&Segment::try_from("2023.1").unwrap()));
+    /// assert!(!segment.is_prefix_of(&Segment::try_from("2023").unwrap()));
+    /// ```
+    pub fn is_prefix_of(&self, other: &Segment) -> bool {
+        other.0.starts_with(&self.0)
+    }
+}
+
+fn check_position(position: usize) -> Result<(), String> {
+    match position > MAX_POSITION {
+        true => Err(format!(
+            "position {} is after the last position {}",
+            position, MAX_POSITION
+        )),
+        false => Ok(()),
+    }
 }
 
 impl TryFrom<String> for Segment {
@@ -57,20 +172,44 @@ impl TryFrom<&str> for Segment {
     type Error = String;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        Ok(Self(
-            value
-                .split('.')
-                .map(|s| {
-                    s.parse::<i32>()
-                        .map_err(|_| format!("invalid character in {}", s))
-                })
-                .collect::<Result<Vec<i32>, String>>()?,
-        ))
+        parse_dot_sequence(value).map_err(|(index, reason)| {
+            format!(
+                "invalid dot sequence \"{}\": {} at index {}",
+                value, reason, index
+            )
+        })
     }
 }
 
+/// Parses pkg(5) dot sequence, returns index of invalid character and reason on error
+///
+/// Numbers must be non-empty, without leading zeros (except "0") and fit into [`u64`].
+pub(crate) fn parse_dot_sequence(value: &str) -> Result<Segment, (usize, String)> {
+    let mut numbers = vec![];
+    let mut start = 0;
+
+    for part in value.split('.') {
+        if part.is_empty() {
+            return Err((start, "empty number".to_owned()));
+        }
+        if let Some((index, c)) = part.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+            return Err((start + index, format!("invalid character '{}'", c)));
+        }
+        if part.len() > 1 && part.starts_with('0') {
+            return Err((start, "leading zero".to_owned()));
+        }
+        numbers.push(
+            part.parse::<u64>()
+                .map_err(|_| (start, "number too large".to_owned()))?,
+        );
+        start += part.len() + 1;
+    }
+
+    Ok(Segment(numbers))
+}
+
 impl Display for Segment {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.as_string())
     }
 }
@@ -1,8 +1,12 @@
-use std::fmt::{Debug, Display, Formatter};
+use alloc::{format, string::String};
+use core::fmt::{Display, Formatter};
 
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::{FMRI, version::segment::Segment, version::Version};
+use crate::version::segment::{parse_dot_sequence, Segment};
+#[cfg(doc)]
+use crate::{version::Version, FMRI};
 
 /// [`Segments`] is a part of [`Version`] in [`FMRI`]
 ///
@@ -14,7 +18,8 @@ use crate::{FMRI, version::segment::Segment, version::Version};
 /// ,5.11
 /// :20171212T185746Z
 /// ```
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Segments {
     /// after '@'
     ComponentVersion(Segment),
@@ -48,12 +53,13 @@ impl Segments {
         mut string: String,
         segment_starts_with: char,
     ) -> Result<Self, String> {
-        let mut end = match string.find(segment_starts_with) {
+        let start = match string.find(segment_starts_with) {
             None => return Ok(Self::None),
-            Some(position) => string.split_off(position + 1),
+            Some(position) => position + 1,
         };
+        let mut end = string.split_off(start);
 
-        for (index, c) in end.clone().chars().enumerate() {
+        for (index, c) in end.clone().char_indices() {
             match c {
                 ',' | '-' | ':' => {
                     let _ = end.split_off(index);
@@ -66,18 +72,48 @@ impl Segments {
             }
         }
 
+        let segment = |name: &str| {
+            parse_dot_sequence(&end).map_err(|(index, reason)| {
+                format!(
+                    "invalid {} in \"{}{}\": {} at index {}",
+                    name,
+                    string,
+                    end,
+                    reason,
+                    start + index
+                )
+            })
+        };
+
         Ok(match segment_starts_with {
-            '@' => Self::ComponentVersion(Segment::try_from(end.clone())?),
-            ',' => Self::BuildVersion(Segment::try_from(end.clone())?),
-            '-' => Self::BranchVersion(Segment::try_from(end.clone())?),
+            '@' => Self::ComponentVersion(segment("component version")?),
+            ',' => Self::BuildVersion(segment("build version")?),
+            '-' => Self::BranchVersion(segment("branch version")?),
             ':' => Self::Timestamp(end.clone()),
             _ => Self::None,
         })
     }
+
+    /// Returns [`Segment`] of component, build or branch version, [`None`] otherwise
+    pub fn get_segment_ref(&self) -> Option<&Segment> {
+        match self {
+            Self::ComponentVersion(segment)
+            | Self::BuildVersion(segment)
+            | Self::BranchVersion(segment) => Some(segment),
+            Self::Timestamp(_) | Self::None => None,
+        }
+    }
 }
 
 impl Display for Segments {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.to_owned())
+    /// Writes the segment without its leading separator ("2.1.1", "20171212T185746Z")
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::ComponentVersion(segment)
+            | Self::BuildVersion(segment)
+            | Self::BranchVersion(segment) => write!(f, "{}", segment),
+            Self::Timestamp(timestamp) => write!(f, "{}", timestamp),
+            Self::None => Ok(()),
+        }
     }
 }
@@ -0,0 +1,176 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// Runs `fmri` binary with arguments and stdin
+fn fmri(arguments: &[&str], stdin: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_fmri"))
+        .args(arguments)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+#[test]
+fn compare() {
+    for (a, b, symbol, code) in [
+        ("library@1.2", "library@1.2", "=", 0),
+        ("library@1.2", "library@1.10", "<", 1),
+        ("library@1.10", "library@1.2", ">", 2),
+        ("library", "library@1", "<", 1),
+        // timestamps are ignored
+        (
+            "library@1.2:20230101T000000Z",
+            "library@1.2:20230201T000000Z",
+            "=",
+            0,
+        ),
+    ] {
+        let output = fmri(&["compare", a, b], b"");
+        assert_eq!(output.status.code(), Some(code), "{} {}", a, b);
+        assert_eq!(stdout(&output), format!("{}\n", symbol));
+    }
+}
+
+#[test]
+fn usage_and_data_errors() {
+    let output = fmri(&["compare", "library@1.2"], b"");
+    assert_eq!(output.status.code(), Some(64));
+
+    let output = fmri(&["compare", "library@1.a", "library@1"], b"");
+    assert_eq!(output.status.code(), Some(65));
+    assert!(stderr(&output).starts_with("fmri: invalid fmri \"library@1.a\""));
+
+    let output = fmri(&["--strict", "parse", "fmri=library@1"], b"");
+    assert_eq!(output.status.code(), Some(65));
+}
+
+#[test]
+fn io_error() {
+    let output = fmri(&["sort"], b"library@1\n\xff\n");
+    assert_eq!(output.status.code(), Some(74));
+    assert!(stderr(&output).starts_with("fmri: can't read stdin"));
+}
+
+#[test]
+fn parse() {
+    let output = fmri(
+        &[
+            "parse",
+            "pkg://openindiana.org/shell/bash@5.2.15,5.11-2023.0.0.1",
+        ],
+        b"",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "fmri: pkg://openindiana.org/shell/bash@5.2.15,5.11-2023.0.0.1\n\
+         publisher: openindiana.org\n\
+         package_name: shell/bash\n\
+         component_version: 5.2.15\n\
+         build_version: 5.11\n\
+         branch_version: 2023.0.0.1\n\
+         timestamp: \n"
+    );
+
+    let output = fmri(
+        &["parse", "--json"],
+        b"shell/bash@5.2:20230101T000000Z\n\ntext/gnu-grep\n",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let lines: Vec<serde_json::Value> = stdout(&output)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(
+        lines,
+        [
+            serde_json::json!({
+                "fmri": "pkg:/shell/bash@5.2:20230101T000000Z",
+                "publisher": null,
+                "package_name": "shell/bash",
+                "component_version": "5.2",
+                "build_version": null,
+                "branch_version": null,
+                "timestamp": "20230101T000000Z",
+            }),
+            serde_json::json!({
+                "fmri": "pkg:/text/gnu-grep",
+                "publisher": null,
+                "package_name": "text/gnu-grep",
+                "component_version": null,
+                "build_version": null,
+                "branch_version": null,
+                "timestamp": null,
+            }),
+        ]
+    );
+}
+
+#[test]
+fn sort_and_latest() {
+    let stdin = b"shell/bash@5.2.21\nlibrary/zlib@1.3\n\nshell/bash@5.2.15\n";
+
+    let output = fmri(&["sort"], stdin);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "pkg:/library/zlib@1.3\npkg:/shell/bash@5.2.15\npkg:/shell/bash@5.2.21\n"
+    );
+
+    let output = fmri(&["latest"], stdin);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "pkg:/library/zlib@1.3\npkg:/shell/bash@5.2.21\n"
+    );
+
+    let output = fmri(&["sort"], b"shell/bash@5.2\n\nshell/bash@5.a\n");
+    assert_eq!(output.status.code(), Some(65));
+    assert!(stderr(&output).starts_with("fmri: line 3: invalid fmri \"shell/bash@5.a\""));
+}
+
+#[test]
+fn matches() {
+    let stdin = b"shell/bash@5.2\nshell/zsh@5.9\n";
+
+    let output = fmri(&["match", "*bash"], stdin);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "pkg:/shell/bash@5.2\n");
+
+    let output = fmri(&["match", "fish"], stdin);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn validate() {
+    let output = fmri(&["validate", "shell/bash@5.2", "shell/bash@5.a"], b"");
+    assert_eq!(output.status.code(), Some(65));
+    let stderr_lines = stderr(&output);
+    let stderr_lines: Vec<&str> = stderr_lines.lines().collect();
+    assert_eq!(stderr_lines.len(), 1);
+    assert!(stderr_lines[0].starts_with("argument 2: invalid fmri \"shell/bash@5.a\""));
+
+    let output = fmri(&["validate"], b"shell/bash@5.2\n\nshell/bash@5.a\n");
+    assert_eq!(output.status.code(), Some(65));
+    assert!(stderr(&output).starts_with("line 3: invalid fmri \"shell/bash@5.a\""));
+
+    let output = fmri(
+        &["validate"],
+        b"shell/bash@5.2\npkg://openindiana.org/shell/zsh\n",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stderr(&output), "");
+}